@@ -0,0 +1,26 @@
+#version 450
+layout(location = 0) in vec3 v_normal;
+layout(location = 1) in vec2 v_uv;
+layout(location = 2) in vec4 v_tint;
+layout(location = 0) out vec4 f_color;
+layout(set = 1, binding = 0) uniform sampler2D base_color;
+layout(set = 1, binding = 1) uniform Light
+{
+    vec4 direction;
+    vec4 color;
+} light;
+layout(constant_id = 0) const int ENCODE_SRGB = 0;
+vec3 linear_to_srgb(vec3 color)
+{
+    return mix(color * 12.92, 1.055 * pow(color, vec3(1.0 / 2.4)) - 0.055, step(vec3(0.0031308), color));
+}
+void main()
+{
+    float diffuse = max(dot(normalize(v_normal), -light.direction.xyz), 0.0);
+    vec4 color = texture(base_color, v_uv) * v_tint;
+    f_color = vec4(color.rgb * (0.2 + 0.8 * diffuse * light.color.rgb), color.a);
+    if (ENCODE_SRGB != 0)
+    {
+        f_color.rgb = linear_to_srgb(f_color.rgb);
+    }
+}
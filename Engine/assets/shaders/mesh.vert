@@ -0,0 +1,33 @@
+#version 450
+layout(location = 0) in vec3 position;
+layout(location = 1) in vec3 normal;
+layout(location = 2) in vec2 uv;
+layout(location = 0) out vec3 v_normal;
+layout(location = 1) out vec2 v_uv;
+layout(location = 2) out vec4 v_tint;
+layout(set = 0, binding = 0) uniform FrameUniforms
+{
+    float time;
+    float delta_time;
+    uint frame_number;
+} frame;
+layout(set = 0, binding = 1) uniform ViewUniforms
+{
+    mat4 view;
+    mat4 projection;
+    mat4 view_projection;
+    vec4 camera_position;
+} view;
+layout(push_constant) uniform ObjectUniforms
+{
+    mat4 model;
+    vec4 normal_matrix[3];
+    vec4 tint;
+} object;
+void main()
+{
+    v_normal = mat3(object.normal_matrix[0].xyz, object.normal_matrix[1].xyz, object.normal_matrix[2].xyz) * normal;
+    v_uv = uv;
+    v_tint = object.tint;
+    gl_Position = view.view_projection * object.model * vec4(position, 1.0);
+}
@@ -0,0 +1,18 @@
+#version 450
+layout(location = 0) in vec4 v_color;
+layout(location = 1) in vec2 v_uv;
+layout(location = 0) out vec4 f_color;
+layout(set = 0, binding = 0) uniform sampler2D tex;
+layout(constant_id = 0) const int ENCODE_SRGB = 0;
+vec3 linear_to_srgb(vec3 color)
+{
+    return mix(color * 12.92, 1.055 * pow(color, vec3(1.0 / 2.4)) - 0.055, step(vec3(0.0031308), color));
+}
+void main()
+{
+    f_color = v_color * texture(tex, v_uv);
+    if (ENCODE_SRGB != 0)
+    {
+        f_color.rgb = linear_to_srgb(f_color.rgb);
+    }
+}
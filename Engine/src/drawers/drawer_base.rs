@@ -1,9 +1,25 @@
 use vulkano::framebuffer::RenderPassAbstract;
 use vulkano::command_buffer::AutoCommandBufferBuilder;
 
+use camera::Camera;
+use engine_error::EngineError;
+use frames::frame_context::FrameContext;
+
+use std::any::Any;
 use std::sync::Arc;
 
-pub trait Drawer
+//every drawer records on its own job worker at the same time as the others
+//the builder is a secondary command buffer for the first subpass of render_pass, it gets executed in the drawer registry's order
+//shaders write linear colors, color::target_encodes_srgb tells whether the render pass encodes them or the shader has to
+pub trait Drawer : Send
 {
-    fn render(&mut self, dimensions : [u32; 2], command_buffer_builder : AutoCommandBufferBuilder, render_pass : Arc<RenderPassAbstract + Send + Sync>) -> AutoCommandBufferBuilder;
+    //uploads for the frame go through the frame context, its buffers are reused once the gpu has finished the frame
+    fn render(&mut self, camera : &Camera, frame : &FrameContext, command_buffer_builder : AutoCommandBufferBuilder, render_pass : Arc<RenderPassAbstract + Send + Sync>) -> Result<AutoCommandBufferBuilder, EngineError>;
+
+    //drops everything queued since the last frame, called instead of render while the drawer is disabled
+    fn clear(&mut self);
+
+    fn as_any(&self) -> &Any;
+
+    fn as_any_mut(&mut self) -> &mut Any;
 }
\ No newline at end of file
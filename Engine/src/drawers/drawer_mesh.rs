@@ -0,0 +1,264 @@
+use drawers::drawer_base::Drawer;
+
+use vulkano::buffer::BufferAccess;
+use vulkano::buffer::BufferUsage;
+use vulkano::buffer::CpuAccessibleBuffer;
+use vulkano::device::Device;
+use vulkano::command_buffer::AutoCommandBufferBuilder;
+use vulkano::command_buffer::DynamicState;
+use vulkano::descriptor::descriptor_set::PersistentDescriptorSet;
+use vulkano::framebuffer::RenderPassAbstract;
+
+use camera::Camera;
+use color;
+use engine_error::EngineError;
+use pipelines::pipeline_key::BlendMode;
+use pipelines::pipeline_key::DepthMode;
+use pipelines::pipeline_key::PipelineState;
+use pipelines::pipeline_key::VertexLayout;
+use pipelines::pipeline_manager::PipelineManager;
+use shaders::reloadable_pipeline::ReloadablePipeline;
+use shaders::shader_interface::ShaderInterface;
+use shaders::shader_library::ShaderLibrary;
+use shaders::shader_library::ShaderStage;
+use frames::frame_context::FrameContext;
+use math::Mat4;
+use math::Vec3;
+use textures::texture::Texture;
+use uniforms;
+use uniforms::BlockLayout;
+use uniforms::field_offset;
+use uniforms::FrameUniforms;
+use uniforms::ObjectUniforms;
+use uniforms::PlainBlock;
+use uniforms::ViewUniforms;
+
+use std::any::Any;
+use std::sync::Arc;
+use std::vec::Vec;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct MeshVertex { pub position: [f32; 3], pub normal: [f32; 3], pub uv: [f32; 2] }
+impl_vertex!(MeshVertex, position, normal, uv);
+
+//mesh geometry on the cpu, what loaders produce and create_mesh uploads
+#[derive(Debug, Clone, Default)]
+pub struct MeshData
+{
+    pub vertices : Vec<MeshVertex>,
+    pub indices : Vec<u32>
+}
+
+const VERTEX_SHADER_NAME : &str = "mesh.vert";
+const FRAGMENT_SHADER_NAME : &str = "mesh.frag";
+
+//the shared blocks are copies of the ones in uniforms, MeshDrawer::new checks they still match
+mod vs
+{
+    #[derive(VulkanoShader)]
+    #[ty = "vertex"]
+    #[path = "assets/shaders/mesh.vert"]
+    struct _Dummy;
+}
+
+//generated from blocks of floats, uints and matrices only
+unsafe impl PlainBlock for vs::ty::FrameUniforms
+{
+}
+
+unsafe impl PlainBlock for vs::ty::ViewUniforms
+{
+}
+
+unsafe impl PlainBlock for vs::ty::ObjectUniforms
+{
+}
+
+mod fs
+{
+    #[derive(VulkanoShader)]
+    #[ty = "fragment"]
+    #[path = "assets/shaders/mesh.frag"]
+    struct _Dummy;
+}
+
+//texture and light, right after the shared uniforms
+const MATERIAL_SET : usize = uniforms::SHARED_SET + 1;
+
+//lights meshes until a scene with a light says otherwise
+const DEFAULT_LIGHT_DIRECTION : [f32; 3] = [0.3, -1.0, 0.5];
+
+//indexed geometry uploaded once and drawn as many times as needed
+pub struct Mesh
+{
+    vertex_buffer : Arc<CpuAccessibleBuffer<[MeshVertex]>>,
+    index_buffer : Arc<CpuAccessibleBuffer<[u32]>>
+}
+
+struct MeshInstance
+{
+    mesh : Arc<Mesh>,
+    uniforms : ObjectUniforms,
+    texture : Arc<Texture>
+}
+
+pub struct MeshDrawer
+{
+    device : Arc<Device>,
+    //rebuilt for new render passes and whenever its shader files change
+    pipeline : ReloadablePipeline,
+    //sampled by meshes drawn without a texture
+    white_texture : Arc<Texture>,
+    light : fs::ty::Light,
+    mesh_list : Vec<MeshInstance>
+}
+
+impl MeshDrawer
+{
+    pub fn new(device : Arc<Device>, white_texture : Arc<Texture>, shaders : &mut ShaderLibrary, pipelines : Arc<PipelineManager>) -> Result<MeshDrawer, EngineError>
+    {
+        uniforms::check_block("FrameUniforms", BlockLayout::of(|block : &vs::ty::FrameUniforms|
+            vec![field_offset(block, &block.time), field_offset(block, &block.delta_time), field_offset(block, &block.frame_number)]), FrameUniforms::layout())?;
+        uniforms::check_block("ViewUniforms", BlockLayout::of(|block : &vs::ty::ViewUniforms|
+            vec![field_offset(block, &block.view), field_offset(block, &block.projection), field_offset(block, &block.view_projection), field_offset(block, &block.camera_position)]), ViewUniforms::layout())?;
+        uniforms::check_block("ObjectUniforms", BlockLayout::of(|block : &vs::ty::ObjectUniforms|
+            vec![field_offset(block, &block.model), field_offset(block, &block.normal_matrix), field_offset(block, &block.tint)]), ObjectUniforms::layout())?;
+
+        //the baked in shaders are used until the files load, and if they never do
+        let vertex_shader = vs::Shader::load(device.clone()).map_err(EngineError::Shader)?;
+        let fragment_shader = fs::Shader::load(device.clone()).map_err(EngineError::Shader)?;
+
+        let pipeline = ReloadablePipeline::new(vec!(
+            shaders.load(VERTEX_SHADER_NAME, ShaderStage::Vertex, ShaderInterface::of(&vs::MainInput, &vs::MainOutput, &vs::Layout(ShaderStage::Vertex.stages())), vertex_shader.module().clone()),
+            shaders.load(FRAGMENT_SHADER_NAME, ShaderStage::Fragment, ShaderInterface::of(&fs::MainInput, &fs::MainOutput, &fs::Layout(ShaderStage::Fragment.stages())), fragment_shader.module().clone())),
+            PipelineState{vertex_layout: VertexLayout::of::<MeshVertex>(), blend: BlendMode::Opaque, depth: DepthMode::TestAndWrite},
+            pipelines);
+
+        let mut mesh_drawer = MeshDrawer
+        {
+            device,
+            pipeline,
+            white_texture,
+            light : fs::ty::Light{direction: [0.0; 4], color: [0.0; 4]},
+            mesh_list : Vec::new()
+        };
+
+        mesh_drawer.set_directional_light(DEFAULT_LIGHT_DIRECTION.into(), [1.0, 1.0, 1.0]);
+        Ok(mesh_drawer)
+    }
+
+    //a single directional light shining along direction, lasts until it is set again
+    pub fn set_directional_light(&mut self, direction : Vec3, color : [f32; 3])
+    {
+        self.light = fs::ty::Light
+        {
+            direction : direction.normalize().extend(0.0).into(),
+            color : [color[0], color[1], color[2], 1.0]
+        };
+    }
+
+    pub fn create_mesh(&self, vertices : &[MeshVertex], indices : &[u32]) -> Result<Arc<Mesh>, EngineError>
+    {
+        let vertex_buffer = CpuAccessibleBuffer::from_iter(self.device.clone(), BufferUsage::vertex_buffer(), vertices.iter().cloned())?;
+        let index_buffer = CpuAccessibleBuffer::from_iter(self.device.clone(), BufferUsage::index_buffer(), indices.iter().cloned())?;
+
+        Ok(Arc::new(Mesh
+        {
+            vertex_buffer,
+            index_buffer
+        }))
+    }
+
+    //drawn from the renderer's camera, model places the mesh in the world
+    pub fn draw_mesh(&mut self, mesh : Arc<Mesh>, model : Mat4)
+    {
+        let white_texture = self.white_texture.clone();
+        self.draw_mesh_textured(mesh, model, white_texture);
+    }
+
+    //the texture is sampled with the mesh's uvs and lit like the untextured meshes
+    pub fn draw_mesh_textured(&mut self, mesh : Arc<Mesh>, model : Mat4, texture : Arc<Texture>)
+    {
+        self.push_instance(mesh, model, texture, [1.0; 4]);
+    }
+
+    //the tint is an sRGB color the mesh is multiplied with
+    pub fn draw_mesh_tinted(&mut self, mesh : Arc<Mesh>, model : Mat4, tint : [f32; 4])
+    {
+        let white_texture = self.white_texture.clone();
+        self.draw_mesh_textured_tinted(mesh, model, white_texture, tint);
+    }
+
+    //what scene nodes are drawn with, the texture and the sRGB tint are multiplied together
+    pub fn draw_mesh_textured_tinted(&mut self, mesh : Arc<Mesh>, model : Mat4, texture : Arc<Texture>, tint : [f32; 4])
+    {
+        self.push_instance(mesh, model, texture, color::srgb_color_to_linear(tint));
+    }
+
+    fn push_instance(&mut self, mesh : Arc<Mesh>, model : Mat4, texture : Arc<Texture>, linear_tint : [f32; 4])
+    {
+        self.mesh_list.push(MeshInstance
+        {
+            mesh,
+            uniforms : ObjectUniforms::new(model, linear_tint),
+            texture
+        });
+    }
+}
+
+impl Drawer for MeshDrawer
+{
+    fn render(&mut self, camera : &Camera, frame : &FrameContext, command_buffer_builder : AutoCommandBufferBuilder, render_pass : Arc<RenderPassAbstract + Send + Sync>) -> Result<AutoCommandBufferBuilder, EngineError>
+    {
+        if self.mesh_list.is_empty()
+        {
+            return Ok(command_buffer_builder);
+        }
+
+        let device = self.device.clone();
+        let pipeline = self.pipeline.get(render_pass, |key| build_drawer_pipeline!(device, key, MeshVertex, vs, fs))?;
+
+        let dynamic_state = DynamicState
+        {
+            line_width: None,
+            viewports: Some(vec![camera.viewport()]),
+            scissors: None,
+        };
+
+        //camera matrices come from the frame's shared uniforms
+        let shared_set = frame.shared_uniforms()?.descriptor_set(pipeline.clone())?;
+
+        let light_buffer = Arc::new(frame.upload_uniform(self.light)?);
+
+        let mut command_buffer_builder = command_buffer_builder;
+
+        for instance in self.mesh_list.drain(..)
+        {
+            let descriptor_set = Arc::new(PersistentDescriptorSet::start(pipeline.clone(), MATERIAL_SET)
+                .add_sampled_image(instance.texture.image(), instance.texture.sampler()).map_err(EngineError::command_buffer)?
+                .add_buffer(light_buffer.clone()).map_err(EngineError::command_buffer)?
+                .build().map_err(EngineError::command_buffer)?);
+
+            command_buffer_builder = command_buffer_builder.draw_indexed(pipeline.clone(), dynamic_state.clone(),
+                vec![instance.mesh.vertex_buffer.clone() as Arc<BufferAccess + Send + Sync>],
+                instance.mesh.index_buffer.clone(), (shared_set.clone(), descriptor_set), instance.uniforms).map_err(EngineError::command_buffer)?;
+        }
+
+        Ok(command_buffer_builder)
+    }
+
+    fn clear(&mut self)
+    {
+        self.mesh_list.clear();
+    }
+
+    fn as_any(&self) -> &Any
+    {
+        self
+    }
+
+    fn as_any_mut(&mut self) -> &mut Any
+    {
+        self
+    }
+}
@@ -0,0 +1,368 @@
+use drawers::drawer_base::Drawer;
+
+use std::boxed::Box;
+use std::string::String;
+use std::vec::Vec;
+
+struct DrawerEntry
+{
+    name : String,
+    order : i32,
+    enabled : bool,
+    //built in drawers can be disabled but not removed, the renderer's draw functions need them
+    built_in : bool,
+    drawer : Box<Drawer>
+}
+
+//every drawer the renderer knows about, lower orders are drawn first
+//drawers with the same order are drawn in the order they were added
+pub struct DrawerRegistry
+{
+    entries : Vec<DrawerEntry>
+}
+
+impl DrawerRegistry
+{
+    pub fn new() -> DrawerRegistry
+    {
+        DrawerRegistry
+        {
+            entries : Vec::new()
+        }
+    }
+
+    //returns false and drops the drawer if the name is already taken
+    pub fn add<D : Drawer + 'static>(&mut self, name : &str, order : i32, drawer : D) -> bool
+    {
+        self.add_entry(name, order, false, Box::new(drawer))
+    }
+
+    //like add, but remove refuses to take it out again
+    pub fn add_built_in<D : Drawer + 'static>(&mut self, name : &str, order : i32, drawer : D) -> bool
+    {
+        self.add_entry(name, order, true, Box::new(drawer))
+    }
+
+    //None if there is no drawer with that name or it is a built in one
+    pub fn remove(&mut self, name : &str) -> Option<Box<Drawer>>
+    {
+        match self.position(name)
+        {
+            Some(index) if !self.entries[index].built_in => Some(self.entries.remove(index).drawer),
+            _ => None
+        }
+    }
+
+    pub fn is_built_in(&self, name : &str) -> bool
+    {
+        self.position(name).map_or(false, |index| self.entries[index].built_in)
+    }
+
+    pub fn contains(&self, name : &str) -> bool
+    {
+        self.position(name).is_some()
+    }
+
+    pub fn names<'a>(&'a self) -> Box<Iterator<Item = &'a str> + 'a>
+    {
+        Box::new(self.entries.iter().map(|entry| &entry.name[..]))
+    }
+
+    //returns false if there is no drawer with that name
+    pub fn set_enabled(&mut self, name : &str, enabled : bool) -> bool
+    {
+        match self.position(name)
+        {
+            Some(index) =>
+            {
+                self.entries[index].enabled = enabled;
+                true
+            },
+            None => false
+        }
+    }
+
+    pub fn is_enabled(&self, name : &str) -> bool
+    {
+        self.position(name).map_or(false, |index| self.entries[index].enabled)
+    }
+
+    //the drawer goes after the ones already at the new order
+    pub fn set_order(&mut self, name : &str, order : i32) -> bool
+    {
+        match self.position(name)
+        {
+            Some(index) =>
+            {
+                let mut entry = self.entries.remove(index);
+                entry.order = order;
+                self.insert(entry);
+                true
+            },
+            None => false
+        }
+    }
+
+    pub fn order(&self, name : &str) -> Option<i32>
+    {
+        self.position(name).map(|index| self.entries[index].order)
+    }
+
+    pub fn by_name(&self, name : &str) -> Option<&Drawer>
+    {
+        match self.position(name)
+        {
+            Some(index) => Some(&*self.entries[index].drawer),
+            None => None
+        }
+    }
+
+    pub fn by_name_mut(&mut self, name : &str) -> Option<&mut Drawer>
+    {
+        match self.position(name)
+        {
+            Some(index) => Some(&mut *self.entries[index].drawer),
+            None => None
+        }
+    }
+
+    //None if there is no drawer with that name or it is not a D
+    pub fn by_name_as_mut<D : Drawer + 'static>(&mut self, name : &str) -> Option<&mut D>
+    {
+        self.by_name_mut(name).and_then(|drawer| drawer.as_any_mut().downcast_mut::<D>())
+    }
+
+    //the first drawer of type D in draw order
+    pub fn get<D : Drawer + 'static>(&self) -> Option<&D>
+    {
+        self.entries.iter().filter_map(|entry| entry.drawer.as_any().downcast_ref::<D>()).next()
+    }
+
+    pub fn get_mut<D : Drawer + 'static>(&mut self) -> Option<&mut D>
+    {
+        self.entries.iter_mut().filter_map(|entry| entry.drawer.as_any_mut().downcast_mut::<D>()).next()
+    }
+
+    //what disabled drawers were given this frame is thrown away, the enabled ones are returned in draw order
+    pub fn prepare_frame(&mut self) -> Vec<&mut Drawer>
+    {
+        let mut enabled_drawers = Vec::new();
+
+        for entry in self.entries.iter_mut()
+        {
+            if entry.enabled
+            {
+                enabled_drawers.push(&mut *entry.drawer as &mut Drawer);
+            }
+            else
+            {
+                entry.drawer.clear();
+            }
+        }
+
+        enabled_drawers
+    }
+
+    fn position(&self, name : &str) -> Option<usize>
+    {
+        self.entries.iter().position(|entry| entry.name == name)
+    }
+
+    fn add_entry(&mut self, name : &str, order : i32, built_in : bool, drawer : Box<Drawer>) -> bool
+    {
+        if self.contains(name)
+        {
+            return false;
+        }
+
+        self.insert(DrawerEntry
+        {
+            name : name.to_string(),
+            order,
+            enabled : true,
+            built_in,
+            drawer
+        });
+
+        true
+    }
+
+    fn insert(&mut self, entry : DrawerEntry)
+    {
+        let index = self.entries.iter().position(|other| other.order > entry.order).unwrap_or(self.entries.len());
+        self.entries.insert(index, entry);
+    }
+}
+
+#[cfg(test)]
+mod tests
+{
+    use vulkano::command_buffer::AutoCommandBufferBuilder;
+    use vulkano::framebuffer::RenderPassAbstract;
+
+    use camera::Camera;
+    use drawers::drawer_base::Drawer;
+    use drawers::drawer_registry::DrawerRegistry;
+    use engine_error::EngineError;
+    use frames::frame_context::FrameContext;
+
+    use std::any::Any;
+    use std::sync::Arc;
+
+    struct CountingDrawer
+    {
+        id : u32,
+        cleared : u32
+    }
+
+    impl CountingDrawer
+    {
+        fn new(id : u32) -> CountingDrawer
+        {
+            CountingDrawer{id, cleared: 0}
+        }
+    }
+
+    impl Drawer for CountingDrawer
+    {
+        fn render(&mut self, _camera : &Camera, _frame : &FrameContext, command_buffer_builder : AutoCommandBufferBuilder, _render_pass : Arc<RenderPassAbstract + Send + Sync>) -> Result<AutoCommandBufferBuilder, EngineError>
+        {
+            Ok(command_buffer_builder)
+        }
+
+        fn clear(&mut self)
+        {
+            self.cleared += 1;
+        }
+
+        fn as_any(&self) -> &Any
+        {
+            self
+        }
+
+        fn as_any_mut(&mut self) -> &mut Any
+        {
+            self
+        }
+    }
+
+    struct OtherDrawer;
+
+    impl Drawer for OtherDrawer
+    {
+        fn render(&mut self, _camera : &Camera, _frame : &FrameContext, command_buffer_builder : AutoCommandBufferBuilder, _render_pass : Arc<RenderPassAbstract + Send + Sync>) -> Result<AutoCommandBufferBuilder, EngineError>
+        {
+            Ok(command_buffer_builder)
+        }
+
+        fn clear(&mut self)
+        {
+        }
+
+        fn as_any(&self) -> &Any
+        {
+            self
+        }
+
+        fn as_any_mut(&mut self) -> &mut Any
+        {
+            self
+        }
+    }
+
+    fn ids(registry : &mut DrawerRegistry) -> Vec<u32>
+    {
+        registry.prepare_frame().into_iter().map(|drawer| drawer.as_any().downcast_ref::<CountingDrawer>().unwrap().id).collect()
+    }
+
+    #[test]
+    fn drawers_are_ordered_by_order_then_by_when_they_were_added()
+    {
+        let mut registry = DrawerRegistry::new();
+        assert!(registry.add("late", 10, CountingDrawer::new(1)));
+        assert!(registry.add("early", -5, CountingDrawer::new(2)));
+        assert!(registry.add("also late", 10, CountingDrawer::new(3)));
+        assert!(registry.add("middle", 0, CountingDrawer::new(4)));
+
+        assert_eq!(registry.names().collect::<Vec<_>>(), vec!["early", "middle", "late", "also late"]);
+        assert_eq!(ids(&mut registry), vec![2, 4, 1, 3]);
+
+        //moving a drawer puts it after the ones already at that order
+        assert!(registry.set_order("early", 10));
+        assert_eq!(registry.order("early"), Some(10));
+        assert_eq!(ids(&mut registry), vec![4, 1, 3, 2]);
+        assert!(!registry.set_order("missing", 0));
+    }
+
+    #[test]
+    fn names_can_only_be_taken_once()
+    {
+        let mut registry = DrawerRegistry::new();
+        assert!(registry.add("drawer", 0, CountingDrawer::new(1)));
+        assert!(!registry.add("drawer", 5, CountingDrawer::new(2)));
+        assert!(!registry.add_built_in("drawer", 5, CountingDrawer::new(3)));
+
+        assert_eq!(ids(&mut registry), vec![1]);
+    }
+
+    #[test]
+    fn disabled_drawers_are_cleared_instead_of_drawn()
+    {
+        let mut registry = DrawerRegistry::new();
+        registry.add("first", 0, CountingDrawer::new(1));
+        registry.add("second", 1, CountingDrawer::new(2));
+
+        assert!(registry.set_enabled("first", false));
+        assert!(!registry.is_enabled("first"));
+        assert_eq!(ids(&mut registry), vec![2]);
+        assert_eq!(registry.get::<CountingDrawer>().map(|drawer| drawer.cleared), Some(1));
+
+        assert!(registry.set_enabled("first", true));
+        assert_eq!(ids(&mut registry), vec![1, 2]);
+        assert_eq!(registry.get::<CountingDrawer>().map(|drawer| drawer.cleared), Some(1));
+
+        assert!(!registry.set_enabled("missing", true));
+        assert!(!registry.is_enabled("missing"));
+    }
+
+    #[test]
+    fn drawers_are_found_by_name_and_by_type()
+    {
+        let mut registry = DrawerRegistry::new();
+        registry.add("counting", 5, CountingDrawer::new(1));
+        registry.add("other", 0, OtherDrawer);
+        registry.add("another counting", 0, CountingDrawer::new(2));
+
+        assert!(registry.contains("other"));
+        assert!(registry.by_name("other").unwrap().as_any().is::<OtherDrawer>());
+        assert!(registry.by_name("missing").is_none());
+
+        assert_eq!(registry.by_name_as_mut::<CountingDrawer>("counting").map(|drawer| drawer.id), Some(1));
+        assert!(registry.by_name_as_mut::<CountingDrawer>("other").is_none());
+
+        //by type finds the first in draw order
+        assert_eq!(registry.get::<CountingDrawer>().map(|drawer| drawer.id), Some(2));
+        registry.get_mut::<CountingDrawer>().unwrap().id = 3;
+        assert_eq!(registry.by_name_as_mut::<CountingDrawer>("another counting").map(|drawer| drawer.id), Some(3));
+    }
+
+    #[test]
+    fn built_in_drawers_can_not_be_removed()
+    {
+        let mut registry = DrawerRegistry::new();
+        registry.add_built_in("built in", 0, CountingDrawer::new(1));
+        registry.add("added", 1, CountingDrawer::new(2));
+
+        assert!(registry.is_built_in("built in"));
+        assert!(!registry.is_built_in("added"));
+
+        assert!(registry.remove("built in").is_none());
+        assert!(registry.remove("added").is_some());
+        assert!(registry.remove("added").is_none());
+
+        //but they can be disabled
+        assert!(registry.set_enabled("built in", false));
+        assert!(ids(&mut registry).is_empty());
+        assert_eq!(registry.names().collect::<Vec<_>>(), vec!["built in"]);
+    }
+}
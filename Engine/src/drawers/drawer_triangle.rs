@@ -1,34 +1,46 @@
 use drawers::drawer_base::Drawer;
 
-use vulkano::buffer::BufferUsage;
-use vulkano::buffer::CpuAccessibleBuffer;
+use vulkano::buffer::BufferAccess;
 use vulkano::device::Device;
 use vulkano::command_buffer::AutoCommandBufferBuilder;
 use vulkano::command_buffer::DynamicState;
+use vulkano::descriptor::descriptor_set::PersistentDescriptorSet;
 use vulkano::framebuffer::RenderPassAbstract;
-use vulkano::framebuffer::Subpass;
-use vulkano::pipeline::GraphicsPipeline;
-use vulkano::pipeline::viewport::Viewport;
 
+use camera::Camera;
+use color;
+use engine_error::EngineError;
+use pipelines::pipeline_key::BlendMode;
+use pipelines::pipeline_key::DepthMode;
+use pipelines::pipeline_key::PipelineState;
+use pipelines::pipeline_key::VertexLayout;
+use pipelines::pipeline_manager::PipelineManager;
+use shaders::reloadable_pipeline::ReloadablePipeline;
+use shaders::shader_interface::ShaderInterface;
+use shaders::shader_library::ShaderLibrary;
+use shaders::shader_library::ShaderStage;
+use frames::frame_context::FrameContext;
+use textures::texture::Texture;
+
+use std::any::Any;
 use std::sync::Arc;
 use std::vec::Vec;
 
-#[derive(Debug, Clone)]
-struct Vertex { position: [f32; 2] }
-impl_vertex!(Vertex, position);
+//used by draw_triangle, the color every triangle used to have
+pub const DEFAULT_TRIANGLE_COLOR : [f32; 4] = [0.0, 0.0, 1.0, 1.0];
+
+#[derive(Debug, Clone, Copy)]
+struct Vertex { position: [f32; 2], color: [f32; 4], uv: [f32; 2] }
+impl_vertex!(Vertex, position, color, uv);
+
+const VERTEX_SHADER_NAME : &str = "triangle.vert";
+const FRAGMENT_SHADER_NAME : &str = "triangle.frag";
 
 mod vs
 {
     #[derive(VulkanoShader)]
     #[ty = "vertex"]
-    #[src = "
-    #version 450
-        layout(location = 0) in vec2 position;
-        void main() 
-        {
-            gl_Position = vec4(position, 0.0, 1.0);
-        }
-    "]
+    #[path = "assets/shaders/triangle.vert"]
     struct _Dummy;
 }
 
@@ -36,88 +48,158 @@ mod fs
 {
     #[derive(VulkanoShader)]
     #[ty = "fragment"]
-    #[src = "
-    #version 450
-        layout(location = 0) out vec4 f_color;
-        void main() 
-        {
-            f_color = vec4(0.0, 0.0, 1.0, 1.0);
-        }
-    "]
+    #[path = "assets/shaders/triangle.frag"]
     struct _Dummy;
 }
 
+//consecutive triangles sharing a texture, drawn with a single draw call
+struct TriangleBatch
+{
+    texture : Arc<Texture>,
+    triangles : Vec<[Vertex; 3]>
+}
+
 pub struct TriangleDrawer
 {
     device : Arc<Device>,
-    vertex_shader : vs::Shader,
-    fragment_shader : fs::Shader,
-    triangle_list : Vec<[[f32; 2]; 3]>
+    //rebuilt for new render passes and whenever its shader files change
+    pipeline : ReloadablePipeline,
+    //sampled by triangles without a texture of their own
+    white_texture : Arc<Texture>,
+    batches : Vec<TriangleBatch>
 }
 
 impl TriangleDrawer
 {
-    pub fn new(device : Arc<Device>) -> TriangleDrawer
+    pub fn new(device : Arc<Device>, white_texture : Arc<Texture>, shaders : &mut ShaderLibrary, pipelines : Arc<PipelineManager>) -> Result<TriangleDrawer, EngineError>
     {
-        let vertex_shader = vs::Shader::load(device.clone()).expect("Could not create vertex shader module for TriangleDrawer!");
-        let fragment_shader = fs::Shader::load(device.clone()).expect("Could not create fragment shader module for TriangleDrawer!");
+        //the baked in shaders are used until the files load, and if they never do
+        let vertex_shader = vs::Shader::load(device.clone()).map_err(EngineError::Shader)?;
+        let fragment_shader = fs::Shader::load(device.clone()).map_err(EngineError::Shader)?;
+
+        let pipeline = ReloadablePipeline::new(vec!(
+            shaders.load(VERTEX_SHADER_NAME, ShaderStage::Vertex, ShaderInterface::of(&vs::MainInput, &vs::MainOutput, &vs::Layout(ShaderStage::Vertex.stages())), vertex_shader.module().clone()),
+            shaders.load(FRAGMENT_SHADER_NAME, ShaderStage::Fragment, ShaderInterface::of(&fs::MainInput, &fs::MainOutput, &fs::Layout(ShaderStage::Fragment.stages())), fragment_shader.module().clone())),
+            PipelineState{vertex_layout: VertexLayout::of::<Vertex>(), blend: BlendMode::Opaque, depth: DepthMode::Disabled},
+            pipelines);
 
-        TriangleDrawer
+        Ok(TriangleDrawer
         {
             device,
-            vertex_shader,
-            fragment_shader,
-            triangle_list : Vec::new()
-        }
+            pipeline,
+            white_texture,
+            batches : Vec::new()
+        })
     }
 
     pub fn draw_triangle(&mut self, points : [[f32; 2]; 3])
     {
-        self.triangle_list.push(points);
+        self.draw_triangle_colored(points, DEFAULT_TRIANGLE_COLOR);
+    }
+
+    pub fn draw_triangle_colored(&mut self, points : [[f32; 2]; 3], color : [f32; 4])
+    {
+        self.draw_triangle_gradient(points, [color, color, color]);
+    }
+
+    //colors are per point and get interpolated over the triangle
+    //they are sRGB like the ones from a color picker, the interpolation happens in linear space
+    pub fn draw_triangle_gradient(&mut self, points : [[f32; 2]; 3], colors : [[f32; 4]; 3])
+    {
+        let white_texture = self.white_texture.clone();
+        let colors = [color::srgb_color_to_linear(colors[0]), color::srgb_color_to_linear(colors[1]), color::srgb_color_to_linear(colors[2])];
+        self.push_triangle(white_texture, points, [[0.0, 0.0]; 3], colors);
+    }
+
+    //uvs are per point, (0, 0) is the top left of the texture
+    pub fn draw_triangle_textured(&mut self, points : [[f32; 2]; 3], uvs : [[f32; 2]; 3], texture : Arc<Texture>)
+    {
+        self.push_triangle(texture, points, uvs, [[1.0; 4]; 3]);
+    }
+
+    //the whole texture stretched over the rectangle from min to max
+    pub fn draw_sprite(&mut self, min : [f32; 2], max : [f32; 2], texture : Arc<Texture>)
+    {
+        self.draw_triangle_textured([min, [max[0], min[1]], max], [[0.0, 0.0], [1.0, 0.0], [1.0, 1.0]], texture.clone());
+        self.draw_triangle_textured([max, [min[0], max[1]], min], [[1.0, 1.0], [0.0, 1.0], [0.0, 0.0]], texture);
+    }
+
+    fn push_triangle(&mut self, texture : Arc<Texture>, points : [[f32; 2]; 3], uvs : [[f32; 2]; 3], colors : [[f32; 4]; 3])
+    {
+        let triangle = [
+            Vertex {position: points[0], color: colors[0], uv: uvs[0]},
+            Vertex {position: points[1], color: colors[1], uv: uvs[1]},
+            Vertex {position: points[2], color: colors[2], uv: uvs[2]}
+        ];
+
+        //draw order is kept, so only the last batch can be extended
+        if let Some(batch) = self.batches.last_mut()
+        {
+            if Arc::ptr_eq(&batch.texture, &texture)
+            {
+                batch.triangles.push(triangle);
+                return;
+            }
+        }
+
+        self.batches.push(TriangleBatch
+        {
+            texture,
+            triangles : vec![triangle]
+        });
     }
 }
 
 impl Drawer for TriangleDrawer
 {
-    fn render(&mut self, dimensions : [u32; 2], command_buffer_builder : AutoCommandBufferBuilder, render_pass : Arc<RenderPassAbstract + Send + Sync>) -> AutoCommandBufferBuilder
+    fn render(&mut self, camera : &Camera, frame : &FrameContext, command_buffer_builder : AutoCommandBufferBuilder, render_pass : Arc<RenderPassAbstract + Send + Sync>) -> Result<AutoCommandBufferBuilder, EngineError>
     {
+        if self.batches.is_empty()
+        {
+            return Ok(command_buffer_builder);
+        }
+
+        let device = self.device.clone();
+        let pipeline = self.pipeline.get(render_pass, |key| build_drawer_pipeline!(device, key, Vertex, vs, fs))?;
+
+        let dynamic_state = DynamicState
+        {
+            line_width: None,
+            viewports: Some(vec![camera.viewport()]),
+            scissors: None,
+        };
+
         let mut command_buffer_builder = command_buffer_builder;
-        for triangle in self.triangle_list.clone()
+
+        for batch in self.batches.drain(..)
         {
-            let pipeline = Arc::new(
-                GraphicsPipeline::start()
-                .vertex_input_single_buffer()
-                .vertex_shader(self.vertex_shader.main_entry_point(), ())
-                .triangle_list()
-                .viewports_dynamic_scissors_irrelevant(1)
-                .fragment_shader(self.fragment_shader.main_entry_point(), ())
-                .render_pass(Subpass::from(render_pass.clone(), 0).unwrap())
-                .build(self.device.clone())
-                .unwrap());
-
-            let vertex_buffer = 
-            {
-                CpuAccessibleBuffer::from_iter(self.device.clone(), BufferUsage::all(), [
-                    Vertex {position: triangle[0]},
-                    Vertex {position: triangle[1]},
-                    Vertex {position: triangle[2]}
-                ].iter().cloned()).expect("Could not create vertex buffer!")
-            };
+            let vertices = batch.triangles.iter().flat_map(|triangle| triangle.iter().cloned());
             
-            command_buffer_builder = command_buffer_builder.draw(pipeline.clone(),             
-                DynamicState
-                {
-                    line_width: None,
-                    viewports: Some(vec![Viewport {
-                        origin: [0.0, 0.0],
-                        dimensions: [dimensions[0] as f32, dimensions[1] as f32],
-                        depth_range: 0.0 .. 1.0,
-                    }]),
-                    scissors: None,
-                },             
-                vertex_buffer.clone(), (), ()).unwrap();
+            let vertex_buffer = frame.upload_vertices(vertices.collect::<Vec<_>>())?;
+
+            let descriptor_set = Arc::new(PersistentDescriptorSet::start(pipeline.clone(), 0)
+                .add_sampled_image(batch.texture.image(), batch.texture.sampler()).map_err(EngineError::command_buffer)?
+                .build().map_err(EngineError::command_buffer)?);
+
+            command_buffer_builder = command_buffer_builder.draw(pipeline.clone(), dynamic_state.clone(),
+                vec![Arc::new(vertex_buffer) as Arc<BufferAccess + Send + Sync>], descriptor_set, ()).map_err(EngineError::command_buffer)?;
         }
-        self.triangle_list.clear();
-        command_buffer_builder
+
+        Ok(command_buffer_builder)
+    }
+
+    fn clear(&mut self)
+    {
+        self.batches.clear();
+    }
+
+    fn as_any(&self) -> &Any
+    {
+        self
+    }
+
+    fn as_any_mut(&mut self) -> &mut Any
+    {
+        self
     }
 }
\ No newline at end of file
@@ -1,3 +1,5 @@
 //outside interface
 pub mod drawer_base;
+pub mod drawer_registry;
 pub mod drawer_triangle;
+pub mod drawer_mesh;
@@ -6,31 +6,174 @@ extern crate vulkano;
 #[macro_use]
 extern crate vulkano_shader_derive;
 
+extern crate image;
+extern crate gltf;
+extern crate glsl_to_spirv;
+
 mod vulkano_win_frankenstein;
 mod vulkano_instance;
+mod engine_error;
+mod device_selection;
+mod present_mode;
+mod surface_format;
+mod color;
+mod frame_capture;
+mod math;
+mod camera;
+mod textures;
+mod scene;
+mod assets;
 mod benchmarks;
+mod golden;
+//before drawers, they build their pipelines with its build_drawer_pipeline macro
+#[macro_use]
+mod shaders;
 mod drawers;
+mod jobs;
+mod frames;
+mod uniforms;
+mod pipelines;
+mod ecs;
+mod test_game;
 
 use benchmarks::render_benchmarks;
+use vulkano_instance::PipelineImplementer;
+
+fn create_benchmarker() -> benchmarks::benchmarker::Benchmarker
+{
+    let bench_length = std::time::Duration::new(3, 0);
+
+    benchmarks::benchmarker::Benchmarker::new(vec!(
+        Box::new(render_benchmarks::TriangleBenchmark::new(bench_length, 10, 10)),
+        Box::new(render_benchmarks::TriangleBenchmark::new(bench_length, 1, 1)),
+        Box::new(render_benchmarks::TriangleBenchmark::new(bench_length, 3, 3)),
+        Box::new(render_benchmarks::MeshBenchmark::new(bench_length, concat!(env!("CARGO_MANIFEST_DIR"), "/assets/models/cube.obj"), 10, 10)),
+        ))
+}
+
+//--vsync, --relaxed, --mailbox or --uncapped
+fn present_modes_from_args() -> Option<present_mode::PresentModeConfig>
+{
+    std::env::args().skip(1)
+        .filter(|argument| argument.starts_with("--"))
+        .filter_map(|argument| present_mode::PresentModeConfig::from_name(&argument[2..]))
+        .next()
+}
+
+//the renderer can not go on, say why and leave instead of panicking
+fn exit_with_error(err : engine_error::EngineError) -> !
+{
+    println!("Engine error: {}", err);
+    std::process::exit(1);
+}
+
+//runs every benchmark once without opening a window, then exits
+fn run_headless()
+{
+    let jobs = std::sync::Arc::new(jobs::job_system::JobSystem::with_default_workers());
+
+    let mut instance = vulkano_instance::VulkanoInstance::new_headless([1280, 720], jobs).unwrap_or_else(|err| exit_with_error(err));
+
+    let mut benchmarker = create_benchmarker();
+
+    while benchmarker.rounds_completed() == 0
+    {
+        if let Err(err) = benchmarker.tick_tests(&mut instance)
+        {
+            exit_with_error(err);
+        }
+    }
+}
+
+//renders every golden test offscreen and compares it with the reference images, --bless overwrites them
+fn run_golden_tests(bless : bool) -> bool
+{
+    let jobs = std::sync::Arc::new(jobs::job_system::JobSystem::with_default_workers());
+
+    let mut instance = vulkano_instance::VulkanoInstance::new_headless([256, 256], jobs).unwrap_or_else(|err| exit_with_error(err));
+
+    let golden_directory = std::path::Path::new(env!("CARGO_MANIFEST_DIR")).join("golden");
+
+    let mut runner = golden::golden_runner::GoldenRunner::new(vec!(
+        Box::new(golden::golden_tests::TriangleGridGoldenTest::new(1, 1)),
+        Box::new(golden::golden_tests::TriangleGridGoldenTest::new(3, 3)),
+        Box::new(golden::golden_tests::TriangleGridGoldenTest::new(10, 10)),
+        Box::new(golden::golden_tests::ColoredTriangleGoldenTest),
+        Box::new(golden::golden_tests::OverlappingMeshGoldenTest::new()),
+        Box::new(golden::golden_tests::GltfSceneGoldenTest::new()),
+        Box::new(golden::golden_tests::SpriteGoldenTest::new()),
+        ),
+        golden_directory.join("reference"),
+        golden_directory.join("output"),
+        bless);
+
+    runner.run(&mut instance)
+}
+
+fn save_screenshot(frame : &frame_capture::CapturedFrame)
+{
+    let since_epoch = std::time::SystemTime::now().duration_since(std::time::UNIX_EPOCH).unwrap_or(std::time::Duration::new(0, 0));
+    let path = format!("screenshot_{}.png", since_epoch.as_secs());
+
+    match frame.save_png(&path)
+    {
+        Ok(()) => println!("Saved screenshot to {}", path),
+        Err(err) => println!("Could not save screenshot to {}: {:?}", path, err)
+    }
+}
 
 fn main() {
 
+    if std::env::args().any(|argument| argument == "--golden")
+    {
+        let bless = std::env::args().any(|argument| argument == "--bless");
+        if !run_golden_tests(bless)
+        {
+            std::process::exit(1);
+        }
+        return;
+    }
+
+    if std::env::args().any(|argument| argument == "--headless")
+    {
+        run_headless();
+        return;
+    }
+
     let mut event_loop = winit::EventsLoop::new();
 
-    let mut instance = vulkano_instance::VulkanoInstance::new(&mut event_loop);
+    let jobs = std::sync::Arc::new(jobs::job_system::JobSystem::with_default_workers());
+
+    let present_modes = present_modes_from_args();
+
+    //--hdr asks for 10 bit or float output, it falls back to sRGB where the surface has none
+    let format_preference = if std::env::args().any(|argument| argument == "--hdr") { surface_format::SurfaceFormatPreference::Hdr } else { surface_format::SurfaceFormatPreference::Srgb };
+
+    let mut instance = vulkano_instance::VulkanoInstance::new(&mut event_loop, jobs.clone(), present_modes.clone().unwrap_or_default(), format_preference).unwrap_or_else(|err| exit_with_error(err));
 
     let mut run = true;
 
-    let bench_length = std::time::Duration::new(3, 0);
+    //--game plays the test game instead of cycling through the benchmarks
+    let mut game = if std::env::args().any(|argument| argument == "--game")
+    {
+        Some(test_game::TestGame::new(&mut instance, jobs.clone()).unwrap_or_else(|err| exit_with_error(err)))
+    }
+    else
+    {
+        None
+    };
 
-    let mut benchmarker = benchmarks::benchmarker::Benchmarker::new(vec!(
-        Box::new(render_benchmarks::TriangleBenchmark::new(bench_length, 10, 10)),
-        Box::new(render_benchmarks::TriangleBenchmark::new(bench_length, 1, 1)),
-        Box::new(render_benchmarks::TriangleBenchmark::new(bench_length, 3, 3)),
-        ));
+    //without a present mode on the command line the benchmarks alternate between uncapped and vsynced rounds
+    let mut benchmarker = match present_modes
+    {
+        Some(_) => create_benchmarker(),
+        None => create_benchmarker().with_present_modes(vec!(present_mode::PresentModeConfig::uncapped(), present_mode::PresentModeConfig::vsync()))
+    };
 
     while run
     {        
+        let mut screenshot_requested = false;
+
         event_loop.poll_events(|event| 
         {
             match event 
@@ -39,10 +182,40 @@ fn main() {
                 event: winit::WindowEvent::CloseRequested,
                 ..
                 } => run = false,
+                winit::Event::WindowEvent {
+                event: winit::WindowEvent::KeyboardInput {
+                    input: winit::KeyboardInput {
+                        state: winit::ElementState::Pressed,
+                        virtual_keycode: Some(winit::VirtualKeyCode::F12),
+                        ..
+                    },
+                    ..
+                },
+                ..
+                } => screenshot_requested = true,
                 _ => run = true,
             };
         });
 
-        benchmarker.tick_tests(&mut instance);
+        if screenshot_requested
+        {
+            instance.capture_next_frame();
+        }
+
+        let ticked = match game
+        {
+            Some(ref mut game) => game.tick(&mut instance),
+            None => benchmarker.tick_tests(&mut instance)
+        };
+
+        if let Err(err) = ticked
+        {
+            exit_with_error(err);
+        }
+
+        if let Some(frame) = instance.take_captured_frame()
+        {
+            save_screenshot(&frame);
+        }
     }
 }
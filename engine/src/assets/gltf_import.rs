@@ -0,0 +1,663 @@
+use color;
+use drawers::drawer_mesh::MeshData;
+use drawers::drawer_mesh::MeshVertex;
+use engine_error::EngineError;
+use scene::components::CameraComponent;
+use scene::components::MeshComponent;
+use scene::scene_graph::NodeId;
+use scene::scene_graph::Scene;
+use textures::texture::Texture;
+use vulkano_instance::PipelineImplementer;
+use math::Mat4;
+use math::Quat;
+use math::Vec3;
+
+use std::error;
+use std::fmt;
+use std::path::Path;
+use std::sync::Arc;
+
+//gltf 2.0, both .gltf with external or embedded buffers and binary .glb
+//gltf already uses our conventions: right handed, +y up, texture origin at the top left
+
+#[derive(Debug, Clone)]
+pub struct GltfScene
+{
+    //indexed the same way as in the file, so node references stay valid
+    pub nodes : Vec<GltfNode>,
+    //nodes of the default scene (or the first one) without a parent
+    pub roots : Vec<usize>,
+    pub meshes : Vec<GltfMesh>,
+    pub materials : Vec<PbrMaterial>,
+    pub images : Vec<GltfImage>,
+    pub cameras : Vec<GltfCamera>,
+    pub skins : Vec<GltfSkin>
+}
+
+impl GltfScene
+{
+    //adds the nodes reachable from the roots below parent, uploading what they use through the renderer
+    //nodes with several primitives get a child node for every primitive after the first
+    pub fn instantiate(&self, renderer : &mut PipelineImplementer, scene : &mut Scene, parent : Option<NodeId>) -> Result<Vec<NodeId>, EngineError>
+    {
+        //create_texture decodes sRGB, which is only right for base colors, the other images are not drawn with
+        let mut textures : Vec<Option<Arc<Texture>>> = vec![None; self.images.len()];
+        for image in self.base_color_images()
+        {
+            textures[image] = Some(renderer.create_texture(self.images[image].dimensions, &self.images[image].pixels, true)?);
+        }
+
+        let meshes : Vec<Vec<MeshComponent>> = self.meshes.iter().map(|mesh| mesh.primitives.iter().map(|primitive| -> Result<MeshComponent, EngineError>
+        {
+            let mesh = renderer.create_mesh(&primitive.mesh.vertices, &primitive.mesh.indices)?;
+            let material = primitive.material.map(|material| &self.materials[material]);
+            let texture = material.and_then(|material| material.base_color_image).and_then(|image| textures[image].clone());
+            //gltf factors are linear, tints are sRGB like the colors given to draw_mesh_tinted
+            let tint = material.map_or([1.0; 4], |material| color::linear_color_to_srgb(material.base_color_factor));
+            Ok(MeshComponent{mesh, texture, tint})
+        }).collect::<Result<Vec<_>, EngineError>>()).collect::<Result<_, EngineError>>()?;
+
+        let mut roots = Vec::new();
+        let mut pending : Vec<(usize, Option<NodeId>)> = self.roots.iter().rev().map(|&root| (root, parent)).collect();
+
+        while let Some((index, parent)) = pending.pop()
+        {
+            let gltf_node = &self.nodes[index];
+            let name = gltf_node.name.clone().unwrap_or_else(|| format!("node {}", index));
+
+            let id = scene.add_node(name.clone(), parent);
+            scene.set_transform(id, gltf_node.translation, gltf_node.rotation, gltf_node.scale);
+
+            if let Some(mesh) = gltf_node.mesh
+            {
+                for (primitive_index, primitive) in meshes[mesh].iter().enumerate()
+                {
+                    let primitive_node = if primitive_index == 0 { id } else { scene.add_node(format!("{} primitive {}", name, primitive_index), Some(id)) };
+                    scene.node_mut(primitive_node).unwrap().mesh = Some(primitive.clone());
+                }
+            }
+
+            if let Some(camera) = gltf_node.camera
+            {
+                scene.node_mut(id).unwrap().camera = Some(self.cameras[camera].to_component());
+            }
+
+            if self.roots.contains(&index)
+            {
+                roots.push(id);
+            }
+
+            pending.extend(gltf_node.children.iter().rev().map(|&child| (child, Some(id))));
+        }
+
+        Ok(roots)
+    }
+
+    //indices of the images a primitive's material uses as its base color, each once and in order
+    pub fn base_color_images(&self) -> Vec<usize>
+    {
+        let mut images : Vec<usize> = self.meshes.iter()
+            .flat_map(|mesh| mesh.primitives.iter())
+            .filter_map(|primitive| primitive.material)
+            .filter_map(|material| self.materials[material].base_color_image)
+            .collect();
+
+        images.sort();
+        images.dedup();
+        images
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct GltfNode
+{
+    pub name : Option<String>,
+    pub translation : Vec3,
+    pub rotation : Quat,
+    pub scale : Vec3,
+    pub children : Vec<usize>,
+    pub mesh : Option<usize>,
+    pub camera : Option<usize>,
+    pub skin : Option<usize>
+}
+
+impl GltfNode
+{
+    pub fn local_transform(&self) -> Mat4
+    {
+        Mat4::from_translation_rotation_scale(self.translation, self.rotation, self.scale)
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct GltfMesh
+{
+    pub name : Option<String>,
+    pub primitives : Vec<GltfPrimitive>
+}
+
+#[derive(Debug, Clone)]
+pub struct GltfPrimitive
+{
+    pub mesh : MeshData,
+    //None uses the gltf default material
+    pub material : Option<usize>
+}
+
+#[derive(Debug, Clone)]
+pub struct PbrMaterial
+{
+    pub name : Option<String>,
+    pub base_color_factor : [f32; 4],
+    //indices into GltfScene::images
+    pub base_color_image : Option<usize>,
+    pub metallic_factor : f32,
+    pub roughness_factor : f32,
+    pub metallic_roughness_image : Option<usize>,
+    pub normal_image : Option<usize>,
+    pub emissive_factor : [f32; 3],
+    pub double_sided : bool
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum ImageSource
+{
+    //stored in a buffer view, always the case for .glb
+    Embedded,
+    //the uri as written in the file, data uris included
+    External(String)
+}
+
+#[derive(Debug, Clone)]
+pub struct GltfImage
+{
+    pub name : Option<String>,
+    pub source : ImageSource,
+    pub dimensions : [u32; 2],
+    //decoded to tightly packed RGBA8 whatever the file stored
+    pub pixels : Vec<u8>
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum GltfProjection
+{
+    Perspective
+    {
+        vertical_fov : f32,
+        aspect_ratio : Option<f32>,
+        near : f32,
+        //None means an infinite far plane
+        far : Option<f32>
+    },
+    Orthographic
+    {
+        x_magnification : f32,
+        y_magnification : f32,
+        near : f32,
+        far : f32
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct GltfCamera
+{
+    pub name : Option<String>,
+    pub projection : GltfProjection
+}
+
+impl GltfCamera
+{
+    //the renderer's aspect ratio wins over the one in the file, and infinite far planes become very far
+    pub fn to_component(&self) -> CameraComponent
+    {
+        match self.projection
+        {
+            GltfProjection::Perspective{vertical_fov, near, far, ..} => CameraComponent::perspective(vertical_fov, near, far.unwrap_or(near * 100000.0)),
+            GltfProjection::Orthographic{y_magnification, near, far, ..} => CameraComponent::orthographic(y_magnification, near, far)
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct GltfSkin
+{
+    pub name : Option<String>,
+    //node indices
+    pub joints : Vec<usize>,
+    //one per joint, identity when the file leaves them out
+    pub inverse_bind_matrices : Vec<Mat4>,
+    pub skeleton : Option<usize>
+}
+
+#[derive(Debug)]
+pub enum GltfError
+{
+    //reading, parsing or validating the file or one of its buffers and images
+    Import(::gltf::Error),
+    MissingPositions{mesh : usize, primitive : usize},
+    //only triangle lists are supported
+    UnsupportedPrimitiveMode{mesh : usize, primitive : usize}
+}
+
+impl error::Error for GltfError
+{
+    fn description(&self) -> &str
+    {
+        match *self
+        {
+            GltfError::Import(_) => "error while importing the gltf file",
+            GltfError::MissingPositions{..} => "a mesh primitive has no positions",
+            GltfError::UnsupportedPrimitiveMode{..} => "a mesh primitive is not a triangle list"
+        }
+    }
+
+    fn cause(&self) -> Option<&error::Error>
+    {
+        match *self
+        {
+            GltfError::Import(ref err) => Some(err),
+            _ => None
+        }
+    }
+}
+
+impl fmt::Display for GltfError
+{
+    fn fmt(&self, fmt : &mut fmt::Formatter) -> Result<(), fmt::Error>
+    {
+        match *self
+        {
+            GltfError::Import(ref err) => write!(fmt, "{}: {}", error::Error::description(self), err),
+            GltfError::MissingPositions{mesh, primitive} | GltfError::UnsupportedPrimitiveMode{mesh, primitive} =>
+                write!(fmt, "{} (mesh {}, primitive {})", error::Error::description(self), mesh, primitive)
+        }
+    }
+}
+
+impl From<::gltf::Error> for GltfError
+{
+    fn from(err : ::gltf::Error) -> GltfError
+    {
+        GltfError::Import(err)
+    }
+}
+
+//external buffers and images are resolved relative to the file
+pub fn load_gltf<P : AsRef<Path>>(path : P) -> Result<GltfScene, GltfError>
+{
+    let (document, buffers, images) = ::gltf::import(path)?;
+    convert(&document, &buffers, &images)
+}
+
+//for .glb or .gltf held in memory, which then cannot reference external files
+pub fn parse_gltf(bytes : &[u8]) -> Result<GltfScene, GltfError>
+{
+    let (document, buffers, images) = ::gltf::import_slice(bytes)?;
+    convert(&document, &buffers, &images)
+}
+
+fn convert(document : &::gltf::Document, buffers : &[::gltf::buffer::Data], images : &[::gltf::image::Data]) -> Result<GltfScene, GltfError>
+{
+    let mut meshes = Vec::new();
+    for mesh in document.meshes()
+    {
+        meshes.push(convert_mesh(&mesh, buffers)?);
+    }
+
+    let scene = document.default_scene().or_else(|| document.scenes().next());
+
+    Ok(GltfScene
+    {
+        nodes : document.nodes().map(|node| convert_node(&node)).collect(),
+        roots : scene.map(|scene| scene.nodes().map(|node| node.index()).collect()).unwrap_or_default(),
+        meshes,
+        materials : document.materials().map(|material| convert_material(&material)).collect(),
+        images : document.images().zip(images.iter()).map(|(image, data)| convert_image(&image, data)).collect(),
+        cameras : document.cameras().map(|camera| convert_camera(&camera)).collect(),
+        skins : document.skins().map(|skin| convert_skin(&skin, buffers)).collect()
+    })
+}
+
+fn convert_node(node : &::gltf::Node) -> GltfNode
+{
+    let (translation, rotation, scale) = node.transform().decomposed();
+
+    GltfNode
+    {
+        name : node.name().map(|name| name.to_string()),
+        translation : translation.into(),
+        rotation : rotation.into(),
+        scale : scale.into(),
+        children : node.children().map(|child| child.index()).collect(),
+        mesh : node.mesh().map(|mesh| mesh.index()),
+        camera : node.camera().map(|camera| camera.index()),
+        skin : node.skin().map(|skin| skin.index())
+    }
+}
+
+fn convert_mesh(mesh : &::gltf::Mesh, buffers : &[::gltf::buffer::Data]) -> Result<GltfMesh, GltfError>
+{
+    let mut primitives = Vec::new();
+
+    for primitive in mesh.primitives()
+    {
+        let location = (mesh.index(), primitive.index());
+
+        if primitive.mode() != ::gltf::mesh::Mode::Triangles
+        {
+            return Err(GltfError::UnsupportedPrimitiveMode{mesh: location.0, primitive: location.1});
+        }
+
+        let reader = primitive.reader(|buffer| Some(&buffers[buffer.index()]));
+
+        let positions : Vec<[f32; 3]> = match reader.read_positions()
+        {
+            Some(positions) => positions.collect(),
+            None => return Err(GltfError::MissingPositions{mesh: location.0, primitive: location.1})
+        };
+
+        let indices : Vec<u32> = match reader.read_indices()
+        {
+            Some(indices) => indices.into_u32().collect(),
+            None => (0 .. positions.len() as u32).collect()
+        };
+
+        let normals : Vec<[f32; 3]> = match reader.read_normals()
+        {
+            Some(normals) => normals.collect(),
+            None => smooth_normals(&positions, &indices)
+        };
+
+        let uvs : Vec<[f32; 2]> = match reader.read_tex_coords(0)
+        {
+            Some(uvs) => uvs.into_f32().collect(),
+            None => vec![[0.0, 0.0]; positions.len()]
+        };
+
+        let vertices = positions.iter().zip(normals.iter()).zip(uvs.iter())
+            .map(|((&position, &normal), &uv)| MeshVertex{position, normal, uv})
+            .collect();
+
+        primitives.push(GltfPrimitive
+        {
+            mesh : MeshData{vertices, indices},
+            material : primitive.material().index()
+        });
+    }
+
+    Ok(GltfMesh
+    {
+        name : mesh.name().map(|name| name.to_string()),
+        primitives
+    })
+}
+
+//area weighted average of the normals of every triangle touching a vertex
+fn smooth_normals(positions : &[[f32; 3]], indices : &[u32]) -> Vec<[f32; 3]>
+{
+    let mut normals = vec![Vec3::zero(); positions.len()];
+
+    for triangle in indices.chunks(3).filter(|triangle| triangle.len() == 3)
+    {
+        let a = Vec3::from(positions[triangle[0] as usize]);
+        let b = Vec3::from(positions[triangle[1] as usize]);
+        let c = Vec3::from(positions[triangle[2] as usize]);
+        let normal = (b - a).cross(c - a);
+
+        for &index in triangle
+        {
+            normals[index as usize] += normal;
+        }
+    }
+
+    normals.into_iter().map(|normal| normal.normalize().into()).collect()
+}
+
+fn convert_material(material : &::gltf::Material) -> PbrMaterial
+{
+    let pbr = material.pbr_metallic_roughness();
+
+    PbrMaterial
+    {
+        name : material.name().map(|name| name.to_string()),
+        base_color_factor : pbr.base_color_factor(),
+        base_color_image : pbr.base_color_texture().map(|info| info.texture().source().index()),
+        metallic_factor : pbr.metallic_factor(),
+        roughness_factor : pbr.roughness_factor(),
+        metallic_roughness_image : pbr.metallic_roughness_texture().map(|info| info.texture().source().index()),
+        normal_image : material.normal_texture().map(|normal| normal.texture().source().index()),
+        emissive_factor : material.emissive_factor(),
+        double_sided : material.double_sided()
+    }
+}
+
+fn convert_image(image : &::gltf::Image, data : &::gltf::image::Data) -> GltfImage
+{
+    use ::gltf::image::Format;
+
+    let source = match image.source()
+    {
+        ::gltf::image::Source::View{..} => ImageSource::Embedded,
+        ::gltf::image::Source::Uri{uri, ..} => ImageSource::External(uri.to_string())
+    };
+
+    //16 bit channels keep their high byte, missing channels are filled in like vulkan does
+    let (channels, bytes_per_channel) = match data.format
+    {
+        Format::R8 => (1, 1),
+        Format::R8G8 => (2, 1),
+        Format::R8G8B8 | Format::B8G8R8 => (3, 1),
+        Format::R8G8B8A8 | Format::B8G8R8A8 => (4, 1),
+        Format::R16 => (1, 2),
+        Format::R16G16 => (2, 2),
+        Format::R16G16B16 => (3, 2),
+        Format::R16G16B16A16 => (4, 2)
+    };
+
+    let swap_red_blue = data.format == Format::B8G8R8 || data.format == Format::B8G8R8A8;
+
+    let mut pixels = Vec::with_capacity((data.width * data.height * 4) as usize);
+    for texel in data.pixels.chunks(channels * bytes_per_channel)
+    {
+        let channel = |index : usize| texel[index * bytes_per_channel + bytes_per_channel - 1];
+
+        let mut rgba = [0, 0, 0, 255];
+        for index in 0..channels
+        {
+            rgba[index] = channel(index);
+        }
+        if swap_red_blue
+        {
+            rgba.swap(0, 2);
+        }
+        pixels.extend_from_slice(&rgba);
+    }
+
+    GltfImage
+    {
+        name : image.name().map(|name| name.to_string()),
+        source,
+        dimensions : [data.width, data.height],
+        pixels
+    }
+}
+
+fn convert_camera(camera : &::gltf::Camera) -> GltfCamera
+{
+    let projection = match camera.projection()
+    {
+        ::gltf::camera::Projection::Perspective(perspective) => GltfProjection::Perspective
+        {
+            vertical_fov : perspective.yfov(),
+            aspect_ratio : perspective.aspect_ratio(),
+            near : perspective.znear(),
+            far : perspective.zfar()
+        },
+        ::gltf::camera::Projection::Orthographic(orthographic) => GltfProjection::Orthographic
+        {
+            x_magnification : orthographic.xmag(),
+            y_magnification : orthographic.ymag(),
+            near : orthographic.znear(),
+            far : orthographic.zfar()
+        }
+    };
+
+    GltfCamera
+    {
+        name : camera.name().map(|name| name.to_string()),
+        projection
+    }
+}
+
+fn convert_skin(skin : &::gltf::Skin, buffers : &[::gltf::buffer::Data]) -> GltfSkin
+{
+    let joints : Vec<usize> = skin.joints().map(|joint| joint.index()).collect();
+
+    let reader = skin.reader(|buffer| Some(&buffers[buffer.index()]));
+    let inverse_bind_matrices = match reader.read_inverse_bind_matrices()
+    {
+        Some(matrices) => matrices.map(Mat4::from).collect(),
+        None => vec![Mat4::identity(); joints.len()]
+    };
+
+    GltfSkin
+    {
+        name : skin.name().map(|name| name.to_string()),
+        joints,
+        inverse_bind_matrices,
+        skeleton : skin.skeleton().map(|skeleton| skeleton.index())
+    }
+}
+
+#[cfg(test)]
+mod tests
+{
+    use assets::gltf_import::load_gltf;
+    use assets::gltf_import::parse_gltf;
+    use assets::gltf_import::GltfError;
+    use assets::gltf_import::GltfProjection;
+    use assets::gltf_import::GltfScene;
+    use assets::gltf_import::ImageSource;
+    use assets::gltf_import::PbrMaterial;
+    use math::Mat4;
+    use math::Quat;
+    use math::Vec3;
+
+    //both files hold the same scene, the .glb embeds the buffer and the image the .gltf references
+    fn assert_is_triangle_scene(scene : &GltfScene, image_source : ImageSource)
+    {
+        //Root with the triangle and the camera below it
+        assert_eq!(scene.roots, vec![0]);
+        assert_eq!(scene.nodes.len(), 3);
+
+        let root = &scene.nodes[0];
+        assert_eq!(root.name.as_ref().map(|name| name.as_str()), Some("Root"));
+        assert_eq!(root.children, vec![1, 2]);
+        assert_eq!(root.translation, Vec3::new(0.0, 0.0, -3.0));
+        assert_eq!(root.mesh, None);
+
+        let triangle = &scene.nodes[1];
+        assert_eq!(triangle.name.as_ref().map(|name| name.as_str()), Some("Triangle"));
+        assert!(triangle.children.is_empty());
+        assert_eq!(triangle.mesh, Some(0));
+        assert_eq!(triangle.skin, Some(0));
+        assert!(triangle.rotation.approx_eq(Quat::from_axis_angle(Vec3::unit_z(), ::std::f32::consts::PI / 4.0)));
+        assert_eq!(triangle.scale, Vec3::splat(2.0));
+
+        let camera = &scene.nodes[2];
+        assert_eq!(camera.camera, Some(0));
+        assert_eq!(camera.translation, Vec3::new(0.0, 0.0, 3.0));
+        assert!(camera.local_transform().approx_eq(&Mat4::from_translation(Vec3::new(0.0, 0.0, 3.0))));
+
+        assert_eq!(scene.meshes.len(), 1);
+        assert_eq!(scene.meshes[0].primitives.len(), 1);
+        let primitive = &scene.meshes[0].primitives[0];
+        assert_eq!(primitive.material, Some(0));
+        assert_eq!(primitive.mesh.indices, vec![0, 1, 2]);
+        let positions : Vec<[f32; 3]> = primitive.mesh.vertices.iter().map(|vertex| vertex.position).collect();
+        assert_eq!(positions, vec![[-0.5, -0.5, 0.0], [0.5, -0.5, 0.0], [0.0, 0.5, 0.0]]);
+
+        assert_eq!(scene.materials.len(), 1);
+        let material = &scene.materials[0];
+        assert_eq!(material.name.as_ref().map(|name| name.as_str()), Some("Checker"));
+        assert_eq!(material.base_color_factor, [1.0, 0.5, 0.5, 1.0]);
+        assert_eq!(material.base_color_image, Some(0));
+        assert_eq!(material.metallic_factor, 0.0);
+        assert_eq!(material.roughness_factor, 0.75);
+        assert_eq!(material.metallic_roughness_image, None);
+        assert_eq!(material.normal_image, None);
+        assert!(material.double_sided);
+
+        //a 2x2 checker, white in the top left
+        assert_eq!(scene.images.len(), 1);
+        let image = &scene.images[0];
+        assert_eq!(image.source, image_source);
+        assert_eq!(image.dimensions, [2, 2]);
+        assert_eq!(image.pixels, vec![255, 255, 255, 255, 0, 0, 0, 255, 0, 0, 0, 255, 255, 255, 255, 255]);
+
+        assert_eq!(scene.cameras.len(), 1);
+        assert_eq!(scene.cameras[0].name.as_ref().map(|name| name.as_str()), Some("Camera"));
+        assert_eq!(scene.cameras[0].projection, GltfProjection::Perspective{vertical_fov: 0.8, aspect_ratio: None, near: 0.1, far: Some(100.0)});
+
+        assert_eq!(scene.skins.len(), 1);
+        let skin = &scene.skins[0];
+        assert_eq!(skin.name.as_ref().map(|name| name.as_str()), Some("Skin"));
+        assert_eq!(skin.joints, vec![1]);
+        assert_eq!(skin.skeleton, Some(0));
+        assert_eq!(skin.inverse_bind_matrices.len(), 1);
+        assert!(skin.inverse_bind_matrices[0].approx_eq(&Mat4::identity()));
+    }
+
+    #[test]
+    fn loads_gltf_with_an_external_buffer_and_image()
+    {
+        let scene = load_gltf(concat!(env!("CARGO_MANIFEST_DIR"), "/assets/models/triangle.gltf")).unwrap();
+        assert_is_triangle_scene(&scene, ImageSource::External("checker.png".to_string()));
+    }
+
+    #[test]
+    fn loads_glb()
+    {
+        let scene = load_gltf(concat!(env!("CARGO_MANIFEST_DIR"), "/assets/models/triangle.glb")).unwrap();
+        assert_is_triangle_scene(&scene, ImageSource::Embedded);
+    }
+
+    #[test]
+    fn parses_glb_from_memory()
+    {
+        let scene = parse_gltf(include_bytes!(concat!(env!("CARGO_MANIFEST_DIR"), "/assets/models/triangle.glb"))).unwrap();
+        assert_is_triangle_scene(&scene, ImageSource::Embedded);
+    }
+
+    #[test]
+    fn only_base_color_images_of_used_materials_are_textures()
+    {
+        let mut scene = load_gltf(concat!(env!("CARGO_MANIFEST_DIR"), "/assets/models/triangle.gltf")).unwrap();
+        assert_eq!(scene.base_color_images(), vec![0]);
+
+        //a second image the used material only reads metallic and roughness from
+        scene.images.push(scene.images[0].clone());
+        scene.materials[0].metallic_roughness_image = Some(1);
+        assert_eq!(scene.base_color_images(), vec![0]);
+
+        //and a material using it as a base color that no primitive uses
+        let unused = PbrMaterial{base_color_image: Some(1), .. scene.materials[0].clone()};
+        scene.materials.push(unused);
+        assert_eq!(scene.base_color_images(), vec![0]);
+
+        scene.meshes[0].primitives[0].material = Some(1);
+        assert_eq!(scene.base_color_images(), vec![1]);
+    }
+
+    #[test]
+    fn rejects_files_that_are_not_gltf()
+    {
+        match parse_gltf(b"not a gltf file")
+        {
+            Err(GltfError::Import(_)) => {},
+            other => panic!("expected an import error, got {:?}", other.map(|scene| scene.nodes.len()))
+        }
+
+        assert!(load_gltf(concat!(env!("CARGO_MANIFEST_DIR"), "/assets/models/missing.gltf")).is_err());
+    }
+}
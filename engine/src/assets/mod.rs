@@ -0,0 +1,3 @@
+//outside interface
+pub mod obj;
+pub mod gltf_import;
@@ -0,0 +1,720 @@
+use drawers::drawer_mesh::MeshData;
+use drawers::drawer_mesh::MeshVertex;
+use math::Vec3;
+
+use std::collections::HashMap;
+use std::error;
+use std::fmt;
+use std::fs::File;
+use std::io;
+use std::io::BufRead;
+use std::io::BufReader;
+use std::mem;
+use std::path::Path;
+use std::path::PathBuf;
+
+//wavefront obj and mtl, as exported by most modelling tools
+//texture coordinates are flipped to vulkan's top-left origin and polygons are triangulated as fans
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct ObjMaterial
+{
+    pub name : String,
+    pub ambient : [f32; 3],
+    pub diffuse : [f32; 3],
+    pub specular : [f32; 3],
+    pub shininess : f32,
+    //1 is fully opaque
+    pub dissolve : f32,
+    //relative to the mtl file unless absolute
+    pub diffuse_texture : Option<PathBuf>
+}
+
+impl ObjMaterial
+{
+    fn new(name : String) -> ObjMaterial
+    {
+        ObjMaterial
+        {
+            name,
+            ambient : [0.0, 0.0, 0.0],
+            diffuse : [1.0, 1.0, 1.0],
+            specular : [0.0, 0.0, 0.0],
+            shininess : 0.0,
+            dissolve : 1.0,
+            diffuse_texture : None
+        }
+    }
+}
+
+//the faces between two g/o/usemtl statements
+#[derive(Debug, Clone)]
+pub struct ObjGroup
+{
+    pub name : String,
+    pub material : Option<String>,
+    pub mesh : MeshData
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct ObjModel
+{
+    pub groups : Vec<ObjGroup>,
+    pub materials : Vec<ObjMaterial>,
+    //file names from mtllib statements, load_obj loads them into materials
+    pub material_libraries : Vec<String>
+}
+
+impl ObjModel
+{
+    pub fn material(&self, name : &str) -> Option<&ObjMaterial>
+    {
+        self.materials.iter().find(|material| material.name == name)
+    }
+
+    //every group in one mesh, for when materials do not matter
+    pub fn merged_mesh(&self) -> MeshData
+    {
+        let mut merged = MeshData::default();
+
+        for group in &self.groups
+        {
+            let offset = merged.vertices.len() as u32;
+            merged.vertices.extend_from_slice(&group.mesh.vertices);
+            merged.indices.extend(group.mesh.indices.iter().map(|index| index + offset));
+        }
+
+        merged
+    }
+}
+
+#[derive(Debug)]
+pub enum ObjErrorKind
+{
+    Io(io::Error),
+    //a keyword was missing some of its values
+    MissingValue(&'static str),
+    InvalidNumber(String),
+    InvalidIndex(String),
+    //an index pointing before the start or past the end of its list
+    IndexOutOfRange(i64),
+    FaceTooSmall(usize),
+    //a material statement before any newmtl
+    NoCurrentMaterial
+}
+
+#[derive(Debug)]
+pub struct ObjError
+{
+    //None when parsing from a reader
+    pub file : Option<PathBuf>,
+    //1 based, 0 if the error is not tied to a line
+    pub line : usize,
+    pub kind : ObjErrorKind
+}
+
+impl ObjError
+{
+    fn new(line : usize, kind : ObjErrorKind) -> ObjError
+    {
+        ObjError{file: None, line, kind}
+    }
+
+    fn in_file(mut self, file : &Path) -> ObjError
+    {
+        if self.file.is_none()
+        {
+            self.file = Some(file.to_path_buf());
+        }
+        self
+    }
+}
+
+impl error::Error for ObjError
+{
+    fn description(&self) -> &str
+    {
+        match self.kind
+        {
+            ObjErrorKind::Io(_) => "error while reading the file",
+            ObjErrorKind::MissingValue(_) => "a statement is missing a value",
+            ObjErrorKind::InvalidNumber(_) => "a value is not a valid number",
+            ObjErrorKind::InvalidIndex(_) => "a face has an invalid vertex reference",
+            ObjErrorKind::IndexOutOfRange(_) => "a face references a vertex that does not exist",
+            ObjErrorKind::FaceTooSmall(_) => "a face has fewer than three vertices",
+            ObjErrorKind::NoCurrentMaterial => "a material property appears before newmtl"
+        }
+    }
+
+    fn cause(&self) -> Option<&error::Error>
+    {
+        match self.kind
+        {
+            ObjErrorKind::Io(ref err) => Some(err),
+            _ => None
+        }
+    }
+}
+
+impl fmt::Display for ObjError
+{
+    fn fmt(&self, fmt : &mut fmt::Formatter) -> Result<(), fmt::Error>
+    {
+        if let Some(ref file) = self.file
+        {
+            write!(fmt, "{}:", file.display())?;
+        }
+        write!(fmt, "{}: {}", self.line, error::Error::description(self))?;
+        match self.kind
+        {
+            ObjErrorKind::Io(ref err) => write!(fmt, " ({})", err),
+            ObjErrorKind::MissingValue(keyword) => write!(fmt, " ({})", keyword),
+            ObjErrorKind::InvalidNumber(ref value) | ObjErrorKind::InvalidIndex(ref value) => write!(fmt, " ({:?})", value),
+            ObjErrorKind::IndexOutOfRange(index) => write!(fmt, " ({})", index),
+            ObjErrorKind::FaceTooSmall(count) => write!(fmt, " ({})", count),
+            ObjErrorKind::NoCurrentMaterial => Ok(())
+        }
+    }
+}
+
+//reads the obj and every mtl it references, relative to the obj's directory
+pub fn load_obj<P : AsRef<Path>>(path : P) -> Result<ObjModel, ObjError>
+{
+    let path = path.as_ref();
+    let file = File::open(path).map_err(|err| ObjError::new(0, ObjErrorKind::Io(err)).in_file(path))?;
+
+    let mut model = parse_obj(BufReader::new(file)).map_err(|err| err.in_file(path))?;
+
+    let directory = path.parent().unwrap_or(Path::new(""));
+
+    for library in model.material_libraries.clone()
+    {
+        let mut materials = load_mtl(directory.join(library))?;
+        model.materials.append(&mut materials);
+    }
+
+    Ok(model)
+}
+
+pub fn load_mtl<P : AsRef<Path>>(path : P) -> Result<Vec<ObjMaterial>, ObjError>
+{
+    let path = path.as_ref();
+    let file = File::open(path).map_err(|err| ObjError::new(0, ObjErrorKind::Io(err)).in_file(path))?;
+
+    let mut materials = parse_mtl(BufReader::new(file)).map_err(|err| err.in_file(path))?;
+
+    //texture paths are relative to the mtl
+    let directory = path.parent().unwrap_or(Path::new(""));
+    for material in materials.iter_mut()
+    {
+        material.diffuse_texture = material.diffuse_texture.take().map(|texture| directory.join(texture));
+    }
+
+    Ok(materials)
+}
+
+//position, texture coordinate and normal indices of one face corner, already zero based
+type Corner = (usize, Option<usize>, Option<usize>);
+
+struct GroupBuilder
+{
+    group : ObjGroup,
+    corner_indices : HashMap<Corner, u32>
+}
+
+impl GroupBuilder
+{
+    fn new(name : String, material : Option<String>) -> GroupBuilder
+    {
+        GroupBuilder
+        {
+            group : ObjGroup{name, material, mesh: MeshData::default()},
+            corner_indices : HashMap::new()
+        }
+    }
+}
+
+//does not load mtllib files, see load_obj
+pub fn parse_obj<R : BufRead>(reader : R) -> Result<ObjModel, ObjError>
+{
+    let mut model = ObjModel::default();
+
+    let mut positions : Vec<[f32; 3]> = Vec::new();
+    let mut uvs : Vec<[f32; 2]> = Vec::new();
+    let mut normals : Vec<[f32; 3]> = Vec::new();
+
+    let mut current = GroupBuilder::new("default".to_string(), None);
+
+    for (line_index, line) in reader.lines().enumerate()
+    {
+        let line_number = line_index + 1;
+        let line = line.map_err(|err| ObjError::new(line_number, ObjErrorKind::Io(err)))?;
+        let line = strip_comment(&line);
+
+        let mut values = line.split_whitespace();
+        let keyword = match values.next()
+        {
+            Some(keyword) => keyword,
+            None => continue
+        };
+
+        match keyword
+        {
+            "v" => positions.push(parse_floats(&mut values, line_number, "v")?),
+            "vt" =>
+            {
+                let u = parse_float(values.next(), line_number, "vt")?;
+                let v = match values.next()
+                {
+                    Some(value) => parse_float(Some(value), line_number, "vt")?,
+                    None => 0.0
+                };
+                uvs.push([u, 1.0 - v]);
+            },
+            "vn" => normals.push(parse_floats(&mut values, line_number, "vn")?),
+            "f" =>
+            {
+                let mut corners = Vec::new();
+                for value in values
+                {
+                    corners.push(parse_corner(value, positions.len(), uvs.len(), normals.len(), line_number)?);
+                }
+
+                if corners.len() < 3
+                {
+                    return Err(ObjError::new(line_number, ObjErrorKind::FaceTooSmall(corners.len())));
+                }
+
+                add_face(&mut current, &corners, &positions, &uvs, &normals);
+            },
+            "g" | "o" =>
+            {
+                let name = values.collect::<Vec<_>>().join(" ");
+                let material = current.group.material.clone();
+                finish_group(&mut model, &mut current, GroupBuilder::new(name, material));
+            },
+            "usemtl" =>
+            {
+                let material = values.next().ok_or(ObjError::new(line_number, ObjErrorKind::MissingValue("usemtl")))?.to_string();
+                let name = current.group.name.clone();
+                finish_group(&mut model, &mut current, GroupBuilder::new(name, Some(material)));
+            },
+            "mtllib" =>
+            {
+                let library = values.collect::<Vec<_>>().join(" ");
+                if library.is_empty()
+                {
+                    return Err(ObjError::new(line_number, ObjErrorKind::MissingValue("mtllib")));
+                }
+                model.material_libraries.push(library);
+            },
+            //smoothing groups, lines, points and anything newer are not supported and skipped
+            _ => {}
+        }
+    }
+
+    let last = GroupBuilder::new(String::new(), None);
+    finish_group(&mut model, &mut current, last);
+
+    Ok(model)
+}
+
+pub fn parse_mtl<R : BufRead>(reader : R) -> Result<Vec<ObjMaterial>, ObjError>
+{
+    let mut materials : Vec<ObjMaterial> = Vec::new();
+
+    for (line_index, line) in reader.lines().enumerate()
+    {
+        let line_number = line_index + 1;
+        let line = line.map_err(|err| ObjError::new(line_number, ObjErrorKind::Io(err)))?;
+        let line = strip_comment(&line);
+
+        let mut values = line.split_whitespace();
+        let keyword = match values.next()
+        {
+            Some(keyword) => keyword,
+            None => continue
+        };
+
+        if keyword == "newmtl"
+        {
+            let name = values.collect::<Vec<_>>().join(" ");
+            if name.is_empty()
+            {
+                return Err(ObjError::new(line_number, ObjErrorKind::MissingValue("newmtl")));
+            }
+            materials.push(ObjMaterial::new(name));
+            continue;
+        }
+
+        let known = ["Ka", "Kd", "Ks", "Ns", "d", "Tr", "map_Kd"];
+        if !known.contains(&keyword)
+        {
+            continue;
+        }
+
+        let material = materials.last_mut().ok_or(ObjError::new(line_number, ObjErrorKind::NoCurrentMaterial))?;
+
+        match keyword
+        {
+            "Ka" => material.ambient = parse_floats(&mut values, line_number, "Ka")?,
+            "Kd" => material.diffuse = parse_floats(&mut values, line_number, "Kd")?,
+            "Ks" => material.specular = parse_floats(&mut values, line_number, "Ks")?,
+            "Ns" => material.shininess = parse_float(values.next(), line_number, "Ns")?,
+            "d" => material.dissolve = parse_float(values.next(), line_number, "d")?,
+            "Tr" => material.dissolve = 1.0 - parse_float(values.next(), line_number, "Tr")?,
+            //options such as -bm come before the file name, which is always last
+            "map_Kd" => material.diffuse_texture = Some(PathBuf::from(values.last().ok_or(ObjError::new(line_number, ObjErrorKind::MissingValue("map_Kd")))?)),
+            _ => {}
+        }
+    }
+
+    Ok(materials)
+}
+
+fn strip_comment(line : &str) -> &str
+{
+    match line.find('#')
+    {
+        Some(start) => &line[..start],
+        None => line
+    }
+}
+
+fn parse_float(value : Option<&str>, line : usize, keyword : &'static str) -> Result<f32, ObjError>
+{
+    let value = value.ok_or(ObjError::new(line, ObjErrorKind::MissingValue(keyword)))?;
+    value.parse::<f32>().map_err(|_| ObjError::new(line, ObjErrorKind::InvalidNumber(value.to_string())))
+}
+
+fn parse_floats<'a, I>(values : &mut I, line : usize, keyword : &'static str) -> Result<[f32; 3], ObjError>
+    where I : Iterator<Item = &'a str>
+{
+    Ok([
+        parse_float(values.next(), line, keyword)?,
+        parse_float(values.next(), line, keyword)?,
+        parse_float(values.next(), line, keyword)?
+    ])
+}
+
+//obj indices are 1 based, negative ones count back from the latest element
+fn resolve_index(value : &str, count : usize, line : usize) -> Result<usize, ObjError>
+{
+    let index = value.parse::<i64>().map_err(|_| ObjError::new(line, ObjErrorKind::InvalidIndex(value.to_string())))?;
+
+    let resolved = if index < 0 { count as i64 + index } else { index - 1 };
+
+    if resolved < 0 || resolved >= count as i64
+    {
+        return Err(ObjError::new(line, ObjErrorKind::IndexOutOfRange(index)));
+    }
+
+    Ok(resolved as usize)
+}
+
+//v, v/vt, v//vn or v/vt/vn
+fn parse_corner(value : &str, position_count : usize, uv_count : usize, normal_count : usize, line : usize) -> Result<Corner, ObjError>
+{
+    let mut parts = value.split('/');
+
+    let position = resolve_index(parts.next().unwrap_or(""), position_count, line)?;
+
+    let uv = match parts.next()
+    {
+        Some(part) if !part.is_empty() => Some(resolve_index(part, uv_count, line)?),
+        _ => None
+    };
+
+    let normal = match parts.next()
+    {
+        Some(part) if !part.is_empty() => Some(resolve_index(part, normal_count, line)?),
+        _ => None
+    };
+
+    if parts.next().is_some()
+    {
+        return Err(ObjError::new(line, ObjErrorKind::InvalidIndex(value.to_string())));
+    }
+
+    Ok((position, uv, normal))
+}
+
+fn add_face(builder : &mut GroupBuilder, corners : &[Corner], positions : &[[f32; 3]], uvs : &[[f32; 2]], normals : &[[f32; 3]])
+{
+    //corners without a normal get the face normal, and are never shared with other faces
+    let face_normal =
+    {
+        let a = Vec3::from(positions[corners[0].0]);
+        let b = Vec3::from(positions[corners[1].0]);
+        let c = Vec3::from(positions[corners[2].0]);
+        (b - a).cross(c - a).normalize()
+    };
+
+    let mut indices = Vec::with_capacity(corners.len());
+
+    for &corner in corners
+    {
+        let (position, uv, normal) = corner;
+
+        if normal.is_some()
+        {
+            if let Some(&index) = builder.corner_indices.get(&corner)
+            {
+                indices.push(index);
+                continue;
+            }
+        }
+
+        let index = builder.group.mesh.vertices.len() as u32;
+        builder.group.mesh.vertices.push(MeshVertex
+        {
+            position : positions[position],
+            normal : normal.map(|normal| normals[normal]).unwrap_or(face_normal.into()),
+            uv : uv.map(|uv| uvs[uv]).unwrap_or([0.0, 0.0])
+        });
+
+        if normal.is_some()
+        {
+            builder.corner_indices.insert(corner, index);
+        }
+        indices.push(index);
+    }
+
+    for i in 1..indices.len() - 1
+    {
+        builder.group.mesh.indices.extend_from_slice(&[indices[0], indices[i], indices[i + 1]]);
+    }
+}
+
+//keeps the finished group unless it has no faces
+fn finish_group(model : &mut ObjModel, current : &mut GroupBuilder, next : GroupBuilder)
+{
+    let finished = mem::replace(current, next);
+
+    if !finished.group.mesh.indices.is_empty()
+    {
+        model.groups.push(finished.group);
+    }
+}
+
+#[cfg(test)]
+mod tests
+{
+    use assets::obj::load_obj;
+    use assets::obj::parse_mtl;
+    use assets::obj::parse_obj;
+    use assets::obj::ObjErrorKind;
+
+    use std::path::PathBuf;
+
+    const TRIANGLE : &str = "
+v 0 0 0
+v 1 0 0
+v 0 1 0
+vt 0 0
+vt 1 0
+vt 0 1
+vn 0 0 1
+f 1/1/1 2/2/1 3/3/1
+";
+
+    #[test]
+    fn parses_a_triangle()
+    {
+        let model = parse_obj(TRIANGLE.as_bytes()).unwrap();
+
+        assert_eq!(model.groups.len(), 1);
+        assert_eq!(model.groups[0].name, "default");
+        assert_eq!(model.groups[0].material, None);
+
+        let mesh = &model.groups[0].mesh;
+        assert_eq!(mesh.indices, vec![0, 1, 2]);
+        assert_eq!(mesh.vertices[1].position, [1.0, 0.0, 0.0]);
+        assert_eq!(mesh.vertices[1].normal, [0.0, 0.0, 1.0]);
+        //v is flipped to a top left origin
+        assert_eq!(mesh.vertices[2].uv, [0.0, 0.0]);
+        assert_eq!(mesh.vertices[0].uv, [0.0, 1.0]);
+    }
+
+    #[test]
+    fn negative_indices_count_back_from_the_latest_element()
+    {
+        let source = "
+v 0 0 0
+v 1 0 0
+v 0 1 0
+vn 0 0 1
+f -3//-1 -2//-1 -1//-1
+v 5 5 5
+f -4 -3 -1
+";
+        let model = parse_obj(source.as_bytes()).unwrap();
+        let mesh = &model.groups[0].mesh;
+
+        assert_eq!(mesh.indices.len(), 6);
+        assert_eq!(mesh.vertices[mesh.indices[0] as usize].position, [0.0, 0.0, 0.0]);
+        assert_eq!(mesh.vertices[mesh.indices[2] as usize].position, [0.0, 1.0, 0.0]);
+        //-1 is the vertex added after the first face
+        assert_eq!(mesh.vertices[mesh.indices[5] as usize].position, [5.0, 5.0, 5.0]);
+        assert_eq!(mesh.vertices[mesh.indices[3] as usize].position, [0.0, 0.0, 0.0]);
+    }
+
+    #[test]
+    fn polygons_are_triangulated_as_fans()
+    {
+        let source = "
+v 0 0 0
+v 1 0 0
+v 1 1 0
+v 0 1 0
+v -1 1 0
+vn 0 0 1
+f 1//1 2//1 3//1 4//1
+f 1//1 2//1 3//1 4//1 5//1
+";
+        let model = parse_obj(source.as_bytes()).unwrap();
+        let mesh = &model.groups[0].mesh;
+
+        //corners with the same position and normal are shared between faces
+        assert_eq!(mesh.vertices.len(), 5);
+        assert_eq!(mesh.indices, vec![0, 1, 2, 0, 2, 3, 0, 1, 2, 0, 2, 3, 0, 3, 4]);
+    }
+
+    #[test]
+    fn corners_without_normals_get_the_face_normal()
+    {
+        let source = "
+v 0 0 0
+v 0 1 0
+v 1 0 0
+f 1 2 3
+";
+        let model = parse_obj(source.as_bytes()).unwrap();
+
+        for vertex in model.groups[0].mesh.vertices.iter()
+        {
+            assert_eq!(vertex.normal, [0.0, 0.0, -1.0]);
+        }
+    }
+
+    #[test]
+    fn usemtl_splits_groups()
+    {
+        let source = "
+mtllib first.mtl
+v 0 0 0
+v 1 0 0
+v 0 1 0
+g body
+usemtl skin
+f 1 2 3
+usemtl cloth
+f 1 2 3
+f 3 2 1
+g head
+f 1 2 3
+usemtl unused
+";
+        let model = parse_obj(source.as_bytes()).unwrap();
+
+        assert_eq!(model.material_libraries, vec!["first.mtl".to_string()]);
+
+        let groups : Vec<(&str, Option<&str>, usize)> = model.groups.iter()
+            .map(|group| (group.name.as_str(), group.material.as_ref().map(|material| material.as_str()), group.mesh.indices.len() / 3))
+            .collect();
+
+        //groups without faces are dropped, a new group keeps the current material
+        assert_eq!(groups, vec![("body", Some("skin"), 1), ("body", Some("cloth"), 2), ("head", Some("cloth"), 1)]);
+        assert_eq!(model.merged_mesh().indices.len(), 12);
+    }
+
+    #[test]
+    fn errors_report_their_line()
+    {
+        let cases : Vec<(&str, usize)> = vec![
+            ("v 0 0 0\nv 1 0 0\nv 0 1 0\n\nf 1 2 4\n", 5),
+            ("v 0 0 0\nv 1 zero 0\n", 2),
+            ("# comment\nv 0 0\n", 2),
+            ("v 0 0 0\nv 1 0 0\nf 1 2\n", 3),
+            ("v 0 0 0\nf 1/a/1 1 1\n", 2),
+            ("v 0 0 0\nf 0 1 1\n", 2),
+            ("v 0 0 0\nusemtl\n", 2)
+        ];
+
+        for &(source, line) in cases.iter()
+        {
+            let err = parse_obj(source.as_bytes()).unwrap_err();
+            assert_eq!(err.line, line, "{:?} failed with {}", source, err);
+            assert!(err.to_string().starts_with(&format!("{}: ", line)));
+        }
+
+        match parse_obj("v 0 0 0\nf 1 2 3\n".as_bytes()).unwrap_err().kind
+        {
+            ObjErrorKind::IndexOutOfRange(2) => {},
+            kind => panic!("expected index 2 to be out of range, got {:?}", kind)
+        }
+    }
+
+    #[test]
+    fn parses_materials()
+    {
+        let source = "
+# a comment
+newmtl red
+Ka 0.1 0.1 0.1
+Kd 1 0 0 # trailing comment
+Ks 0.5 0.5 0.5
+Ns 10
+d 0.5
+map_Kd -bm 1.0 textures/red.png
+
+newmtl glass
+Tr 0.75
+illum 2
+";
+        let materials = parse_mtl(source.as_bytes()).unwrap();
+
+        assert_eq!(materials.len(), 2);
+        assert_eq!(materials[0].name, "red");
+        assert_eq!(materials[0].ambient, [0.1, 0.1, 0.1]);
+        assert_eq!(materials[0].diffuse, [1.0, 0.0, 0.0]);
+        assert_eq!(materials[0].specular, [0.5, 0.5, 0.5]);
+        assert_eq!(materials[0].shininess, 10.0);
+        assert_eq!(materials[0].dissolve, 0.5);
+        assert_eq!(materials[0].diffuse_texture, Some(PathBuf::from("textures/red.png")));
+
+        assert_eq!(materials[1].name, "glass");
+        assert_eq!(materials[1].diffuse, [1.0, 1.0, 1.0]);
+        assert_eq!(materials[1].dissolve, 0.25);
+    }
+
+    #[test]
+    fn material_errors_report_their_line()
+    {
+        let err = parse_mtl("Kd 1 1 1\n".as_bytes()).unwrap_err();
+        assert_eq!(err.line, 1);
+        match err.kind
+        {
+            ObjErrorKind::NoCurrentMaterial => {},
+            kind => panic!("expected no current material, got {:?}", kind)
+        }
+
+        assert_eq!(parse_mtl("newmtl a\n\nKd 1 1\n".as_bytes()).unwrap_err().line, 3);
+        assert_eq!(parse_mtl("newmtl a\nNs many\n".as_bytes()).unwrap_err().line, 2);
+        assert_eq!(parse_mtl("\nnewmtl\n".as_bytes()).unwrap_err().line, 2);
+    }
+
+    #[test]
+    fn loads_the_cube_with_its_materials()
+    {
+        let model = load_obj(concat!(env!("CARGO_MANIFEST_DIR"), "/assets/models/cube.obj")).unwrap();
+
+        assert_eq!(model.groups.len(), 3);
+        assert_eq!(model.merged_mesh().indices.len(), 36);
+        assert_eq!(model.material("green").map(|material| material.diffuse), Some([0.1, 0.8, 0.1]));
+
+        let missing = load_obj(concat!(env!("CARGO_MANIFEST_DIR"), "/assets/models/missing.obj")).unwrap_err();
+        assert!(missing.file.is_some());
+        assert_eq!(missing.line, 0);
+    }
+}
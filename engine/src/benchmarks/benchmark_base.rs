@@ -1,4 +1,7 @@
+use vulkano::swapchain::PresentMode;
+
 use vulkano_instance::PipelineImplementer;
+use engine_error::EngineError;
 use std::time::Instant;
 use std::time::Duration;
 
@@ -31,35 +34,39 @@ pub trait RenderBenchmark
         benchmark_common.frames_rendered = 0;
     }
 
-    fn bench_frame_with_boilerplate(&mut self, renderer: &mut PipelineImplementer) -> BenchmarkStatus
+    fn bench_frame_with_boilerplate(&mut self, renderer: &mut PipelineImplementer) -> Result<BenchmarkStatus, EngineError>
     {
         let start = Instant::now();
-        if renderer.begin_render().is_err()
+        match renderer.begin_render()
         {
-            return BenchmarkStatus::InProgress;
+            Ok(()) => {},
+            Err(EngineError::SwapchainOutOfDate) => return Ok(BenchmarkStatus::InProgress),
+            Err(err) => return Err(err)
         }
 
-        self.bench_frame(renderer);
+        self.bench_frame(renderer)?;
 
-        renderer.end_render();
+        renderer.end_render()?;
         let end = Instant::now();
 
+        let present_mode = renderer.present_mode();
         let benchmark_common = self.benchmark_common();
+        benchmark_common.present_mode = present_mode;
         
         benchmark_common.current_duration += end - start;
         benchmark_common.frames_rendered += 1;
 
         if benchmark_common.current_duration > benchmark_common.target_duration
         {
-            BenchmarkStatus::Finished
+            Ok(BenchmarkStatus::Finished)
         }
         else
         {
-            BenchmarkStatus::InProgress
+            Ok(BenchmarkStatus::InProgress)
         }
     }
 
-    fn bench_frame(&mut self, &mut PipelineImplementer);
+    fn bench_frame(&mut self, &mut PipelineImplementer) -> Result<(), EngineError>;
 
     fn print_result(&self);
 
@@ -70,13 +77,25 @@ pub struct BenchmarkCommon
 {
     pub current_duration : Duration,
     pub target_duration : Duration,
-    pub frames_rendered : i32
+    pub frames_rendered : i32,
+    //what the last frame was presented with, None when headless
+    pub present_mode : Option<PresentMode>
 }
 
 impl BenchmarkCommon
 {
     pub fn new(length: Duration) -> Self
     {
-        BenchmarkCommon{current_duration: Duration::new(0, 0), target_duration: length, frames_rendered: 0}
+        BenchmarkCommon{current_duration: Duration::new(0, 0), target_duration: length, frames_rendered: 0, present_mode: None}
+    }
+
+    //for the end of print_result
+    pub fn present_mode_description(&self) -> String
+    {
+        match self.present_mode
+        {
+            Some(present_mode) => format!("{:?}", present_mode),
+            None => "no present mode (headless)".to_string()
+        }
     }
 }
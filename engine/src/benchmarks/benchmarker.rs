@@ -1,11 +1,17 @@
 use benchmarks::benchmark_base::RenderBenchmark;
 use benchmarks::benchmark_base::BenchmarkStatus;
 use vulkano_instance::PipelineImplementer;
+use engine_error::EngineError;
+use present_mode::PresentModeConfig;
 
 pub struct Benchmarker
 {
     tests : Vec<Box<RenderBenchmark>>,
     test_index : usize,
+    rounds_completed : u32,
+    //every round runs with the next one of these, so the same tests get measured capped and uncapped
+    present_modes : Vec<PresentModeConfig>,
+    present_modes_applied : bool,
 }
 
 impl Benchmarker
@@ -14,30 +20,67 @@ impl Benchmarker
     {
         Self{
             tests,
-            test_index : 0
+            test_index : 0,
+            rounds_completed : 0,
+            present_modes : Vec::new(),
+            present_modes_applied : true
             }
     }
 
+    pub fn with_present_modes(mut self, present_modes : Vec<PresentModeConfig>) -> Self
+    {
+        self.present_modes_applied = present_modes.is_empty();
+        self.present_modes = present_modes;
+        self
+    }
+
     pub fn increment_test(&mut self)
     {
         self.tests[self.test_index].print_result();
 
         self.test_index += 1;
 
+        if self.test_index == self.tests.len()
+        {
+            self.rounds_completed += 1;
+            self.present_modes_applied = self.present_modes.is_empty();
+        }
+
         self.test_index %= self.tests.len();
 
         self.tests[self.test_index].begin_bench();
     }
 
+    //how many times every test has been run
+    pub fn rounds_completed(&self) -> u32
+    {
+        self.rounds_completed
+    }
+
     //runs  tests.
-    pub fn tick_tests(&mut self, renderer : &mut PipelineImplementer)
+    pub fn tick_tests(&mut self, renderer : &mut PipelineImplementer) -> Result<(), EngineError>
     {
-        let status = self.tests[self.test_index].bench_frame_with_boilerplate(renderer);
+        if !self.present_modes_applied
+        {
+            let present_modes = self.present_modes[self.rounds_completed as usize % self.present_modes.len()].clone();
+            println!("Benchmarking with present modes {:?}", present_modes.preferred);
+            renderer.set_present_modes(present_modes);
+            self.present_modes_applied = true;
+
+            //the next frame recreates the swapchain, it is drawn but not timed
+            self.tests[self.test_index].bench_frame_with_boilerplate(renderer)?;
+            self.tests[self.test_index].begin_bench();
+            return Ok(());
+        }
+
+        let status = self.tests[self.test_index].bench_frame_with_boilerplate(renderer)?;
 
         match status
         {
             BenchmarkStatus::Finished => self.increment_test(),
             _ => {}
         }
+
+        Ok(())
     }
 }
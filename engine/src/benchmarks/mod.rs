@@ -1,6 +1,4 @@
 //outside interface
 pub mod render_benchmarks;
 pub mod benchmarker;
-
-//internal use only
-mod benchmark_base;
+pub mod benchmark_base;
@@ -1,11 +1,23 @@
 //mod vulkano_instance;
 use vulkano_instance::PipelineImplementer;
+use engine_error::EngineError;
 use std::time::Duration;
+use std::path::PathBuf;
 
 
 use benchmarks::benchmark_base::BenchmarkCommon;
 use benchmarks::benchmark_base::RenderBenchmark;
 use benchmarks::benchmark_base::ToMilliseconds;
+use math::Vec2;
+use math::Vec3;
+use math::Quat;
+use assets::obj;
+use scene::components::CameraComponent;
+use scene::components::MeshComponent;
+use scene::scene_graph::NodeId;
+use scene::scene_graph::Scene;
+
+use std::f32::consts::PI;
 
 pub struct TriangleBenchmark
 {
@@ -24,33 +36,41 @@ impl TriangleBenchmark
 
 impl RenderBenchmark for TriangleBenchmark
 {
-    fn bench_frame(&mut self, renderer: &mut PipelineImplementer)
+    fn bench_frame(&mut self, renderer: &mut PipelineImplementer) -> Result<(), EngineError>
     {
-        let width_per_triangle = 2f32 / self.triangles_x as f32;
-        let height_per_triangle = 2f32 / self.triangles_y as f32;
+        //ndc goes from -1 to 1 on both axes
+        let triangle_size = Vec2::new(2f32 / self.triangles_x as f32, 2f32 / self.triangles_y as f32);
+        let half_size = triangle_size / 2f32;
+        let wobble = (self.benchmark_common.current_duration.to_milliseconds() * 0.003f32).sin();
 
         for x in 0..self.triangles_x
         {
             for y in 0..self.triangles_y
             {
+                let center = Vec2::splat(-1f32) + Vec2::new(0.5f32 + x as f32, 0.5f32 + y as f32).scale(triangle_size);
+
                 renderer.draw_triangle
                 ([
-                    [-1f32 + (0.5f32 + x as f32) * width_per_triangle - width_per_triangle / 2f32, -1f32 + (0.5f32 + y as f32) * height_per_triangle - height_per_triangle / 2f32], 
-                    [-1f32 + (0.5f32 + x as f32) * width_per_triangle + width_per_triangle / 2f32, -1f32 + (0.5f32 + y as f32) * height_per_triangle - height_per_triangle / 2f32], 
-                    [-1f32 + (0.5f32 + x as f32) * width_per_triangle, -1f32 + (0.5f32 + y as f32) * height_per_triangle + height_per_triangle / 2f32 + (self.benchmark_common.current_duration.to_milliseconds() * 0.003f32).sin()]
+                    (center - half_size).into(),
+                    Vec2::new(center.x + half_size.x, center.y - half_size.y).into(),
+                    Vec2::new(center.x, center.y + half_size.y + wobble).into()
                 ]);
             }
         }
+
+        Ok(())
     }
 
     fn print_result(&self)
     {
-        println!("Triangle test complete: {} triangles over {} ms and {} frames took an average of {} per frame. A total of {} vertex buffers were killed in this process.\n", 
+        println!("Triangle test complete: {} triangles over {} ms and {} frames took an average of {} per frame. A total of {} triangles were batched into {} draw calls. Presented with {}.\n", 
             self.triangles_x * self.triangles_y, 
             self.benchmark_common.target_duration.to_milliseconds(), 
             self.benchmark_common.frames_rendered,
             self.benchmark_common.target_duration.to_milliseconds()  / self.benchmark_common.frames_rendered as f32,
-            self.benchmark_common.frames_rendered * self.triangles_x * self.triangles_y
+            self.benchmark_common.frames_rendered * self.triangles_x * self.triangles_y,
+            self.benchmark_common.frames_rendered,
+            self.benchmark_common.present_mode_description()
         );
     }
 
@@ -60,3 +80,88 @@ impl RenderBenchmark for TriangleBenchmark
     }
 }
 
+//a grid of spinning copies of an obj model
+pub struct MeshBenchmark
+{
+    benchmark_common : BenchmarkCommon,
+    model_path : PathBuf,
+    //built on the first frame, only the transforms change after that
+    scene : Option<(Scene, Vec<NodeId>)>,
+    meshes_x : i32,
+    meshes_y : i32,
+}
+
+impl MeshBenchmark
+{
+    pub fn new<P : Into<PathBuf>>(length : Duration, model_path : P, meshes_x : i32, meshes_y : i32) -> Self
+    {
+        MeshBenchmark{benchmark_common: BenchmarkCommon::new(length), model_path: model_path.into(), scene: None, meshes_x, meshes_y}
+    }
+}
+
+impl RenderBenchmark for MeshBenchmark
+{
+    fn bench_frame(&mut self, renderer: &mut PipelineImplementer) -> Result<(), EngineError>
+    {
+        if self.scene.is_none()
+        {
+            let model = obj::load_obj(&self.model_path)?;
+            let mesh_data = model.merged_mesh();
+            let mesh = renderer.create_mesh(&mesh_data.vertices, &mesh_data.indices)?;
+
+            let mut scene = Scene::new();
+
+            //step back far enough to fit the whole grid
+            let grid_size = self.meshes_x.max(self.meshes_y) as f32;
+            let camera = scene.add_node("camera", None);
+            scene.set_translation(camera, Vec3::new(0f32, 0f32, grid_size * 1.5f32));
+            scene.node_mut(camera).unwrap().camera = Some(CameraComponent::perspective(PI / 3f32, 0.1f32, 1000f32));
+
+            let grid = scene.add_node("grid", None);
+            let mut nodes = Vec::new();
+            for x in 0..self.meshes_x
+            {
+                for y in 0..self.meshes_y
+                {
+                    let node = scene.add_node(format!("mesh {} {}", x, y), Some(grid));
+                    let position = Vec3::new(x as f32 - (self.meshes_x - 1) as f32 / 2f32, y as f32 - (self.meshes_y - 1) as f32 / 2f32, 0f32);
+                    scene.set_transform(node, position, Quat::identity(), Vec3::splat(0.5f32));
+                    scene.node_mut(node).unwrap().mesh = Some(MeshComponent::new(mesh.clone()));
+                    nodes.push(node);
+                }
+            }
+
+            self.scene = Some((scene, nodes));
+        }
+
+        let angle = self.benchmark_common.current_duration.to_milliseconds() * 0.001f32;
+        let rotation = Quat::from_euler(angle, angle * 0.7f32, 0f32);
+
+        let (ref mut scene, ref nodes) = *self.scene.as_mut().unwrap();
+        for &node in nodes
+        {
+            scene.set_rotation(node, rotation);
+        }
+
+        renderer.draw_scene(scene);
+
+        Ok(())
+    }
+
+    fn print_result(&self)
+    {
+        println!("Mesh test complete: {} meshes from {:?} over {} ms and {} frames took an average of {} per frame. Presented with {}.\n",
+            self.meshes_x * self.meshes_y,
+            self.model_path,
+            self.benchmark_common.target_duration.to_milliseconds(),
+            self.benchmark_common.frames_rendered,
+            self.benchmark_common.target_duration.to_milliseconds()  / self.benchmark_common.frames_rendered as f32,
+            self.benchmark_common.present_mode_description()
+        );
+    }
+
+    fn benchmark_common(&mut self) -> &mut BenchmarkCommon
+    {
+        &mut self.benchmark_common
+    }
+}
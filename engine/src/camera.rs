@@ -0,0 +1,125 @@
+use vulkano::pipeline::viewport::Viewport;
+
+use math::Mat4;
+use math::Quat;
+use math::Vec3;
+
+use std::f32::consts::PI;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Projection
+{
+    Perspective,
+    Orthographic
+}
+
+//the point of view every drawer renders from, owned by the renderer
+#[derive(Debug, Clone)]
+pub struct Camera
+{
+    pub position : Vec3,
+    //identity looks down -z with +y up
+    pub orientation : Quat,
+    pub projection : Projection,
+    pub vertical_fov : f32,
+    pub near : f32,
+    pub far : f32,
+    //half the height of the orthographic view volume in world units
+    pub ortho_size : f32,
+    viewport_dimensions : [u32; 2]
+}
+
+impl Camera
+{
+    pub fn new(viewport_dimensions : [u32; 2]) -> Camera
+    {
+        Camera
+        {
+            position : Vec3::zero(),
+            orientation : Quat::identity(),
+            projection : Projection::Perspective,
+            vertical_fov : PI / 3.0,
+            near : 0.1,
+            far : 1000.0,
+            ortho_size : 1.0,
+            viewport_dimensions
+        }
+    }
+
+    //called by the renderer whenever the render target changes size
+    pub fn set_viewport_dimensions(&mut self, viewport_dimensions : [u32; 2])
+    {
+        self.viewport_dimensions = viewport_dimensions;
+    }
+
+    pub fn viewport_dimensions(&self) -> [u32; 2]
+    {
+        self.viewport_dimensions
+    }
+
+    pub fn aspect_ratio(&self) -> f32
+    {
+        if self.viewport_dimensions[1] == 0
+        {
+            return 1.0;
+        }
+        self.viewport_dimensions[0] as f32 / self.viewport_dimensions[1] as f32
+    }
+
+    //covers the whole render target
+    pub fn viewport(&self) -> Viewport
+    {
+        Viewport
+        {
+            origin: [0.0, 0.0],
+            dimensions: [self.viewport_dimensions[0] as f32, self.viewport_dimensions[1] as f32],
+            depth_range: 0.0 .. 1.0,
+        }
+    }
+
+    pub fn forward(&self) -> Vec3
+    {
+        self.orientation.rotate(-Vec3::unit_z())
+    }
+
+    pub fn right(&self) -> Vec3
+    {
+        self.orientation.rotate(Vec3::unit_x())
+    }
+
+    pub fn up(&self) -> Vec3
+    {
+        self.orientation.rotate(Vec3::unit_y())
+    }
+
+    pub fn look_at(&mut self, target : Vec3, up : Vec3)
+    {
+        let view = Mat4::look_at(self.position, target, up);
+        self.orientation = Quat::from_mat3(view.to_mat3()).conjugate();
+    }
+
+    pub fn view_matrix(&self) -> Mat4
+    {
+        Mat4::from_quat(self.orientation.conjugate()) * Mat4::from_translation(-self.position)
+    }
+
+    pub fn projection_matrix(&self) -> Mat4
+    {
+        let aspect_ratio = self.aspect_ratio();
+
+        match self.projection
+        {
+            Projection::Perspective => Mat4::perspective(self.vertical_fov, aspect_ratio, self.near, self.far),
+            Projection::Orthographic =>
+            {
+                let half_width = self.ortho_size * aspect_ratio;
+                Mat4::orthographic(-half_width, half_width, -self.ortho_size, self.ortho_size, self.near, self.far)
+            }
+        }
+    }
+
+    pub fn view_projection(&self) -> Mat4
+    {
+        self.projection_matrix() * self.view_matrix()
+    }
+}
@@ -0,0 +1,113 @@
+use vulkano::format::Format;
+use vulkano::framebuffer::RenderPassAbstract;
+
+//drawers do their math in linear space, colors from users and image files are sRGB encoded
+
+pub fn srgb_to_linear(value : f32) -> f32
+{
+    if value <= 0.04045
+    {
+        value / 12.92
+    }
+    else
+    {
+        ((value + 0.055) / 1.055).powf(2.4)
+    }
+}
+
+pub fn linear_to_srgb(value : f32) -> f32
+{
+    if value <= 0.0031308
+    {
+        value * 12.92
+    }
+    else
+    {
+        1.055 * value.powf(1.0 / 2.4) - 0.055
+    }
+}
+
+//alpha is linear already
+pub fn srgb_color_to_linear(color : [f32; 4]) -> [f32; 4]
+{
+    [srgb_to_linear(color[0]), srgb_to_linear(color[1]), srgb_to_linear(color[2]), color[3]]
+}
+
+pub fn linear_color_to_srgb(color : [f32; 4]) -> [f32; 4]
+{
+    [linear_to_srgb(color[0]), linear_to_srgb(color[1]), linear_to_srgb(color[2]), color[3]]
+}
+
+//formats the hardware encodes to sRGB when written to and decodes when sampled
+pub fn is_srgb_format(format : Format) -> bool
+{
+    match format
+    {
+        Format::R8Srgb |
+        Format::R8G8Srgb |
+        Format::R8G8B8Srgb |
+        Format::B8G8R8Srgb |
+        Format::R8G8B8A8Srgb |
+        Format::B8G8R8A8Srgb |
+        Format::A8B8G8R8SrgbPack32 => true,
+        _ => false
+    }
+}
+
+//false when the first attachment of the render pass stores what the shader writes as is
+//the shader has to encode to sRGB itself then, see the ENCODE_SRGB specialization constant of the drawers
+pub fn target_encodes_srgb(render_pass : &RenderPassAbstract) -> bool
+{
+    render_pass.attachment_desc(0).map_or(false, |attachment| is_srgb_format(attachment.format))
+}
+
+#[cfg(test)]
+mod tests
+{
+    use vulkano::format::Format;
+
+    use color;
+
+    fn assert_close(actual : f32, expected : f32)
+    {
+        assert!((actual - expected).abs() < 1e-5, "{} is not {}", actual, expected);
+    }
+
+    #[test]
+    fn srgb_and_linear_round_trip()
+    {
+        for &value in [0.0, 0.5, 1.0].iter()
+        {
+            assert_close(color::linear_to_srgb(color::srgb_to_linear(value)), value);
+            assert_close(color::srgb_to_linear(color::linear_to_srgb(value)), value);
+        }
+    }
+
+    #[test]
+    fn srgb_half_gray_is_darker_in_linear()
+    {
+        assert_close(color::srgb_to_linear(0.0), 0.0);
+        assert_close(color::srgb_to_linear(0.5), 0.21404);
+        assert_close(color::srgb_to_linear(1.0), 1.0);
+        assert_close(color::linear_to_srgb(0.5), 0.73536);
+    }
+
+    #[test]
+    fn colors_convert_everything_but_alpha()
+    {
+        let linear = color::srgb_color_to_linear([0.5, 1.0, 0.0, 0.5]);
+
+        assert_close(linear[0], 0.21404);
+        assert_close(linear[3], 0.5);
+        assert_close(color::linear_color_to_srgb(linear)[0], 0.5);
+    }
+
+    #[test]
+    fn only_srgb_formats_are_srgb()
+    {
+        assert!(color::is_srgb_format(Format::B8G8R8A8Srgb));
+        assert!(color::is_srgb_format(Format::R8G8B8A8Srgb));
+        assert!(!color::is_srgb_format(Format::B8G8R8A8Unorm));
+        assert!(!color::is_srgb_format(Format::R16G16B16A16Sfloat));
+    }
+}
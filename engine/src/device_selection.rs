@@ -0,0 +1,333 @@
+use vulkano::device::DeviceExtensions;
+use vulkano::instance::Features;
+use vulkano::instance::PhysicalDevice;
+use vulkano::instance::PhysicalDeviceType;
+
+use std::env;
+use std::string::String;
+use std::vec::Vec;
+
+//set to a device index or part of a device name to pick that device over the best scoring one
+pub const DEVICE_OVERRIDE_VARIABLE : &str = "MOTOR_DEVICE";
+
+//what selection needs to know about a physical device, filled in from vulkano or by hand
+#[derive(Debug, Clone)]
+pub struct DeviceDescription
+{
+    pub index : usize,
+    pub name : String,
+    pub device_type : PhysicalDeviceType,
+    //id of a queue family that supports graphics, and presenting when there is a window
+    pub graphics_queue_family : Option<u32>,
+    pub extensions : DeviceExtensions,
+    pub features : Features
+}
+
+impl DeviceDescription
+{
+    pub fn from_physical(physical : PhysicalDevice, graphics_queue_family : Option<u32>) -> DeviceDescription
+    {
+        DeviceDescription
+        {
+            index : physical.index(),
+            name : physical.name(),
+            device_type : physical.ty(),
+            graphics_queue_family,
+            extensions : DeviceExtensions::supported_by_device(physical),
+            features : physical.supported_features().clone()
+        }
+    }
+}
+
+//devices missing any of these are never picked, and only these get enabled on the chosen one
+#[derive(Debug, Clone)]
+pub struct DeviceRequirements
+{
+    pub extensions : DeviceExtensions,
+    pub features : Features
+}
+
+#[derive(Debug, Clone)]
+pub struct RejectedDevice
+{
+    pub index : usize,
+    pub name : String,
+    pub reason : String
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ChosenDevice
+{
+    //index into PhysicalDevice::enumerate
+    pub index : usize,
+    //the family to create the graphics queue on
+    pub queue_family : u32
+}
+
+#[derive(Debug, Clone)]
+pub struct DeviceSelection
+{
+    pub chosen : Option<ChosenDevice>,
+    pub rejected : Vec<RejectedDevice>,
+    //the preferred device was missing or unsuitable, so the best scoring one was picked instead
+    pub unmatched_preference : Option<String>
+}
+
+impl DeviceSelection
+{
+    pub fn log(&self)
+    {
+        for rejected in self.rejected.iter()
+        {
+            println!("Rejected device {}: {}, ({})", rejected.index, rejected.name, rejected.reason);
+        }
+
+        if let Some(ref preference) = self.unmatched_preference
+        {
+            println!("No suitable device matches \"{}\", using the best one instead", preference);
+        }
+    }
+}
+
+//the preference from the environment, None when it is not set
+pub fn preference_from_env() -> Option<String>
+{
+    env::var(DEVICE_OVERRIDE_VARIABLE).ok().filter(|preference| !preference.is_empty())
+}
+
+//dedicated gpus first, falling back to whatever can draw at all
+pub fn score(description : &DeviceDescription) -> u32
+{
+    match description.device_type
+    {
+        PhysicalDeviceType::DiscreteGpu => 1000,
+        PhysicalDeviceType::IntegratedGpu => 500,
+        PhysicalDeviceType::VirtualGpu => 250,
+        PhysicalDeviceType::Cpu => 100,
+        PhysicalDeviceType::Other => 0
+    }
+}
+
+//why the device can not be used, None if it meets the requirements
+pub fn rejection_reason(description : &DeviceDescription, requirements : &DeviceRequirements) -> Option<String>
+{
+    if description.graphics_queue_family.is_none()
+    {
+        return Some("no queue supports graphics commands".to_string());
+    }
+
+    let missing_extensions = requirements.extensions.difference(&description.extensions);
+    if missing_extensions != DeviceExtensions::none()
+    {
+        return Some(format!("missing extensions {:?}", missing_extensions));
+    }
+
+    if !description.features.superset_of(&requirements.features)
+    {
+        return Some("missing required features".to_string());
+    }
+
+    None
+}
+
+//a preference is either a device index or part of a device name, ignoring case
+fn matches_preference(description : &DeviceDescription, preference : &str) -> bool
+{
+    match preference.trim().parse::<usize>()
+    {
+        Ok(index) => description.index == index,
+        Err(_) => description.name.to_lowercase().contains(&preference.trim().to_lowercase())
+    }
+}
+
+//the highest scoring device that meets the requirements, ties go to the one enumerated first
+//a preference wins over the score as long as the device it names is suitable
+pub fn select_device(candidates : &[DeviceDescription], requirements : &DeviceRequirements, preference : Option<&str>) -> DeviceSelection
+{
+    let mut rejected = Vec::new();
+    let mut suitable = Vec::new();
+
+    for candidate in candidates
+    {
+        match rejection_reason(candidate, requirements)
+        {
+            Some(reason) => rejected.push(RejectedDevice{index: candidate.index, name: candidate.name.clone(), reason}),
+            None => suitable.push(candidate)
+        }
+    }
+
+    let mut unmatched_preference = None;
+
+    if let Some(preference) = preference
+    {
+        if suitable.iter().any(|candidate| matches_preference(candidate, preference))
+        {
+            for candidate in suitable.iter().filter(|candidate| !matches_preference(candidate, preference))
+            {
+                rejected.push(RejectedDevice{index: candidate.index, name: candidate.name.clone(), reason: format!("not the preferred device \"{}\"", preference)});
+            }
+
+            suitable.retain(|candidate| matches_preference(candidate, preference));
+        }
+        else
+        {
+            unmatched_preference = Some(preference.to_string());
+        }
+    }
+
+    let mut chosen : Option<&DeviceDescription> = None;
+    for candidate in suitable
+    {
+        if chosen.map_or(true, |best| score(candidate) > score(best))
+        {
+            chosen = Some(candidate);
+        }
+    }
+
+    DeviceSelection
+    {
+        chosen : chosen.and_then(|description| description.graphics_queue_family.map(|queue_family| ChosenDevice{index: description.index, queue_family})),
+        rejected,
+        unmatched_preference
+    }
+}
+
+#[cfg(test)]
+mod tests
+{
+    use vulkano::device::DeviceExtensions;
+    use vulkano::instance::Features;
+    use vulkano::instance::PhysicalDeviceType;
+
+    use device_selection::ChosenDevice;
+    use device_selection::DeviceDescription;
+    use device_selection::DeviceRequirements;
+    use device_selection::select_device;
+
+    fn device(index : usize, name : &str, device_type : PhysicalDeviceType) -> DeviceDescription
+    {
+        DeviceDescription
+        {
+            index,
+            name : name.to_string(),
+            device_type,
+            graphics_queue_family : Some(index as u32 + 10),
+            extensions : DeviceExtensions{khr_swapchain: true, .. DeviceExtensions::none()},
+            features : Features::none()
+        }
+    }
+
+    fn swapchain_requirements() -> DeviceRequirements
+    {
+        DeviceRequirements
+        {
+            extensions : DeviceExtensions{khr_swapchain: true, .. DeviceExtensions::none()},
+            features : Features::none()
+        }
+    }
+
+    fn chosen_index(candidates : &[DeviceDescription], preference : Option<&str>) -> Option<usize>
+    {
+        select_device(candidates, &swapchain_requirements(), preference).chosen.map(|chosen| chosen.index)
+    }
+
+    #[test]
+    fn discrete_gpus_beat_integrated_ones()
+    {
+        let candidates = [
+            device(0, "Integrated", PhysicalDeviceType::IntegratedGpu),
+            device(1, "Discrete", PhysicalDeviceType::DiscreteGpu),
+            device(2, "Software", PhysicalDeviceType::Cpu)
+        ];
+
+        let selection = select_device(&candidates, &swapchain_requirements(), None);
+
+        assert_eq!(selection.chosen, Some(ChosenDevice{index: 1, queue_family: 11}));
+        assert!(selection.rejected.is_empty());
+        assert!(selection.unmatched_preference.is_none());
+    }
+
+    #[test]
+    fn ties_go_to_the_first_enumerated()
+    {
+        let candidates = [
+            device(0, "Integrated", PhysicalDeviceType::IntegratedGpu),
+            device(1, "First discrete", PhysicalDeviceType::DiscreteGpu),
+            device(2, "Second discrete", PhysicalDeviceType::DiscreteGpu)
+        ];
+
+        assert_eq!(chosen_index(&candidates, None), Some(1));
+    }
+
+    #[test]
+    fn unsuitable_devices_are_rejected_with_a_reason()
+    {
+        let mut no_swapchain = device(0, "No swapchain", PhysicalDeviceType::DiscreteGpu);
+        no_swapchain.extensions = DeviceExtensions::none();
+        let mut no_graphics = device(1, "No graphics", PhysicalDeviceType::DiscreteGpu);
+        no_graphics.graphics_queue_family = None;
+        let candidates = [no_swapchain, no_graphics, device(2, "Fine", PhysicalDeviceType::Cpu)];
+
+        let selection = select_device(&candidates, &swapchain_requirements(), None);
+
+        assert_eq!(selection.chosen, Some(ChosenDevice{index: 2, queue_family: 12}));
+        assert_eq!(selection.rejected.len(), 2);
+        assert_eq!(selection.rejected[0].index, 0);
+        assert!(selection.rejected[0].reason.contains("missing extensions"), "{}", selection.rejected[0].reason);
+        assert_eq!(selection.rejected[1].index, 1);
+        assert!(selection.rejected[1].reason.contains("graphics"), "{}", selection.rejected[1].reason);
+    }
+
+    #[test]
+    fn nothing_is_chosen_when_no_device_is_suitable()
+    {
+        let mut no_graphics = device(0, "No graphics", PhysicalDeviceType::DiscreteGpu);
+        no_graphics.graphics_queue_family = None;
+
+        let selection = select_device(&[no_graphics], &swapchain_requirements(), None);
+
+        assert!(selection.chosen.is_none());
+        assert_eq!(selection.rejected.len(), 1);
+        assert!(select_device(&[], &swapchain_requirements(), None).chosen.is_none());
+    }
+
+    #[test]
+    fn a_preference_picks_by_index_or_by_name()
+    {
+        let candidates = [
+            device(0, "Intel(R) UHD Graphics", PhysicalDeviceType::IntegratedGpu),
+            device(1, "NVIDIA GeForce GTX 1080", PhysicalDeviceType::DiscreteGpu)
+        ];
+
+        assert_eq!(chosen_index(&candidates, Some("0")), Some(0));
+        assert_eq!(chosen_index(&candidates, Some(" 1 ")), Some(1));
+        assert_eq!(chosen_index(&candidates, Some("intel")), Some(0));
+        assert_eq!(chosen_index(&candidates, Some("GeForce")), Some(1));
+
+        let selection = select_device(&candidates, &swapchain_requirements(), Some("uhd"));
+        assert!(selection.unmatched_preference.is_none());
+        assert_eq!(selection.rejected.len(), 1);
+        assert_eq!(selection.rejected[0].index, 1);
+        assert!(selection.rejected[0].reason.contains("preferred"), "{}", selection.rejected[0].reason);
+    }
+
+    #[test]
+    fn an_unmatched_preference_falls_back_to_the_best_device()
+    {
+        let mut unsuitable = device(2, "Radeon", PhysicalDeviceType::DiscreteGpu);
+        unsuitable.graphics_queue_family = None;
+        let candidates = [
+            device(0, "Intel(R) UHD Graphics", PhysicalDeviceType::IntegratedGpu),
+            device(1, "NVIDIA GeForce GTX 1080", PhysicalDeviceType::DiscreteGpu),
+            unsuitable
+        ];
+
+        for preference in ["7", "Matrox", "Radeon", "2"].iter()
+        {
+            let selection = select_device(&candidates, &swapchain_requirements(), Some(preference));
+
+            assert_eq!(selection.chosen.map(|chosen| chosen.index), Some(1), "preference {}", preference);
+            assert_eq!(selection.unmatched_preference.as_ref().map(|preference| preference.as_str()), Some(*preference));
+        }
+    }
+}
@@ -0,0 +1,83 @@
+use std::vec::Vec;
+
+//an index into every component storage, the generation tells reused indices apart
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Entity
+{
+    index : u32,
+    generation : u32
+}
+
+impl Entity
+{
+    pub fn index(&self) -> usize
+    {
+        self.index as usize
+    }
+}
+
+//hands out entities and recycles the indices of deleted ones
+pub struct EntityAllocator
+{
+    generations : Vec<u32>,
+    alive : Vec<bool>,
+    free_indices : Vec<u32>
+}
+
+impl EntityAllocator
+{
+    pub fn new() -> EntityAllocator
+    {
+        EntityAllocator
+        {
+            generations : Vec::new(),
+            alive : Vec::new(),
+            free_indices : Vec::new()
+        }
+    }
+
+    pub fn allocate(&mut self) -> Entity
+    {
+        match self.free_indices.pop()
+        {
+            Some(index) =>
+            {
+                self.alive[index as usize] = true;
+                Entity{index, generation: self.generations[index as usize]}
+            },
+            None =>
+            {
+                self.generations.push(0);
+                self.alive.push(true);
+                Entity{index: (self.generations.len() - 1) as u32, generation: 0}
+            }
+        }
+    }
+
+    //returns false if the entity was already gone
+    pub fn deallocate(&mut self, entity : Entity) -> bool
+    {
+        if !self.is_alive(entity)
+        {
+            return false;
+        }
+
+        self.alive[entity.index()] = false;
+        self.generations[entity.index()] += 1;
+        self.free_indices.push(entity.index);
+        true
+    }
+
+    pub fn is_alive(&self, entity : Entity) -> bool
+    {
+        self.alive.get(entity.index()).cloned().unwrap_or(false) && self.generations[entity.index()] == entity.generation
+    }
+
+    pub fn iter<'a>(&'a self) -> Box<Iterator<Item = Entity> + 'a>
+    {
+        Box::new(self.alive.iter().enumerate().filter(|&(_, &alive)| alive).map(move |(index, _)|
+        {
+            Entity{index: index as u32, generation: self.generations[index]}
+        }))
+    }
+}
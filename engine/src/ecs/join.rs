@@ -0,0 +1,238 @@
+use ecs::entity::Entity;
+use ecs::storage::Component;
+use ecs::storage::Storage;
+
+use std::vec::Vec;
+
+//a query over one or more storages, tuples of storages yield the entities that have all of them
+//  for (entity, (position, velocity)) in (&mut *positions, &*velocities).join() { ... }
+pub trait Join : Sized
+{
+    type Item;
+
+    //every entity this side of the query could yield
+    fn candidates(&self) -> Vec<Entity>;
+
+    fn candidate_count(&self) -> usize;
+
+    //only called once per entity by JoinIter, which is what makes handing out &mut sound
+    unsafe fn fetch(&mut self, entity : Entity) -> Option<Self::Item>;
+
+    fn join(self) -> JoinIter<Self>
+    {
+        let candidates = self.candidates();
+        JoinIter
+        {
+            join : self,
+            candidates,
+            position : 0
+        }
+    }
+}
+
+pub struct JoinIter<J : Join>
+{
+    join : J,
+    candidates : Vec<Entity>,
+    position : usize
+}
+
+impl<J : Join> Iterator for JoinIter<J>
+{
+    type Item = (Entity, J::Item);
+
+    fn next(&mut self) -> Option<(Entity, J::Item)>
+    {
+        while self.position < self.candidates.len()
+        {
+            let entity = self.candidates[self.position];
+            self.position += 1;
+
+            if let Some(item) = unsafe { self.join.fetch(entity) }
+            {
+                return Some((entity, item));
+            }
+        }
+
+        None
+    }
+}
+
+impl<'a, T : Component> Join for &'a Storage<T>
+{
+    type Item = &'a T;
+
+    fn candidates(&self) -> Vec<Entity>
+    {
+        self.entities().collect()
+    }
+
+    fn candidate_count(&self) -> usize
+    {
+        self.len()
+    }
+
+    unsafe fn fetch(&mut self, entity : Entity) -> Option<&'a T>
+    {
+        let storage : &'a Storage<T> = *self;
+        storage.get(entity)
+    }
+}
+
+impl<'a, T : Component> Join for &'a mut Storage<T>
+{
+    type Item = &'a mut T;
+
+    fn candidates(&self) -> Vec<Entity>
+    {
+        self.entities().collect()
+    }
+
+    fn candidate_count(&self) -> usize
+    {
+        self.len()
+    }
+
+    unsafe fn fetch(&mut self, entity : Entity) -> Option<&'a mut T>
+    {
+        //every entity is fetched at most once, so the references never alias
+        self.get_mut(entity).map(|component| &mut *(component as *mut T))
+    }
+}
+
+macro_rules! impl_join_tuple
+{
+    ($($join : ident),+) =>
+    {
+        #[allow(non_snake_case)]
+        impl<$($join : Join),+> Join for ($($join,)+)
+        {
+            type Item = ($($join::Item,)+);
+
+            //the smallest storage drives the query, the others are only looked up
+            fn candidates(&self) -> Vec<Entity>
+            {
+                let ($(ref $join,)+) = *self;
+                let mut smallest : Option<(usize, Vec<Entity>)> = None;
+                $(
+                    if smallest.as_ref().map_or(true, |&(count, _)| $join.candidate_count() < count)
+                    {
+                        smallest = Some(($join.candidate_count(), $join.candidates()));
+                    }
+                )+
+                smallest.unwrap().1
+            }
+
+            fn candidate_count(&self) -> usize
+            {
+                let ($(ref $join,)+) = *self;
+                let mut count = usize::max_value();
+                $( count = count.min($join.candidate_count()); )+
+                count
+            }
+
+            unsafe fn fetch(&mut self, entity : Entity) -> Option<Self::Item>
+            {
+                let ($(ref mut $join,)+) = *self;
+                Some(($(
+                    match $join.fetch(entity)
+                    {
+                        Some(item) => item,
+                        None => return None
+                    },
+                )+))
+            }
+        }
+    }
+}
+
+impl_join_tuple!(A);
+impl_join_tuple!(A, B);
+impl_join_tuple!(A, B, C);
+impl_join_tuple!(A, B, C, D);
+impl_join_tuple!(A, B, C, D, E);
+
+#[cfg(test)]
+mod tests
+{
+    use ecs::entity::Entity;
+    use ecs::entity::EntityAllocator;
+    use ecs::join::Join;
+    use ecs::storage::Storage;
+
+    #[derive(Debug, PartialEq)]
+    struct Position(i32);
+    #[derive(Debug, PartialEq)]
+    struct Velocity(i32);
+    #[derive(Debug, PartialEq)]
+    struct Name(&'static str);
+
+    fn entities(count : usize) -> Vec<Entity>
+    {
+        let mut allocator = EntityAllocator::new();
+        (0 .. count).map(|_| allocator.allocate()).collect()
+    }
+
+    #[test]
+    fn a_single_storage_yields_every_component()
+    {
+        let entities = entities(3);
+        let mut positions = Storage::new();
+        positions.insert(entities[0], Position(0));
+        positions.insert(entities[2], Position(2));
+
+        let joined : Vec<(Entity, &Position)> = (&positions).join().collect();
+
+        assert_eq!(joined, vec![(entities[0], &Position(0)), (entities[2], &Position(2))]);
+    }
+
+    #[test]
+    fn joins_only_yield_entities_with_every_component()
+    {
+        let entities = entities(4);
+        let mut positions = Storage::new();
+        let mut velocities = Storage::new();
+        let mut names = Storage::new();
+
+        for (index, &entity) in entities.iter().enumerate()
+        {
+            positions.insert(entity, Position(index as i32));
+        }
+        velocities.insert(entities[1], Velocity(10));
+        velocities.insert(entities[2], Velocity(20));
+        velocities.insert(entities[3], Velocity(30));
+        names.insert(entities[0], Name("zero"));
+        names.insert(entities[2], Name("two"));
+        names.insert(entities[3], Name("three"));
+
+        let pairs : Vec<Entity> = (&positions, &velocities).join().map(|(entity, _)| entity).collect();
+        assert_eq!(pairs, vec![entities[1], entities[2], entities[3]]);
+
+        let triples : Vec<(Entity, (&Position, &Velocity, &Name))> = (&positions, &velocities, &names).join().collect();
+        assert_eq!(triples, vec![(entities[2], (&Position(2), &Velocity(20), &Name("two"))), (entities[3], (&Position(3), &Velocity(30), &Name("three")))]);
+    }
+
+    #[test]
+    fn mutable_joins_write_through()
+    {
+        let entities = entities(3);
+        let mut positions = Storage::new();
+        let mut velocities = Storage::new();
+
+        for &entity in entities.iter()
+        {
+            positions.insert(entity, Position(1));
+        }
+        velocities.insert(entities[0], Velocity(5));
+        velocities.insert(entities[2], Velocity(7));
+
+        for (_, (position, velocity)) in (&mut positions, &velocities).join()
+        {
+            position.0 += velocity.0;
+        }
+
+        assert_eq!(positions.get(entities[0]), Some(&Position(6)));
+        assert_eq!(positions.get(entities[1]), Some(&Position(1)));
+        assert_eq!(positions.get(entities[2]), Some(&Position(8)));
+    }
+}
@@ -0,0 +1,7 @@
+//outside interface
+pub mod entity;
+pub mod storage;
+pub mod world;
+pub mod join;
+pub mod system;
+pub mod scheduler;
@@ -0,0 +1,187 @@
+use ecs::system;
+use ecs::system::RenderSystem;
+use ecs::system::System;
+use ecs::system::SystemAccess;
+use ecs::world::World;
+use jobs::job_system::JobSystem;
+use vulkano_instance::PipelineImplementer;
+
+use std::boxed::Box;
+use std::sync::Arc;
+use std::vec::Vec;
+
+//systems that do not conflict with each other and run at the same time
+struct Stage
+{
+    systems : Vec<(SystemAccess, Box<System>)>
+}
+
+//runs systems in stages, a system ends up in the stage after the last one it conflicts with
+//so systems touching the same data keep the order they were added in
+pub struct Scheduler
+{
+    jobs : Arc<JobSystem>,
+    stages : Vec<Stage>,
+    render_systems : Vec<Box<RenderSystem>>
+}
+
+impl Scheduler
+{
+    pub fn new(jobs : Arc<JobSystem>) -> Scheduler
+    {
+        Scheduler
+        {
+            jobs,
+            stages : Vec::new(),
+            render_systems : Vec::new()
+        }
+    }
+
+    pub fn add_system<S : System + 'static>(&mut self, system : S)
+    {
+        let access = system.access();
+
+        let first_free_stage = self.stages.iter()
+            .rposition(|stage| stage.systems.iter().any(|&(ref other, _)| access.conflicts_with(other)))
+            .map_or(0, |conflicting_stage| conflicting_stage + 1);
+
+        if first_free_stage == self.stages.len()
+        {
+            self.stages.push(Stage{systems: Vec::new()});
+        }
+
+        self.stages[first_free_stage].systems.push((access, Box::new(system)));
+    }
+
+    pub fn add_render_system<S : RenderSystem + 'static>(&mut self, render_system : S)
+    {
+        self.render_systems.push(Box::new(render_system));
+    }
+
+    //one tick of game logic followed by handing the world to the renderer
+    pub fn run(&mut self, world : &World, renderer : &mut PipelineImplementer)
+    {
+        self.run_systems(world);
+
+        for render_system in self.render_systems.iter_mut()
+        {
+            render_system.render(world, renderer);
+        }
+    }
+
+    pub fn run_systems(&mut self, world : &World)
+    {
+        for stage in self.stages.iter_mut()
+        {
+            //no point in paying for threads for a lone system
+            if stage.systems.len() == 1
+            {
+                let (ref access, ref mut system) = stage.systems[0];
+                system::run_system(access, &mut **system, world);
+                continue;
+            }
+
+            self.jobs.scope(|scope|
+            {
+                for &mut (ref access, ref mut system) in stage.systems.iter_mut()
+                {
+                    scope.spawn(move || system::run_system(access, &mut **system, world));
+                }
+            });
+        }
+    }
+
+    pub fn stage_count(&self) -> usize
+    {
+        self.stages.len()
+    }
+}
+
+#[cfg(test)]
+mod tests
+{
+    use ecs::scheduler::Scheduler;
+    use ecs::system::System;
+    use ecs::system::SystemAccess;
+    use ecs::world::World;
+    use jobs::job_system::JobSystem;
+
+    use std::sync::Arc;
+    use std::sync::Mutex;
+
+    struct Position;
+    struct Velocity;
+
+    //declares access without touching anything, records its id when it runs
+    struct Declares
+    {
+        access : SystemAccess,
+        id : u32,
+        ran : Arc<Mutex<Vec<u32>>>
+    }
+
+    impl System for Declares
+    {
+        fn access(&self) -> SystemAccess
+        {
+            self.access.clone()
+        }
+
+        fn run(&mut self, _world : &World)
+        {
+            self.ran.lock().unwrap().push(self.id);
+        }
+    }
+
+    fn declares(access : SystemAccess, id : u32, ran : &Arc<Mutex<Vec<u32>>>) -> Declares
+    {
+        Declares{access, id, ran : ran.clone()}
+    }
+
+    #[test]
+    fn writers_of_the_same_component_get_separate_stages()
+    {
+        let ran = Arc::new(Mutex::new(Vec::new()));
+        let mut scheduler = Scheduler::new(Arc::new(JobSystem::single_threaded()));
+
+        scheduler.add_system(declares(SystemAccess::new().write::<Position>(), 1, &ran));
+        scheduler.add_system(declares(SystemAccess::new().write::<Position>().read::<Velocity>(), 2, &ran));
+
+        assert_eq!(scheduler.stage_count(), 2);
+    }
+
+    #[test]
+    fn readers_share_a_stage()
+    {
+        let ran = Arc::new(Mutex::new(Vec::new()));
+        let mut scheduler = Scheduler::new(Arc::new(JobSystem::single_threaded()));
+
+        for id in 0 .. 3
+        {
+            scheduler.add_system(declares(SystemAccess::new().read::<Position>().read::<Velocity>(), id, &ran));
+        }
+
+        assert_eq!(scheduler.stage_count(), 1);
+    }
+
+    #[test]
+    fn systems_go_in_the_stage_after_their_last_conflict()
+    {
+        let ran = Arc::new(Mutex::new(Vec::new()));
+        let mut scheduler = Scheduler::new(Arc::new(JobSystem::single_threaded()));
+
+        scheduler.add_system(declares(SystemAccess::new().write::<Position>(), 1, &ran));
+        scheduler.add_system(declares(SystemAccess::new().read::<Position>(), 2, &ran));
+        //conflicts with neither, so it joins the first stage
+        scheduler.add_system(declares(SystemAccess::new().write::<Velocity>(), 3, &ran));
+
+        assert_eq!(scheduler.stage_count(), 2);
+
+        scheduler.run_systems(&World::new());
+
+        //the writer of positions runs before their reader, the velocity writer somewhere in the first stage
+        let ran = ran.lock().unwrap();
+        assert_eq!(ran.len(), 3);
+        assert_eq!(ran[2], 2);
+    }
+}
@@ -0,0 +1,153 @@
+use ecs::entity::Entity;
+
+use std::any::Any;
+use std::vec::Vec;
+
+pub trait Component : Any + Send + Sync {}
+
+impl<T : Any + Send + Sync> Component for T {}
+
+//one component of a type per entity, indexed by the entity's index
+pub struct Storage<T : Component>
+{
+    slots : Vec<Option<(Entity, T)>>,
+    count : usize
+}
+
+impl<T : Component> Storage<T>
+{
+    pub fn new() -> Storage<T>
+    {
+        Storage
+        {
+            slots : Vec::new(),
+            count : 0
+        }
+    }
+
+    //replaces and returns the component the entity already had
+    pub fn insert(&mut self, entity : Entity, component : T) -> Option<T>
+    {
+        let index = entity.index();
+        if index >= self.slots.len()
+        {
+            let missing = index + 1 - self.slots.len();
+            self.slots.extend((0..missing).map(|_| None));
+        }
+
+        let previous = self.slots[index].take();
+        self.slots[index] = Some((entity, component));
+
+        match previous
+        {
+            //a dead entity's leftover is dropped rather than handed to the new owner of the index
+            Some((previous_entity, previous_component)) =>
+            {
+                if previous_entity == entity
+                {
+                    Some(previous_component)
+                }
+                else
+                {
+                    None
+                }
+            },
+            None =>
+            {
+                self.count += 1;
+                None
+            }
+        }
+    }
+
+    pub fn remove(&mut self, entity : Entity) -> Option<T>
+    {
+        if !self.contains(entity)
+        {
+            return None;
+        }
+
+        self.count -= 1;
+        self.slots[entity.index()].take().map(|(_, component)| component)
+    }
+
+    pub fn contains(&self, entity : Entity) -> bool
+    {
+        match self.slots.get(entity.index())
+        {
+            Some(&Some((owner, _))) => owner == entity,
+            _ => false
+        }
+    }
+
+    pub fn get(&self, entity : Entity) -> Option<&T>
+    {
+        match self.slots.get(entity.index())
+        {
+            Some(&Some((owner, ref component))) if owner == entity => Some(component),
+            _ => None
+        }
+    }
+
+    pub fn get_mut(&mut self, entity : Entity) -> Option<&mut T>
+    {
+        match self.slots.get_mut(entity.index())
+        {
+            Some(&mut Some((owner, ref mut component))) if owner == entity => Some(component),
+            _ => None
+        }
+    }
+
+    pub fn len(&self) -> usize
+    {
+        self.count
+    }
+
+    pub fn is_empty(&self) -> bool
+    {
+        self.count == 0
+    }
+
+    pub fn entities<'a>(&'a self) -> Box<Iterator<Item = Entity> + 'a>
+    {
+        Box::new(self.slots.iter().filter_map(|slot| slot.as_ref().map(|&(entity, _)| entity)))
+    }
+
+    pub fn iter<'a>(&'a self) -> Box<Iterator<Item = (Entity, &'a T)> + 'a>
+    {
+        Box::new(self.slots.iter().filter_map(|slot| slot.as_ref().map(|&(entity, ref component)| (entity, component))))
+    }
+
+    pub fn iter_mut<'a>(&'a mut self) -> Box<Iterator<Item = (Entity, &'a mut T)> + 'a>
+    {
+        Box::new(self.slots.iter_mut().filter_map(|slot| slot.as_mut().map(|&mut (entity, ref mut component)| (entity, component))))
+    }
+}
+
+//lets the world hold storages of every type side by side
+pub trait AnyStorage : Send + Sync
+{
+    fn remove_entity(&mut self, entity : Entity);
+
+    fn as_any(&self) -> &Any;
+
+    fn as_any_mut(&mut self) -> &mut Any;
+}
+
+impl<T : Component> AnyStorage for Storage<T>
+{
+    fn remove_entity(&mut self, entity : Entity)
+    {
+        self.remove(entity);
+    }
+
+    fn as_any(&self) -> &Any
+    {
+        self
+    }
+
+    fn as_any_mut(&mut self) -> &mut Any
+    {
+        self
+    }
+}
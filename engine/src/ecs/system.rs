@@ -0,0 +1,202 @@
+use ecs::storage::Component;
+use ecs::world::World;
+use vulkano_instance::PipelineImplementer;
+
+use std::any::TypeId;
+use std::cell::RefCell;
+use std::vec::Vec;
+
+thread_local!
+{
+    //the access of the system running on this thread, None outside of systems
+    static RUNNING_ACCESS : RefCell<Option<SystemAccess>> = RefCell::new(None);
+}
+
+//the component types a system reads and writes, the scheduler only runs systems side by side when these allow it
+#[derive(Debug, Clone, Default)]
+pub struct SystemAccess
+{
+    reads : Vec<TypeId>,
+    writes : Vec<TypeId>
+}
+
+impl SystemAccess
+{
+    pub fn new() -> SystemAccess
+    {
+        SystemAccess::default()
+    }
+
+    pub fn read<T : Component>(mut self) -> SystemAccess
+    {
+        self.reads.push(TypeId::of::<T>());
+        self
+    }
+
+    pub fn write<T : Component>(mut self) -> SystemAccess
+    {
+        self.writes.push(TypeId::of::<T>());
+        self
+    }
+
+    //writing a type allows reading it too
+    pub fn can_read(&self, type_id : TypeId) -> bool
+    {
+        self.reads.contains(&type_id) || self.writes.contains(&type_id)
+    }
+
+    pub fn can_write(&self, type_id : TypeId) -> bool
+    {
+        self.writes.contains(&type_id)
+    }
+
+    //reading the same types is fine, anything involving a write is not
+    pub fn conflicts_with(&self, other : &SystemAccess) -> bool
+    {
+        self.writes.iter().any(|write| other.reads.contains(write) || other.writes.contains(write))
+            || other.writes.iter().any(|write| self.reads.contains(write))
+    }
+}
+
+//game logic, may run on any thread next to the systems it does not conflict with
+pub trait System : Send
+{
+    fn access(&self) -> SystemAccess;
+
+    //only the storages and resources declared in access may be touched, World panics on anything else
+    fn run(&mut self, world : &World);
+}
+
+//runs the system with World checking every storage and resource it locks against access
+pub fn run_system(access : &SystemAccess, system : &mut System, world : &World)
+{
+    //put back even if the system panics, the thread goes on to run other jobs
+    struct RestoreAccess(Option<SystemAccess>);
+
+    impl Drop for RestoreAccess
+    {
+        fn drop(&mut self)
+        {
+            let previous = self.0.take();
+            RUNNING_ACCESS.with(|running| *running.borrow_mut() = previous);
+        }
+    }
+
+    let _restore = RestoreAccess(RUNNING_ACCESS.with(|running| running.replace(Some(access.clone()))));
+    system.run(world);
+}
+
+//panics when the system running on this thread did not declare the access, outside of systems everything is allowed
+//checking this every time is what keeps an undeclared access from only failing when another thread holds the lock
+pub fn check_access(type_id : TypeId, write : bool)
+{
+    RUNNING_ACCESS.with(|running|
+    {
+        if let Some(ref access) = *running.borrow()
+        {
+            let allowed = if write { access.can_write(type_id) } else { access.can_read(type_id) };
+            assert!(allowed, "A system used a storage or resource it did not declare in its access!");
+        }
+    });
+}
+
+//feeds the renderer, these run one after the other on the thread that owns it, after all systems
+pub trait RenderSystem
+{
+    fn render(&mut self, world : &World, renderer : &mut PipelineImplementer);
+}
+
+#[cfg(test)]
+mod tests
+{
+    use ecs::system;
+    use ecs::system::System;
+    use ecs::system::SystemAccess;
+    use ecs::world::World;
+
+    use std::panic;
+    use std::panic::AssertUnwindSafe;
+
+    struct Position;
+    struct Velocity;
+
+    //reads velocities whatever it declares
+    struct ReadsVelocity(SystemAccess);
+
+    impl System for ReadsVelocity
+    {
+        fn access(&self) -> SystemAccess
+        {
+            self.0.clone()
+        }
+
+        fn run(&mut self, world : &World)
+        {
+            let _ = world.read::<Velocity>();
+        }
+    }
+
+    fn world() -> World
+    {
+        let mut world = World::new();
+        world.register::<Position>();
+        world.register::<Velocity>();
+        world
+    }
+
+    fn run(access : SystemAccess, world : &World)
+    {
+        let mut reader = ReadsVelocity(access);
+        let access = reader.access();
+        system::run_system(&access, &mut reader, world);
+    }
+
+    #[test]
+    fn readers_do_not_conflict()
+    {
+        let first = SystemAccess::new().read::<Position>().read::<Velocity>();
+        let second = SystemAccess::new().read::<Position>();
+
+        assert!(!first.conflicts_with(&second));
+        assert!(!second.conflicts_with(&first));
+    }
+
+    #[test]
+    fn writes_conflict_with_reads_and_writes_of_the_same_type()
+    {
+        let writer = SystemAccess::new().write::<Position>();
+
+        assert!(writer.conflicts_with(&SystemAccess::new().read::<Position>()));
+        assert!(SystemAccess::new().read::<Position>().conflicts_with(&writer));
+        assert!(writer.conflicts_with(&writer));
+        assert!(!writer.conflicts_with(&SystemAccess::new().write::<Velocity>()));
+    }
+
+    #[test]
+    fn declared_access_is_allowed()
+    {
+        let world = world();
+
+        run(SystemAccess::new().read::<Velocity>(), &world);
+        run(SystemAccess::new().write::<Velocity>(), &world);
+    }
+
+    #[test]
+    #[should_panic(expected = "did not declare")]
+    fn undeclared_access_panics_even_with_nothing_else_running()
+    {
+        run(SystemAccess::new().write::<Position>(), &world());
+    }
+
+    #[test]
+    fn access_is_only_checked_inside_systems()
+    {
+        let world = world();
+
+        let result = panic::catch_unwind(AssertUnwindSafe(|| run(SystemAccess::new(), &world)));
+        assert!(result.is_err());
+
+        //the failed system's access does not stay behind on the thread
+        let _ = world.write::<Velocity>();
+    }
+}
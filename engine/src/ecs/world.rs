@@ -0,0 +1,285 @@
+use ecs::entity::Entity;
+use ecs::entity::EntityAllocator;
+use ecs::storage::AnyStorage;
+use ecs::storage::Component;
+use ecs::storage::Storage;
+use ecs::system;
+
+use std::any::Any;
+use std::any::TypeId;
+use std::collections::HashMap;
+use std::marker::PhantomData;
+use std::ops::Deref;
+use std::ops::DerefMut;
+use std::sync::RwLock;
+use std::sync::RwLockReadGuard;
+use std::sync::RwLockWriteGuard;
+
+//every entity, a storage per component type and resources that belong to no entity
+//storages and resources are locked one by one, so systems touching different types can run at the same time
+pub struct World
+{
+    entities : EntityAllocator,
+    storages : HashMap<TypeId, RwLock<Box<AnyStorage>>>,
+    resources : HashMap<TypeId, RwLock<Box<Any + Send + Sync>>>
+}
+
+impl World
+{
+    pub fn new() -> World
+    {
+        World
+        {
+            entities : EntityAllocator::new(),
+            storages : HashMap::new(),
+            resources : HashMap::new()
+        }
+    }
+
+    //adding a component registers its type too, this is for systems reading types nothing has yet
+    pub fn register<T : Component>(&mut self)
+    {
+        self.storages.entry(TypeId::of::<T>()).or_insert_with(|| RwLock::new(Box::new(Storage::<T>::new()) as Box<AnyStorage>));
+    }
+
+    pub fn create_entity(&mut self) -> Entity
+    {
+        self.entities.allocate()
+    }
+
+    //takes all of the entity's components with it
+    pub fn delete_entity(&mut self, entity : Entity)
+    {
+        if self.entities.deallocate(entity)
+        {
+            for storage in self.storages.values_mut()
+            {
+                storage.get_mut().unwrap().remove_entity(entity);
+            }
+        }
+    }
+
+    pub fn is_alive(&self, entity : Entity) -> bool
+    {
+        self.entities.is_alive(entity)
+    }
+
+    pub fn entities<'a>(&'a self) -> Box<Iterator<Item = Entity> + 'a>
+    {
+        self.entities.iter()
+    }
+
+    pub fn add_component<T : Component>(&mut self, entity : Entity, component : T) -> Option<T>
+    {
+        assert!(self.is_alive(entity), "Could not add component to a deleted entity!");
+
+        self.register::<T>();
+        self.write::<T>().insert(entity, component)
+    }
+
+    pub fn remove_component<T : Component>(&mut self, entity : Entity) -> Option<T>
+    {
+        match self.storages.get_mut(&TypeId::of::<T>())
+        {
+            Some(storage) => storage.get_mut().unwrap().as_any_mut().downcast_mut::<Storage<T>>().unwrap().remove(entity),
+            None => None
+        }
+    }
+
+    //panics inside a system that did not declare T in its access, every time and whatever else is running
+    //declared access never has to wait, the scheduler keeps conflicting systems apart, so the lock is only taken
+    //when the same thread already holds it, which is a bug and panics too
+    pub fn read<T : Component>(&self) -> ReadStorage<T>
+    {
+        system::check_access(TypeId::of::<T>(), false);
+        let storage = self.storages.get(&TypeId::of::<T>()).expect("Could not find storage, register the component type first!");
+
+        ReadStorage
+        {
+            guard : storage.try_read().expect("Could not read storage, it is being written to!"),
+            component : PhantomData
+        }
+    }
+
+    //panics like read, a system has to declare it writes T
+    pub fn write<T : Component>(&self) -> WriteStorage<T>
+    {
+        system::check_access(TypeId::of::<T>(), true);
+        let storage = self.storages.get(&TypeId::of::<T>()).expect("Could not find storage, register the component type first!");
+
+        WriteStorage
+        {
+            guard : storage.try_write().expect("Could not write storage, it is already in use!"),
+            component : PhantomData
+        }
+    }
+
+    //one value of a type for the whole world, like the frame time, declared in a system's access like components
+    pub fn insert_resource<T : Component>(&mut self, resource : T)
+    {
+        self.resources.insert(TypeId::of::<T>(), RwLock::new(Box::new(resource)));
+    }
+
+    //checked against a system's access like read and write
+    pub fn read_resource<T : Component>(&self) -> ReadResource<T>
+    {
+        system::check_access(TypeId::of::<T>(), false);
+        let resource = self.resources.get(&TypeId::of::<T>()).expect("Could not find resource, insert it first!");
+
+        ReadResource
+        {
+            guard : resource.try_read().expect("Could not read resource, it is being written to!"),
+            resource : PhantomData
+        }
+    }
+
+    pub fn write_resource<T : Component>(&self) -> WriteResource<T>
+    {
+        system::check_access(TypeId::of::<T>(), true);
+        let resource = self.resources.get(&TypeId::of::<T>()).expect("Could not find resource, insert it first!");
+
+        WriteResource
+        {
+            guard : resource.try_write().expect("Could not write resource, it is already in use!"),
+            resource : PhantomData
+        }
+    }
+}
+
+pub struct ReadStorage<'a, T : Component>
+{
+    guard : RwLockReadGuard<'a, Box<AnyStorage>>,
+    component : PhantomData<T>
+}
+
+impl<'a, T : Component> Deref for ReadStorage<'a, T>
+{
+    type Target = Storage<T>;
+
+    fn deref(&self) -> &Storage<T>
+    {
+        self.guard.as_any().downcast_ref::<Storage<T>>().unwrap()
+    }
+}
+
+pub struct WriteStorage<'a, T : Component>
+{
+    guard : RwLockWriteGuard<'a, Box<AnyStorage>>,
+    component : PhantomData<T>
+}
+
+impl<'a, T : Component> Deref for WriteStorage<'a, T>
+{
+    type Target = Storage<T>;
+
+    fn deref(&self) -> &Storage<T>
+    {
+        self.guard.as_any().downcast_ref::<Storage<T>>().unwrap()
+    }
+}
+
+impl<'a, T : Component> DerefMut for WriteStorage<'a, T>
+{
+    fn deref_mut(&mut self) -> &mut Storage<T>
+    {
+        self.guard.as_any_mut().downcast_mut::<Storage<T>>().unwrap()
+    }
+}
+
+pub struct ReadResource<'a, T : Component>
+{
+    guard : RwLockReadGuard<'a, Box<Any + Send + Sync>>,
+    resource : PhantomData<T>
+}
+
+impl<'a, T : Component> Deref for ReadResource<'a, T>
+{
+    type Target = T;
+
+    fn deref(&self) -> &T
+    {
+        self.guard.downcast_ref::<T>().unwrap()
+    }
+}
+
+pub struct WriteResource<'a, T : Component>
+{
+    guard : RwLockWriteGuard<'a, Box<Any + Send + Sync>>,
+    resource : PhantomData<T>
+}
+
+impl<'a, T : Component> Deref for WriteResource<'a, T>
+{
+    type Target = T;
+
+    fn deref(&self) -> &T
+    {
+        self.guard.downcast_ref::<T>().unwrap()
+    }
+}
+
+impl<'a, T : Component> DerefMut for WriteResource<'a, T>
+{
+    fn deref_mut(&mut self) -> &mut T
+    {
+        self.guard.downcast_mut::<T>().unwrap()
+    }
+}
+
+#[cfg(test)]
+mod tests
+{
+    use ecs::entity::Entity;
+    use ecs::join::Join;
+    use ecs::world::World;
+
+    struct Position;
+    struct Velocity;
+
+    fn joined(world : &World) -> Vec<Entity>
+    {
+        let positions = world.read::<Position>();
+        let velocities = world.read::<Velocity>();
+        let entities = (&*positions, &*velocities).join().map(|(entity, _)| entity).collect();
+        entities
+    }
+
+    fn moving_entities(world : &mut World, count : usize) -> Vec<Entity>
+    {
+        (0 .. count).map(|_|
+        {
+            let entity = world.create_entity();
+            world.add_component(entity, Position);
+            world.add_component(entity, Velocity);
+            entity
+        }).collect()
+    }
+
+    #[test]
+    fn removed_components_drop_entities_from_joins()
+    {
+        let mut world = World::new();
+        let entities = moving_entities(&mut world, 3);
+
+        assert!(world.remove_component::<Velocity>(entities[1]).is_some());
+
+        assert_eq!(joined(&world), vec![entities[0], entities[2]]);
+        assert!(world.is_alive(entities[1]));
+    }
+
+    #[test]
+    fn deleted_entities_drop_out_of_joins()
+    {
+        let mut world = World::new();
+        let entities = moving_entities(&mut world, 3);
+
+        world.delete_entity(entities[0]);
+        assert_eq!(joined(&world), vec![entities[1], entities[2]]);
+
+        //a new entity reusing the index does not inherit the deleted one's components
+        let reused = world.create_entity();
+        world.add_component(reused, Position);
+        assert_eq!(joined(&world), vec![entities[1], entities[2]]);
+        assert!(!world.is_alive(entities[0]));
+    }
+}
@@ -0,0 +1,332 @@
+use vulkano::OomError;
+use vulkano::buffer::cpu_access::ReadLockError;
+use vulkano::command_buffer::CommandBufferExecError;
+use vulkano::device::DeviceCreationError;
+use vulkano::framebuffer::FramebufferCreationError;
+use vulkano::framebuffer::RenderPassCreationError;
+use vulkano::image::ImageCreationError;
+use vulkano::instance::InstanceCreationError;
+use vulkano::memory::DeviceMemoryAllocError;
+use vulkano::pipeline::GraphicsPipelineCreationError;
+use vulkano::sampler::SamplerCreationError;
+use vulkano::swapchain::AcquireError;
+use vulkano::swapchain::CapabilitiesError;
+use vulkano::swapchain::SwapchainCreationError;
+use vulkano::sync::FlushError;
+
+use assets::gltf_import::GltfError;
+use assets::obj::ObjError;
+use textures::texture::TextureError;
+use uniforms::BlockMismatch;
+use vulkano_win_frankenstein::vulkano_win_frankenstein::CreationError as WindowCreationError;
+
+use std::boxed::Box;
+use std::error;
+use std::fmt;
+
+//everything the renderer can fail with
+#[derive(Debug)]
+pub enum EngineError
+{
+    //the window changed under the swapchain, skip the frame, the swapchain is recreated before the next one
+    SwapchainOutOfDate,
+    Instance(InstanceCreationError),
+    NoPhysicalDevice,
+    //no queue family can both draw and present to the window
+    NoGraphicsQueue,
+    Device(DeviceCreationError),
+    Window(WindowCreationError),
+    WindowClosed,
+    Surface(CapabilitiesError),
+    //the surface supports no composite alpha mode or format we could create a swapchain with
+    UnsupportedSurface,
+    Swapchain(SwapchainCreationError),
+    Acquire(AcquireError),
+    RenderPass(RenderPassCreationError),
+    Framebuffer(FramebufferCreationError),
+    Shader(OomError),
+    Pipeline(GraphicsPipelineCreationError),
+    OutOfMemory(OomError),
+    Allocation(DeviceMemoryAllocError),
+    Image(ImageCreationError),
+    Sampler(SamplerCreationError),
+    //vulkano has an error type per command, they all end up here
+    CommandBuffer(Box<error::Error + Send + Sync>),
+    Execution(CommandBufferExecError),
+    Flush(FlushError),
+    Capture(ReadLockError),
+    Texture(TextureError),
+    Obj(ObjError),
+    Gltf(GltfError),
+    //end_render was called without a begin_render before it
+    NoFrameStarted,
+    //a frame that failed did not put back the future the next one waits on
+    MissingFrameFuture,
+    //the render pass has no subpass for the drawers to draw in
+    NoSubpass,
+    //the frame ring needs at least one frame
+    NoFramesInFlight,
+    //can not be done between begin_render and end_render
+    FrameInProgress,
+    UniformBlock(BlockMismatch),
+    //a drawer recorded before the renderer uploaded the frame and view uniforms
+    NoSharedUniforms,
+    //the pool an upload arena keeps for a type is of another type
+    UploadPoolMismatch
+}
+
+impl EngineError
+{
+    //for map_err on anything that records commands
+    pub fn command_buffer<E : error::Error + Send + Sync + 'static>(err : E) -> EngineError
+    {
+        EngineError::CommandBuffer(Box::new(err))
+    }
+}
+
+impl error::Error for EngineError
+{
+    fn description(&self) -> &str
+    {
+        match *self
+        {
+            EngineError::SwapchainOutOfDate => "the swapchain is out of date",
+            EngineError::Instance(_) => "error while creating the Vulkan instance",
+            EngineError::NoPhysicalDevice => "no suitable Vulkan device was found",
+            EngineError::NoGraphicsQueue => "no queue supports graphics commands",
+            EngineError::Device(_) => "error while creating the device",
+            EngineError::Window(_) => "error while creating the window",
+            EngineError::WindowClosed => "the window no longer exists",
+            EngineError::Surface(_) => "error while querying the surface",
+            EngineError::UnsupportedSurface => "the surface supports no usable alpha mode or format",
+            EngineError::Swapchain(_) => "error while creating the swapchain",
+            EngineError::Acquire(_) => "error while acquiring a swapchain image",
+            EngineError::RenderPass(_) => "error while creating the render pass",
+            EngineError::Framebuffer(_) => "error while creating a framebuffer",
+            EngineError::Shader(_) => "error while creating a shader module",
+            EngineError::Pipeline(_) => "error while creating a pipeline",
+            EngineError::OutOfMemory(_) => "out of memory",
+            EngineError::Allocation(_) => "error while allocating device memory",
+            EngineError::Image(_) => "error while creating an image",
+            EngineError::Sampler(_) => "error while creating a sampler",
+            EngineError::CommandBuffer(_) => "error while recording commands",
+            EngineError::Execution(_) => "error while submitting commands",
+            EngineError::Flush(_) => "error while flushing commands to the gpu",
+            EngineError::Capture(_) => "error while reading back the captured frame",
+            EngineError::Texture(_) => "error while loading a texture",
+            EngineError::Obj(_) => "error while loading an obj model",
+            EngineError::Gltf(_) => "error while loading a gltf scene",
+            EngineError::NoFrameStarted => "no frame was started",
+            EngineError::MissingFrameFuture => "the previous frame left nothing to wait on",
+            EngineError::NoSubpass => "the render pass has no subpass to draw in",
+            EngineError::NoFramesInFlight => "at least one frame has to be in flight",
+            EngineError::FrameInProgress => "a frame is being recorded",
+            EngineError::UniformBlock(_) => "a shader does not lay out a uniform block like the engine",
+            EngineError::NoSharedUniforms => "the shared uniforms were not uploaded for this frame",
+            EngineError::UploadPoolMismatch => "an upload pool was stored under the wrong type"
+        }
+    }
+
+    fn cause(&self) -> Option<&error::Error>
+    {
+        match *self
+        {
+            EngineError::Instance(ref err) => Some(err),
+            EngineError::Device(ref err) => Some(err),
+            EngineError::Window(ref err) => Some(err),
+            EngineError::Surface(ref err) => Some(err),
+            EngineError::Swapchain(ref err) => Some(err),
+            EngineError::Acquire(ref err) => Some(err),
+            EngineError::RenderPass(ref err) => Some(err),
+            EngineError::Framebuffer(ref err) => Some(err),
+            EngineError::Shader(ref err) => Some(err),
+            EngineError::Pipeline(ref err) => Some(err),
+            EngineError::OutOfMemory(ref err) => Some(err),
+            EngineError::Allocation(ref err) => Some(err),
+            EngineError::Image(ref err) => Some(err),
+            EngineError::Sampler(ref err) => Some(err),
+            EngineError::CommandBuffer(ref err) => Some(&**err),
+            EngineError::Execution(ref err) => Some(err),
+            EngineError::Flush(ref err) => Some(err),
+            EngineError::Capture(ref err) => Some(err),
+            EngineError::Texture(ref err) => Some(err),
+            EngineError::Obj(ref err) => Some(err),
+            EngineError::Gltf(ref err) => Some(err),
+            EngineError::UniformBlock(ref err) => Some(err),
+            _ => None
+        }
+    }
+}
+
+impl fmt::Display for EngineError
+{
+    fn fmt(&self, fmt : &mut fmt::Formatter) -> Result<(), fmt::Error>
+    {
+        match error::Error::cause(self)
+        {
+            Some(cause) => write!(fmt, "{}: {}", error::Error::description(self), cause),
+            None => write!(fmt, "{}", error::Error::description(self))
+        }
+    }
+}
+
+impl From<InstanceCreationError> for EngineError
+{
+    fn from(err : InstanceCreationError) -> EngineError
+    {
+        EngineError::Instance(err)
+    }
+}
+
+impl From<DeviceCreationError> for EngineError
+{
+    fn from(err : DeviceCreationError) -> EngineError
+    {
+        EngineError::Device(err)
+    }
+}
+
+impl From<WindowCreationError> for EngineError
+{
+    fn from(err : WindowCreationError) -> EngineError
+    {
+        EngineError::Window(err)
+    }
+}
+
+impl From<CapabilitiesError> for EngineError
+{
+    fn from(err : CapabilitiesError) -> EngineError
+    {
+        EngineError::Surface(err)
+    }
+}
+
+impl From<SwapchainCreationError> for EngineError
+{
+    fn from(err : SwapchainCreationError) -> EngineError
+    {
+        EngineError::Swapchain(err)
+    }
+}
+
+impl From<AcquireError> for EngineError
+{
+    fn from(err : AcquireError) -> EngineError
+    {
+        match err
+        {
+            AcquireError::OutOfDate => EngineError::SwapchainOutOfDate,
+            err => EngineError::Acquire(err)
+        }
+    }
+}
+
+impl From<RenderPassCreationError> for EngineError
+{
+    fn from(err : RenderPassCreationError) -> EngineError
+    {
+        EngineError::RenderPass(err)
+    }
+}
+
+impl From<FramebufferCreationError> for EngineError
+{
+    fn from(err : FramebufferCreationError) -> EngineError
+    {
+        EngineError::Framebuffer(err)
+    }
+}
+
+impl From<GraphicsPipelineCreationError> for EngineError
+{
+    fn from(err : GraphicsPipelineCreationError) -> EngineError
+    {
+        EngineError::Pipeline(err)
+    }
+}
+
+//shader modules map theirs to EngineError::Shader by hand
+impl From<OomError> for EngineError
+{
+    fn from(err : OomError) -> EngineError
+    {
+        EngineError::OutOfMemory(err)
+    }
+}
+
+impl From<DeviceMemoryAllocError> for EngineError
+{
+    fn from(err : DeviceMemoryAllocError) -> EngineError
+    {
+        EngineError::Allocation(err)
+    }
+}
+
+impl From<ImageCreationError> for EngineError
+{
+    fn from(err : ImageCreationError) -> EngineError
+    {
+        EngineError::Image(err)
+    }
+}
+
+impl From<SamplerCreationError> for EngineError
+{
+    fn from(err : SamplerCreationError) -> EngineError
+    {
+        EngineError::Sampler(err)
+    }
+}
+
+impl From<CommandBufferExecError> for EngineError
+{
+    fn from(err : CommandBufferExecError) -> EngineError
+    {
+        EngineError::Execution(err)
+    }
+}
+
+impl From<FlushError> for EngineError
+{
+    fn from(err : FlushError) -> EngineError
+    {
+        match err
+        {
+            FlushError::OutOfDate => EngineError::SwapchainOutOfDate,
+            err => EngineError::Flush(err)
+        }
+    }
+}
+
+impl From<ReadLockError> for EngineError
+{
+    fn from(err : ReadLockError) -> EngineError
+    {
+        EngineError::Capture(err)
+    }
+}
+
+impl From<TextureError> for EngineError
+{
+    fn from(err : TextureError) -> EngineError
+    {
+        EngineError::Texture(err)
+    }
+}
+
+impl From<ObjError> for EngineError
+{
+    fn from(err : ObjError) -> EngineError
+    {
+        EngineError::Obj(err)
+    }
+}
+
+impl From<GltfError> for EngineError
+{
+    fn from(err : GltfError) -> EngineError
+    {
+        EngineError::Gltf(err)
+    }
+}
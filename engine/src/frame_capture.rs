@@ -0,0 +1,64 @@
+extern crate image;
+
+use vulkano::format::Format;
+
+use std::io;
+use std::path::Path;
+use std::vec::Vec;
+
+//a rendered frame copied back to the cpu
+pub struct CapturedFrame
+{
+    pub dimensions : [u32; 2],
+    //tightly packed rows of RGBA8, top row first
+    pub pixels : Vec<u8>
+}
+
+impl CapturedFrame
+{
+    //raw is the image memory as the gpu copied it, in the given format
+    pub fn from_raw(dimensions : [u32; 2], format : Format, raw : &[u8]) -> CapturedFrame
+    {
+        let mut pixels = raw.to_vec();
+
+        match format
+        {
+            Format::B8G8R8A8Unorm | Format::B8G8R8A8Srgb =>
+            {
+                for pixel in pixels.chunks_mut(4)
+                {
+                    pixel.swap(0, 2);
+                }
+            },
+            _ => {}
+        }
+
+        CapturedFrame
+        {
+            dimensions,
+            pixels
+        }
+    }
+
+    pub fn load_png<P : AsRef<Path>>(path : P) -> image::ImageResult<CapturedFrame>
+    {
+        let image = image::open(path)?.to_rgba();
+
+        Ok(CapturedFrame
+        {
+            dimensions : [image.width(), image.height()],
+            pixels : image.into_raw()
+        })
+    }
+
+    pub fn pixel(&self, x : u32, y : u32) -> [u8; 4]
+    {
+        let index = ((y * self.dimensions[0] + x) * 4) as usize;
+        [self.pixels[index], self.pixels[index + 1], self.pixels[index + 2], self.pixels[index + 3]]
+    }
+
+    pub fn save_png<P : AsRef<Path>>(&self, path : P) -> io::Result<()>
+    {
+        image::save_buffer(path, &self.pixels, self.dimensions[0], self.dimensions[1], image::RGBA(8))
+    }
+}
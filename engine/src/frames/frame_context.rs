@@ -0,0 +1,113 @@
+use vulkano::buffer::BufferUsage;
+use vulkano::buffer::CpuBufferPool;
+use vulkano::buffer::cpu_pool::CpuBufferPoolChunk;
+use vulkano::buffer::cpu_pool::CpuBufferPoolSubbuffer;
+use vulkano::device::Device;
+use vulkano::memory::pool::StdMemoryPool;
+
+use engine_error::EngineError;
+use uniforms::SharedUniforms;
+
+use std::any::Any;
+use std::any::TypeId;
+use std::boxed::Box;
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::sync::Mutex;
+use std::vec::Vec;
+
+pub type VertexUpload<V> = CpuBufferPoolChunk<V, Arc<StdMemoryPool>>;
+pub type UniformUpload<U> = CpuBufferPoolSubbuffer<U, Arc<StdMemoryPool>>;
+
+//one pool per uploaded type, created the first time the type is uploaded
+struct UploadArena
+{
+    device : Arc<Device>,
+    usage : BufferUsage,
+    pools : Mutex<HashMap<TypeId, Box<Any + Send + Sync>>>
+}
+
+impl UploadArena
+{
+    fn new(device : Arc<Device>, usage : BufferUsage) -> UploadArena
+    {
+        UploadArena
+        {
+            device,
+            usage,
+            pools : Mutex::new(HashMap::new())
+        }
+    }
+
+    //the lock is only held to find the pool, uploading happens outside of it
+    fn pool<T : Send + Sync + 'static>(&self) -> Result<Arc<CpuBufferPool<T>>, EngineError>
+    {
+        let mut pools = self.pools.lock().unwrap();
+
+        let pool = pools.entry(TypeId::of::<Arc<CpuBufferPool<T>>>()).or_insert_with(||
+        {
+            Box::new(Arc::new(CpuBufferPool::<T>::new(self.device.clone(), self.usage)))
+        });
+
+        pool.downcast_ref::<Arc<CpuBufferPool<T>>>().cloned().ok_or(EngineError::UploadPoolMismatch)
+    }
+}
+
+//what one frame in flight owns, handed to every drawer while it records
+//the frame ring only gives it out again once the gpu has finished the frame that last used it
+pub struct FrameContext
+{
+    vertex_arena : UploadArena,
+    uniform_arena : UploadArena,
+    //kept alive until the gpu is done with the frame they were queued in
+    destruction_queue : Mutex<Vec<Box<Any + Send>>>,
+    //uploaded by the renderer before the drawers record
+    shared_uniforms : Option<SharedUniforms>
+}
+
+impl FrameContext
+{
+    pub fn new(device : Arc<Device>) -> FrameContext
+    {
+        FrameContext
+        {
+            vertex_arena : UploadArena::new(device.clone(), BufferUsage::vertex_buffer()),
+            uniform_arena : UploadArena::new(device, BufferUsage::uniform_buffer()),
+            destruction_queue : Mutex::new(Vec::new()),
+            shared_uniforms : None
+        }
+    }
+
+    pub fn upload_vertices<V : Send + Sync + 'static>(&self, vertices : Vec<V>) -> Result<VertexUpload<V>, EngineError>
+    {
+        Ok(self.vertex_arena.pool::<V>()?.chunk(vertices)?)
+    }
+
+    pub fn upload_uniform<U : Send + Sync + 'static>(&self, uniform : U) -> Result<UniformUpload<U>, EngineError>
+    {
+        Ok(self.uniform_arena.pool::<U>()?.next(uniform)?)
+    }
+
+    pub fn set_shared_uniforms(&mut self, shared_uniforms : SharedUniforms)
+    {
+        self.shared_uniforms = Some(shared_uniforms);
+    }
+
+    //the frame and view uniforms of the frame being recorded, an error before the renderer uploaded them
+    pub fn shared_uniforms(&self) -> Result<&SharedUniforms, EngineError>
+    {
+        self.shared_uniforms.as_ref().ok_or(EngineError::NoSharedUniforms)
+    }
+
+    //for anything the frame being recorded may still use, it gets dropped once that frame has finished on the gpu
+    pub fn destroy_later<T : Send + 'static>(&self, resource : T)
+    {
+        self.destruction_queue.lock().unwrap().push(Box::new(resource));
+    }
+
+    //called by the frame ring after waiting for this frame's fence
+    pub fn destroy_queued(&self)
+    {
+        self.destruction_queue.lock().unwrap().clear();
+    }
+}
@@ -0,0 +1,112 @@
+use vulkano::device::Device;
+use vulkano::sync::FenceSignalFuture;
+use vulkano::sync::GpuFuture;
+
+use engine_error::EngineError;
+use frames::frame_context::FrameContext;
+
+use std::boxed::Box;
+use std::sync::Arc;
+use std::vec::Vec;
+
+//double buffered by default, the cpu records a frame while the gpu draws the previous one
+pub const DEFAULT_FRAMES_IN_FLIGHT : usize = 2;
+
+//signaled when the gpu has finished everything submitted for a frame
+pub type FrameFence = Arc<FenceSignalFuture<Box<GpuFuture>>>;
+
+struct FrameSlot
+{
+    context : FrameContext,
+    fence : Option<FrameFence>
+}
+
+//the frames the cpu may get ahead of the gpu, recording one blocks until the gpu is done with its previous use
+pub struct FrameRing
+{
+    slots : Vec<FrameSlot>,
+    current : usize
+}
+
+impl FrameRing
+{
+    pub fn new(device : Arc<Device>, frames_in_flight : usize) -> Result<FrameRing, EngineError>
+    {
+        if frames_in_flight == 0
+        {
+            return Err(EngineError::NoFramesInFlight);
+        }
+
+        Ok(FrameRing
+        {
+            slots : (0 .. frames_in_flight).map(|_| FrameSlot{context: FrameContext::new(device.clone()), fence: None}).collect(),
+            current : frames_in_flight - 1
+        })
+    }
+
+    pub fn frames_in_flight(&self) -> usize
+    {
+        self.slots.len()
+    }
+
+    //moves on to the next frame, waiting for the gpu to finish the last frame that used it first
+    pub fn begin_frame(&mut self) -> Result<(), EngineError>
+    {
+        self.current = (self.current + 1) % self.slots.len();
+
+        let slot = &mut self.slots[self.current];
+
+        if let Some(fence) = slot.fence.take()
+        {
+            fence.wait(None)?;
+        }
+
+        slot.context.destroy_queued();
+
+        Ok(())
+    }
+
+    pub fn current(&self) -> &FrameContext
+    {
+        &self.slots[self.current].context
+    }
+
+    pub fn current_mut(&mut self) -> &mut FrameContext
+    {
+        &mut self.slots[self.current].context
+    }
+
+    //the fence of the frame just submitted from the current slot, waited on when the slot comes around again
+    pub fn set_fence(&mut self, fence : FrameFence)
+    {
+        self.slots[self.current].fence = Some(fence);
+    }
+
+    //lets the frames that already finished drop what they were holding on to
+    pub fn cleanup_finished(&mut self)
+    {
+        for slot in self.slots.iter_mut()
+        {
+            if let Some(ref mut fence) = slot.fence
+            {
+                fence.cleanup_finished();
+            }
+        }
+    }
+
+    //blocks until every frame in flight is done, for when resources everything shares are about to change
+    pub fn wait_idle(&mut self) -> Result<(), EngineError>
+    {
+        for slot in self.slots.iter_mut()
+        {
+            if let Some(fence) = slot.fence.take()
+            {
+                fence.wait(None)?;
+            }
+
+            slot.context.destroy_queued();
+        }
+
+        Ok(())
+    }
+}
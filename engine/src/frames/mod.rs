@@ -0,0 +1,3 @@
+//outside interface
+pub mod frame_context;
+pub mod frame_ring;
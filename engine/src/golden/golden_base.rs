@@ -0,0 +1,34 @@
+use vulkano_instance::PipelineImplementer;
+use engine_error::EngineError;
+
+//how far a rendered frame may drift from its reference image
+#[derive(Clone, Copy, Debug)]
+pub struct Tolerance
+{
+    //largest allowed difference in any one color channel of a pixel
+    pub per_channel : u8,
+    //how many pixels may exceed per_channel before the test fails
+    pub max_mismatched_pixels : usize
+}
+
+impl Default for Tolerance
+{
+    fn default() -> Self
+    {
+        Tolerance{per_channel: 2, max_mismatched_pixels: 0}
+    }
+}
+
+pub trait GoldenTest
+{
+    //also the file name of the reference image
+    fn name(&self) -> &str;
+
+    //called between begin_render and end_render, must draw the same thing every time
+    fn draw_scene(&mut self, renderer : &mut PipelineImplementer) -> Result<(), EngineError>;
+
+    fn tolerance(&self) -> Tolerance
+    {
+        Tolerance::default()
+    }
+}
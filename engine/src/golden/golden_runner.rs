@@ -0,0 +1,144 @@
+use vulkano_instance::PipelineImplementer;
+use frame_capture::CapturedFrame;
+use golden::golden_base::GoldenTest;
+use golden::image_comparison;
+
+use std::fs;
+use std::path::PathBuf;
+
+pub enum GoldenResult
+{
+    Passed,
+    //blessing was asked for, so the rendered frame became the reference
+    Blessed,
+    Failed(String)
+}
+
+pub struct GoldenRunner
+{
+    tests : Vec<Box<GoldenTest>>,
+    reference_directory : PathBuf,
+    output_directory : PathBuf,
+    bless : bool
+}
+
+impl GoldenRunner
+{
+    pub fn new(tests : Vec<Box<GoldenTest>>, reference_directory : PathBuf, output_directory : PathBuf, bless : bool) -> Self
+    {
+        GoldenRunner
+        {
+            tests,
+            reference_directory,
+            output_directory,
+            bless
+        }
+    }
+
+    //runs every test and prints the outcome, returns false if any of them failed
+    pub fn run(&mut self, renderer : &mut PipelineImplementer) -> bool
+    {
+        let mut all_passed = true;
+
+        for index in 0..self.tests.len()
+        {
+            let result = self.run_test(index, renderer);
+            let name = self.tests[index].name().to_string();
+
+            match result
+            {
+                GoldenResult::Passed => println!("golden {} ... ok", name),
+                GoldenResult::Blessed => println!("golden {} ... blessed, review and commit {:?}", name, self.reference_path(&name)),
+                GoldenResult::Failed(reason) =>
+                {
+                    all_passed = false;
+                    println!("golden {} ... FAILED: {}", name, reason);
+                }
+            }
+        }
+
+        all_passed
+    }
+
+    fn run_test(&mut self, index : usize, renderer : &mut PipelineImplementer) -> GoldenResult
+    {
+        let test = &mut self.tests[index];
+
+        if let Err(err) = renderer.begin_render()
+        {
+            return GoldenResult::Failed(format!("could not begin rendering: {}", err));
+        }
+
+        //end_render still has to run, or the next test would begin rendering in the middle of this frame
+        let drawn = test.draw_scene(renderer);
+
+        renderer.capture_next_frame();
+        let rendered = renderer.end_render();
+
+        if let Err(err) = drawn.and(rendered)
+        {
+            return GoldenResult::Failed(format!("could not render: {}", err));
+        }
+
+        let actual = match renderer.take_captured_frame()
+        {
+            Some(frame) => frame,
+            None => return GoldenResult::Failed("no frame was captured".to_string())
+        };
+
+        let reference_path = self.reference_directory.join(format!("{}.png", test.name()));
+
+        if self.bless
+        {
+            if let Err(err) = fs::create_dir_all(&self.reference_directory).and_then(|_| actual.save_png(&reference_path))
+            {
+                return GoldenResult::Failed(format!("could not save reference image: {:?}", err));
+            }
+            return GoldenResult::Blessed;
+        }
+
+        //a test without a reference compares nothing, it has to be blessed and committed first
+        if !reference_path.exists()
+        {
+            return GoldenResult::Failed(format!("there is no reference image at {:?}, render one with --bless", reference_path));
+        }
+
+        let expected = match CapturedFrame::load_png(&reference_path)
+        {
+            Ok(frame) => frame,
+            Err(err) => return GoldenResult::Failed(format!("could not load reference image: {:?}", err))
+        };
+
+        let tolerance = test.tolerance();
+
+        let comparison = match image_comparison::compare_frames(&actual, &expected, tolerance)
+        {
+            Some(comparison) => comparison,
+            None => return GoldenResult::Failed(format!("rendered {:?} but the reference is {:?}", actual.dimensions, expected.dimensions))
+        };
+
+        if comparison.passes(tolerance)
+        {
+            return GoldenResult::Passed;
+        }
+
+        let actual_path = self.output_directory.join(format!("{}_actual.png", test.name()));
+        let diff_path = self.output_directory.join(format!("{}_diff.png", test.name()));
+
+        let saved = fs::create_dir_all(&self.output_directory)
+            .and_then(|_| actual.save_png(&actual_path))
+            .and_then(|_| comparison.diff.save_png(&diff_path));
+
+        if let Err(err) = saved
+        {
+            println!("Could not save golden output for {}: {:?}", test.name(), err);
+        }
+
+        GoldenResult::Failed(format!("{} pixels differ by up to {}, see {:?}", comparison.mismatched_pixels, comparison.max_difference, diff_path))
+    }
+
+    fn reference_path(&self, name : &str) -> PathBuf
+    {
+        self.reference_directory.join(format!("{}.png", name))
+    }
+}
@@ -0,0 +1,193 @@
+use vulkano_instance::PipelineImplementer;
+use engine_error::EngineError;
+use std::time::Duration;
+use std::sync::Arc;
+use std::path::Path;
+
+use benchmarks::benchmark_base::RenderBenchmark;
+use benchmarks::render_benchmarks::TriangleBenchmark;
+use golden::golden_base::GoldenTest;
+use drawers::drawer_mesh::Mesh;
+use drawers::drawer_mesh::MeshVertex;
+use math::Mat4;
+use camera::Camera;
+use assets::gltf_import;
+use scene::scene_graph::Scene;
+use textures::texture::Texture;
+
+//renders the first frame of a TriangleBenchmark, which pins down its winding and NDC mapping
+pub struct TriangleGridGoldenTest
+{
+    name : String,
+    benchmark : TriangleBenchmark
+}
+
+impl TriangleGridGoldenTest
+{
+    pub fn new(triangles_x : i32, triangles_y : i32) -> Self
+    {
+        TriangleGridGoldenTest
+        {
+            name : format!("triangle_grid_{}x{}", triangles_x, triangles_y),
+            benchmark : TriangleBenchmark::new(Duration::new(0, 0), triangles_x, triangles_y)
+        }
+    }
+}
+
+impl GoldenTest for TriangleGridGoldenTest
+{
+    fn name(&self) -> &str
+    {
+        &self.name
+    }
+
+    fn draw_scene(&mut self, renderer : &mut PipelineImplementer) -> Result<(), EngineError>
+    {
+        //a fresh benchmark sits at time zero, so the animated vertex does not move
+        self.benchmark.begin_bench();
+        self.benchmark.bench_frame(renderer)
+    }
+}
+
+//one flat colored triangle next to one with a color per point
+pub struct ColoredTriangleGoldenTest;
+
+impl GoldenTest for ColoredTriangleGoldenTest
+{
+    fn name(&self) -> &str
+    {
+        "colored_triangles"
+    }
+
+    fn draw_scene(&mut self, renderer : &mut PipelineImplementer) -> Result<(), EngineError>
+    {
+        renderer.draw_triangle_colored([[-0.9, 0.5], [-0.1, 0.5], [-0.5, -0.5]], [1.0, 0.5, 0.0, 1.0]);
+        renderer.draw_triangle_gradient([[0.1, 0.5], [0.9, 0.5], [0.5, -0.5]], [[1.0, 0.0, 0.0, 1.0], [0.0, 1.0, 0.0, 1.0], [0.0, 0.0, 1.0, 1.0]]);
+
+        Ok(())
+    }
+}
+
+//a near quad drawn before a far one that covers it, only the depth buffer keeps the near one visible
+//the camera sits at the origin looking down -z
+pub struct OverlappingMeshGoldenTest
+{
+    quads : Option<(Arc<Mesh>, Arc<Mesh>)>
+}
+
+impl OverlappingMeshGoldenTest
+{
+    pub fn new() -> Self
+    {
+        OverlappingMeshGoldenTest{quads: None}
+    }
+
+    fn quad(renderer : &mut PipelineImplementer, half_size : f32, depth : f32, normal : [f32; 3]) -> Result<Arc<Mesh>, EngineError>
+    {
+        let corner = |x : f32, y : f32| MeshVertex {position: [x * half_size, y * half_size, depth], normal, uv: [(x + 1.0) / 2.0, (1.0 - y) / 2.0]};
+
+        renderer.create_mesh(&[corner(-1.0, -1.0), corner(1.0, -1.0), corner(1.0, 1.0), corner(-1.0, 1.0)], &[0, 1, 2, 2, 3, 0])
+    }
+}
+
+impl GoldenTest for OverlappingMeshGoldenTest
+{
+    fn name(&self) -> &str
+    {
+        "overlapping_meshes"
+    }
+
+    fn draw_scene(&mut self, renderer : &mut PipelineImplementer) -> Result<(), EngineError>
+    {
+        if self.quads.is_none()
+        {
+            let near = OverlappingMeshGoldenTest::quad(renderer, 0.5, -2.0, [0.0, 0.0, 1.0])?;
+            let far = OverlappingMeshGoldenTest::quad(renderer, 4.0, -8.0, [0.0, 1.0, 0.0])?;
+            self.quads = Some((near, far));
+        }
+
+        let (ref near, ref far) = *self.quads.as_ref().unwrap();
+
+        let viewport_dimensions = renderer.camera().viewport_dimensions();
+        *renderer.camera() = Camera::new(viewport_dimensions);
+
+        renderer.draw_mesh(near.clone(), Mat4::identity());
+        renderer.draw_mesh(far.clone(), Mat4::identity());
+
+        Ok(())
+    }
+}
+
+//the bundled glb seen through the camera stored in it, covers the importer and drawing it as a scene
+pub struct GltfSceneGoldenTest
+{
+    scene : Option<Scene>
+}
+
+impl GltfSceneGoldenTest
+{
+    pub fn new() -> Self
+    {
+        GltfSceneGoldenTest{scene: None}
+    }
+}
+
+impl GoldenTest for GltfSceneGoldenTest
+{
+    fn name(&self) -> &str
+    {
+        "gltf_scene"
+    }
+
+    fn draw_scene(&mut self, renderer : &mut PipelineImplementer) -> Result<(), EngineError>
+    {
+        if self.scene.is_none()
+        {
+            let gltf_scene = gltf_import::load_gltf(concat!(env!("CARGO_MANIFEST_DIR"), "/assets/models/triangle.glb"))?;
+
+            let mut scene = Scene::new();
+            gltf_scene.instantiate(renderer, &mut scene, None)?;
+            self.scene = Some(scene);
+        }
+
+        renderer.draw_scene(self.scene.as_mut().unwrap());
+
+        Ok(())
+    }
+}
+
+//a sprite from the bundled checker png next to an untextured triangle, covers decoding, uploading and sampling
+pub struct SpriteGoldenTest
+{
+    texture : Option<Arc<Texture>>
+}
+
+impl SpriteGoldenTest
+{
+    pub fn new() -> Self
+    {
+        SpriteGoldenTest{texture: None}
+    }
+}
+
+impl GoldenTest for SpriteGoldenTest
+{
+    fn name(&self) -> &str
+    {
+        "sprite"
+    }
+
+    fn draw_scene(&mut self, renderer : &mut PipelineImplementer) -> Result<(), EngineError>
+    {
+        if self.texture.is_none()
+        {
+            let path = Path::new(concat!(env!("CARGO_MANIFEST_DIR"), "/assets/models/checker.png"));
+            self.texture = Some(renderer.load_texture(path, false)?);
+        }
+
+        renderer.draw_sprite([-0.75, -0.75], [0.0, 0.0], self.texture.clone().unwrap());
+        renderer.draw_triangle_colored([[0.25, 0.75], [0.75, 0.75], [0.5, 0.25]], [1.0, 0.5, 0.0, 1.0]);
+
+        Ok(())
+    }
+}
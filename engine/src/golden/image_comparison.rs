@@ -0,0 +1,129 @@
+use frame_capture::CapturedFrame;
+use golden::golden_base::Tolerance;
+
+use std::vec::Vec;
+
+pub struct ImageComparison
+{
+    pub mismatched_pixels : usize,
+    pub max_difference : u8,
+    //mismatched pixels in red on top of a darkened copy of the reference
+    pub diff : CapturedFrame
+}
+
+impl ImageComparison
+{
+    pub fn passes(&self, tolerance : Tolerance) -> bool
+    {
+        self.mismatched_pixels <= tolerance.max_mismatched_pixels
+    }
+}
+
+//None if the images are not the same size
+pub fn compare_frames(actual : &CapturedFrame, expected : &CapturedFrame, tolerance : Tolerance) -> Option<ImageComparison>
+{
+    if actual.dimensions != expected.dimensions
+    {
+        return None;
+    }
+
+    let mut mismatched_pixels = 0;
+    let mut max_difference = 0u8;
+    let mut diff_pixels = Vec::with_capacity(expected.pixels.len());
+
+    for (actual_pixel, expected_pixel) in actual.pixels.chunks(4).zip(expected.pixels.chunks(4))
+    {
+        let difference = actual_pixel.iter().zip(expected_pixel.iter())
+            .map(|(&a, &b)| if a > b { a - b } else { b - a })
+            .max()
+            .unwrap_or(0);
+
+        if difference > max_difference
+        {
+            max_difference = difference;
+        }
+
+        if difference > tolerance.per_channel
+        {
+            mismatched_pixels += 1;
+            diff_pixels.extend_from_slice(&[255, 0, 0, 255]);
+        }
+        else
+        {
+            let luminance = (expected_pixel[0] as u32 + expected_pixel[1] as u32 + expected_pixel[2] as u32) / 3 / 4;
+            diff_pixels.extend_from_slice(&[luminance as u8, luminance as u8, luminance as u8, 255]);
+        }
+    }
+
+    Some(ImageComparison
+    {
+        mismatched_pixels,
+        max_difference,
+        diff : CapturedFrame{dimensions: expected.dimensions, pixels: diff_pixels}
+    })
+}
+
+#[cfg(test)]
+mod tests
+{
+    use frame_capture::CapturedFrame;
+    use golden::golden_base::Tolerance;
+    use golden::image_comparison::compare_frames;
+
+    const TOLERANCE : Tolerance = Tolerance{per_channel: 2, max_mismatched_pixels: 1};
+
+    //a 2x2 frame with one color per pixel
+    fn frame(pixels : [[u8; 4]; 4]) -> CapturedFrame
+    {
+        CapturedFrame{dimensions: [2, 2], pixels: pixels.iter().flat_map(|pixel| pixel.iter().cloned()).collect()}
+    }
+
+    fn reference() -> CapturedFrame
+    {
+        frame([[0, 0, 0, 255], [200, 100, 40, 255], [12, 24, 36, 255], [255, 255, 255, 255]])
+    }
+
+    #[test]
+    fn identical_frames_match()
+    {
+        let comparison = compare_frames(&reference(), &reference(), TOLERANCE).unwrap();
+
+        assert_eq!(comparison.mismatched_pixels, 0);
+        assert_eq!(comparison.max_difference, 0);
+        assert!(comparison.passes(TOLERANCE));
+    }
+
+    #[test]
+    fn changes_within_the_per_channel_tolerance_match()
+    {
+        let actual = frame([[2, 0, 0, 255], [198, 101, 40, 255], [12, 24, 38, 255], [255, 253, 255, 255]]);
+        let comparison = compare_frames(&actual, &reference(), TOLERANCE).unwrap();
+
+        assert_eq!(comparison.mismatched_pixels, 0);
+        assert_eq!(comparison.max_difference, 2);
+        assert!(comparison.passes(TOLERANCE));
+    }
+
+    #[test]
+    fn changes_over_the_tolerance_are_marked_in_the_diff()
+    {
+        let actual = frame([[0, 0, 0, 255], [200, 100, 43, 255], [12, 24, 36, 255], [0, 255, 255, 255]]);
+        let comparison = compare_frames(&actual, &reference(), TOLERANCE).unwrap();
+
+        assert_eq!(comparison.mismatched_pixels, 2);
+        assert_eq!(comparison.max_difference, 255);
+        assert!(!comparison.passes(TOLERANCE));
+
+        //mismatches are red, the rest is the reference's darkened gray
+        assert_eq!(comparison.diff.dimensions, [2, 2]);
+        assert_eq!(comparison.diff.pixels, vec![0, 0, 0, 255, 255, 0, 0, 255, 6, 6, 6, 255, 255, 0, 0, 255]);
+    }
+
+    #[test]
+    fn frames_of_different_sizes_are_not_compared()
+    {
+        let wide = CapturedFrame{dimensions: [4, 1], pixels: reference().pixels};
+
+        assert!(compare_frames(&wide, &reference(), TOLERANCE).is_none());
+    }
+}
@@ -0,0 +1,7 @@
+//outside interface
+pub mod golden_tests;
+pub mod golden_runner;
+pub mod image_comparison;
+
+//internal use only
+mod golden_base;
@@ -0,0 +1,114 @@
+use std::boxed::Box;
+use std::sync::Condvar;
+use std::sync::Mutex;
+use std::time::Duration;
+use std::vec::Vec;
+
+//a boxed FnOnce that can be called through the box
+pub trait Task : Send
+{
+    fn run(self : Box<Self>);
+}
+
+impl<F : FnOnce() + Send> Task for F
+{
+    fn run(self : Box<Self>)
+    {
+        (*self)()
+    }
+}
+
+struct FenceState
+{
+    remaining : usize,
+    panicked : bool,
+    //run by whoever finishes the last job, used to schedule jobs that depend on this fence
+    continuations : Vec<Box<Task>>
+}
+
+//counts down as the jobs it guards finish
+pub struct Fence
+{
+    state : Mutex<FenceState>,
+    signaled : Condvar
+}
+
+impl Fence
+{
+    pub fn new(remaining : usize) -> Fence
+    {
+        Fence
+        {
+            state : Mutex::new(FenceState
+            {
+                remaining,
+                panicked : false,
+                continuations : Vec::new()
+            }),
+            signaled : Condvar::new()
+        }
+    }
+
+    pub fn add(&self, count : usize)
+    {
+        self.state.lock().unwrap().remaining += count;
+    }
+
+    pub fn complete_one(&self, panicked : bool)
+    {
+        let continuations =
+        {
+            let mut state = self.state.lock().unwrap();
+            state.remaining -= 1;
+            state.panicked |= panicked;
+
+            if state.remaining != 0
+            {
+                return;
+            }
+
+            self.signaled.notify_all();
+            state.continuations.drain(..).collect::<Vec<_>>()
+        };
+
+        for continuation in continuations
+        {
+            continuation.run();
+        }
+    }
+
+    pub fn is_complete(&self) -> bool
+    {
+        self.state.lock().unwrap().remaining == 0
+    }
+
+    pub fn panicked(&self) -> bool
+    {
+        self.state.lock().unwrap().panicked
+    }
+
+    //runs right away if the fence is already complete
+    pub fn on_complete(&self, continuation : Box<Task>)
+    {
+        {
+            let mut state = self.state.lock().unwrap();
+            if state.remaining != 0
+            {
+                state.continuations.push(continuation);
+                return;
+            }
+        }
+
+        continuation.run();
+    }
+
+    //returns early after timeout so waiting threads can go back to helping with jobs
+    pub fn wait_timeout(&self, timeout : Duration)
+    {
+        let state = self.state.lock().unwrap();
+        if state.remaining != 0
+        {
+            let _state = self.signaled.wait_timeout(state, timeout).unwrap();
+        }
+    }
+}
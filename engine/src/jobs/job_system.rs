@@ -0,0 +1,579 @@
+extern crate num_cpus;
+
+use jobs::fence::Fence;
+use jobs::fence::Task;
+
+use std::boxed::Box;
+use std::cell::Cell;
+use std::collections::VecDeque;
+use std::marker::PhantomData;
+use std::mem;
+use std::panic;
+use std::panic::AssertUnwindSafe;
+use std::sync::Arc;
+use std::sync::Condvar;
+use std::sync::Mutex;
+use std::sync::Weak;
+use std::sync::atomic::AtomicBool;
+use std::sync::atomic::AtomicUsize;
+use std::sync::atomic::Ordering;
+use std::thread;
+use std::thread::JoinHandle;
+use std::time::Duration;
+use std::vec::Vec;
+
+//how long a thread waiting on a fence sleeps before checking for jobs to help with again
+const HELP_INTERVAL_NANOSECONDS : u32 = 1_000_000;
+
+thread_local!
+{
+    //the shared state and deque index of the worker running on this thread, if it is one
+    static CURRENT_WORKER : Cell<Option<(usize, usize)>> = Cell::new(None);
+}
+
+struct Shared
+{
+    //jobs spawned from outside the pool, taken in the order they came in
+    injector : Mutex<VecDeque<Box<Task>>>,
+    //every worker pushes and pops its own deque at the back, others steal from the front
+    deques : Vec<Mutex<VecDeque<Box<Task>>>>,
+    queued_jobs : AtomicUsize,
+    sleep_lock : Mutex<()>,
+    job_available : Condvar,
+    shutdown : AtomicBool
+}
+
+impl Shared
+{
+    fn id(&self) -> usize
+    {
+        self as *const Shared as usize
+    }
+
+    fn worker_index(&self) -> Option<usize>
+    {
+        CURRENT_WORKER.with(|current| match current.get()
+        {
+            Some((id, index)) if id == self.id() => Some(index),
+            _ => None
+        })
+    }
+
+    fn push(&self, job : Box<Task>)
+    {
+        //counted before it can be taken, so the count never drops below zero
+        self.queued_jobs.fetch_add(1, Ordering::SeqCst);
+
+        match self.worker_index()
+        {
+            Some(index) => self.deques[index].lock().unwrap().push_back(job),
+            None => self.injector.lock().unwrap().push_back(job)
+        }
+
+        let _sleep_lock = self.sleep_lock.lock().unwrap();
+        self.job_available.notify_one();
+    }
+
+    fn find_job(&self) -> Option<Box<Task>>
+    {
+        let worker_index = self.worker_index();
+
+        let job = worker_index.and_then(|index| self.deques[index].lock().unwrap().pop_back())
+            .or_else(|| self.injector.lock().unwrap().pop_front())
+            .or_else(||
+            {
+                //start stealing at the next worker so thieves spread out
+                let start = worker_index.map_or(0, |index| index + 1);
+                (0..self.deques.len()).map(|offset| (start + offset) % self.deques.len())
+                    .filter(|&index| Some(index) != worker_index)
+                    .filter_map(|index| self.deques[index].lock().unwrap().pop_front())
+                    .next()
+            });
+
+        if job.is_some()
+        {
+            self.queued_jobs.fetch_sub(1, Ordering::SeqCst);
+        }
+
+        job
+    }
+
+    //runs queued jobs on this thread until the fence is done
+    fn help_until(&self, fence : &Fence)
+    {
+        while !fence.is_complete()
+        {
+            match self.find_job()
+            {
+                Some(job) => job.run(),
+                None => fence.wait_timeout(Duration::new(0, HELP_INTERVAL_NANOSECONDS))
+            }
+        }
+    }
+}
+
+fn worker_loop(shared : Arc<Shared>, index : usize)
+{
+    CURRENT_WORKER.with(|current| current.set(Some((shared.id(), index))));
+
+    loop
+    {
+        if let Some(job) = shared.find_job()
+        {
+            job.run();
+            continue;
+        }
+
+        let sleep_lock = shared.sleep_lock.lock().unwrap();
+
+        if shared.shutdown.load(Ordering::SeqCst)
+        {
+            return;
+        }
+
+        //checked under the lock pushers notify under, so a wake up can not slip in between
+        if shared.queued_jobs.load(Ordering::SeqCst) == 0
+        {
+            let _sleep_lock = shared.job_available.wait(sleep_lock).unwrap();
+        }
+    }
+}
+
+//wraps a job so its fence is signaled however it ends, a panic is recorded instead of taking the worker down
+fn fenced_job<'a, F : FnOnce() + Send + 'a>(fence : Arc<Fence>, job : F) -> Box<Task + 'a>
+{
+    Box::new(move ||
+    {
+        let panicked = panic::catch_unwind(AssertUnwindSafe(job)).is_err();
+        fence.complete_one(panicked);
+    })
+}
+
+//finishes when the job it was returned for does
+#[derive(Clone)]
+pub struct JobHandle
+{
+    fence : Arc<Fence>,
+    shared : Arc<Shared>
+}
+
+impl JobHandle
+{
+    pub fn is_complete(&self) -> bool
+    {
+        self.fence.is_complete()
+    }
+
+    //runs other jobs while waiting, so it is fine to call from inside a job
+    //panics if the job or any job it was spawned after did
+    pub fn wait(&self)
+    {
+        self.shared.help_until(&self.fence);
+
+        if self.fence.panicked()
+        {
+            panic!("A job panicked!");
+        }
+    }
+}
+
+//a pool of worker threads that steal work from each other
+//with no workers every job runs on whichever thread waits for it, in the order they were spawned
+pub struct JobSystem
+{
+    shared : Arc<Shared>,
+    workers : Vec<JoinHandle<()>>
+}
+
+impl JobSystem
+{
+    pub fn new(worker_count : usize) -> JobSystem
+    {
+        let shared = Arc::new(Shared
+        {
+            injector : Mutex::new(VecDeque::new()),
+            deques : (0..worker_count).map(|_| Mutex::new(VecDeque::new())).collect(),
+            queued_jobs : AtomicUsize::new(0),
+            sleep_lock : Mutex::new(()),
+            job_available : Condvar::new(),
+            shutdown : AtomicBool::new(false)
+        });
+
+        let workers = (0..worker_count).map(|index|
+        {
+            let shared = shared.clone();
+            thread::Builder::new()
+                .name(format!("job worker {}", index))
+                .spawn(move || worker_loop(shared, index))
+                .expect("Could not spawn job worker thread!")
+        }).collect();
+
+        JobSystem
+        {
+            shared,
+            workers
+        }
+    }
+
+    //a worker per core, leaving one for the thread that owns the job system
+    pub fn with_default_workers() -> JobSystem
+    {
+        JobSystem::new(num_cpus::get().max(2) - 1)
+    }
+
+    //deterministic, nothing runs until something is waited on
+    pub fn single_threaded() -> JobSystem
+    {
+        JobSystem::new(0)
+    }
+
+    pub fn worker_count(&self) -> usize
+    {
+        self.workers.len()
+    }
+
+    pub fn spawn<F : FnOnce() + Send + 'static>(&self, job : F) -> JobHandle
+    {
+        let fence = Arc::new(Fence::new(1));
+        self.shared.push(fenced_job(fence.clone(), job));
+
+        JobHandle
+        {
+            fence,
+            shared : self.shared.clone()
+        }
+    }
+
+    //the job is only queued once every dependency has finished
+    //if one of them panicked the job is dropped without running and its handle counts as panicked too
+    pub fn spawn_after<F : FnOnce() + Send + 'static>(&self, dependencies : &[JobHandle], job : F) -> JobHandle
+    {
+        if dependencies.is_empty()
+        {
+            return self.spawn(job);
+        }
+
+        let fence = Arc::new(Fence::new(1));
+        let job = Arc::new(Mutex::new(Some(fenced_job(fence.clone(), job))));
+        let remaining = Arc::new(AtomicUsize::new(dependencies.len()));
+        let dependency_panicked = Arc::new(AtomicBool::new(false));
+
+        for dependency in dependencies
+        {
+            let shared = self.shared.clone();
+            let fence = fence.clone();
+            let job = job.clone();
+            let remaining = remaining.clone();
+            let dependency_panicked = dependency_panicked.clone();
+            //weak so the continuation stored in the dependency's fence does not keep that fence alive
+            let dependency_fence : Weak<Fence> = Arc::downgrade(&dependency.fence);
+
+            dependency.fence.on_complete(Box::new(move ||
+            {
+                if dependency_fence.upgrade().map_or(false, |dependency_fence| dependency_fence.panicked())
+                {
+                    dependency_panicked.store(true, Ordering::SeqCst);
+                }
+
+                if remaining.fetch_sub(1, Ordering::SeqCst) == 1
+                {
+                    let job = job.lock().unwrap().take().unwrap();
+
+                    if dependency_panicked.load(Ordering::SeqCst)
+                    {
+                        //signaled right away, which fails whatever was spawned after this job as well
+                        drop(job);
+                        fence.complete_one(true);
+                    }
+                    else
+                    {
+                        shared.push(job);
+                    }
+                }
+            }));
+        }
+
+        JobHandle
+        {
+            fence,
+            shared : self.shared.clone()
+        }
+    }
+
+    //finishes once all of the handles have
+    pub fn join(&self, handles : &[JobHandle]) -> JobHandle
+    {
+        self.spawn_after(handles, || {})
+    }
+
+    //jobs spawned on the scope may borrow anything that outlives it, the scope waits for all of them before returning
+    pub fn scope<'scope, F, R>(&'scope self, body : F) -> R
+        where F : FnOnce(&Scope<'scope>) -> R
+    {
+        //starts at one for the body itself, so the fence can not complete while jobs are still being spawned
+        let scope = Scope
+        {
+            shared : &self.shared,
+            fence : Arc::new(Fence::new(1)),
+            marker : PhantomData
+        };
+
+        let result = panic::catch_unwind(AssertUnwindSafe(|| body(&scope)));
+
+        scope.fence.complete_one(false);
+        self.shared.help_until(&scope.fence);
+
+        match result
+        {
+            Ok(result) =>
+            {
+                if scope.fence.panicked()
+                {
+                    panic!("A scoped job panicked!");
+                }
+                result
+            },
+            Err(payload) => panic::resume_unwind(payload)
+        }
+    }
+
+    //calls job for every item with its index, chunk_size items per job
+    pub fn parallel_for<T, F>(&self, items : &mut [T], chunk_size : usize, job : F)
+        where T : Send, F : Fn(usize, &mut T) + Sync
+    {
+        let chunk_size = chunk_size.max(1);
+        let job = &job;
+
+        self.scope(|scope|
+        {
+            for (chunk_index, chunk) in items.chunks_mut(chunk_size).enumerate()
+            {
+                scope.spawn(move ||
+                {
+                    for (offset, item) in chunk.iter_mut().enumerate()
+                    {
+                        job(chunk_index * chunk_size + offset, item);
+                    }
+                });
+            }
+        });
+    }
+}
+
+impl Drop for JobSystem
+{
+    fn drop(&mut self)
+    {
+        {
+            let _sleep_lock = self.shared.sleep_lock.lock().unwrap();
+            self.shared.shutdown.store(true, Ordering::SeqCst);
+            self.shared.job_available.notify_all();
+        }
+
+        for worker in self.workers.drain(..)
+        {
+            let _ = worker.join();
+        }
+    }
+}
+
+pub struct Scope<'scope>
+{
+    shared : &'scope Arc<Shared>,
+    fence : Arc<Fence>,
+    //invariant, so the scope can not be shortened to accept jobs borrowing less
+    marker : PhantomData<&'scope mut &'scope ()>
+}
+
+impl<'scope> Scope<'scope>
+{
+    pub fn spawn<F : FnOnce() + Send + 'scope>(&self, job : F)
+    {
+        self.fence.add(1);
+
+        let job = fenced_job(self.fence.clone(), job);
+
+        //JobSystem::scope waits for this job before 'scope ends, so nothing it borrows can go away under it
+        let job = unsafe { mem::transmute::<Box<Task + 'scope>, Box<Task + 'static>>(job) };
+
+        self.shared.push(job);
+    }
+}
+
+#[cfg(test)]
+mod tests
+{
+    use jobs::job_system::JobSystem;
+
+    use std::panic;
+    use std::panic::AssertUnwindSafe;
+    use std::sync::Arc;
+    use std::sync::Mutex;
+    use std::sync::atomic::AtomicUsize;
+    use std::sync::atomic::Ordering;
+
+    fn record(order : &Arc<Mutex<Vec<u32>>>, value : u32) -> impl FnOnce() + Send + 'static
+    {
+        let order = order.clone();
+        move || order.lock().unwrap().push(value)
+    }
+
+    fn panics<F : FnOnce()>(body : F) -> bool
+    {
+        panic::catch_unwind(AssertUnwindSafe(body)).is_err()
+    }
+
+    #[test]
+    fn single_threaded_runs_nothing_until_waited_on()
+    {
+        let jobs = JobSystem::single_threaded();
+        let order = Arc::new(Mutex::new(Vec::new()));
+
+        let first = jobs.spawn(record(&order, 1));
+        let second = jobs.spawn(record(&order, 2));
+
+        assert_eq!(jobs.worker_count(), 0);
+        assert!(!first.is_complete());
+        assert!(order.lock().unwrap().is_empty());
+
+        second.wait();
+        assert!(first.is_complete());
+        assert_eq!(*order.lock().unwrap(), vec![1, 2]);
+    }
+
+    #[test]
+    fn spawn_after_is_only_queued_once_its_dependencies_finish()
+    {
+        let jobs = JobSystem::single_threaded();
+        let order = Arc::new(Mutex::new(Vec::new()));
+
+        let first = jobs.spawn(record(&order, 1));
+        let second = jobs.spawn(record(&order, 2));
+        let after_both = jobs.spawn_after(&[first.clone(), second.clone()], record(&order, 3));
+        let unrelated = jobs.spawn(record(&order, 4));
+
+        after_both.wait();
+
+        //4 was spawned later but queued earlier, 3 only went into the queue when 2 finished
+        assert_eq!(*order.lock().unwrap(), vec![1, 2, 4, 3]);
+        assert!(unrelated.is_complete());
+
+        //a dependency that is already done queues the job right away
+        let late = jobs.spawn_after(&[first], record(&order, 5));
+        late.wait();
+        assert_eq!(order.lock().unwrap().last(), Some(&5));
+    }
+
+    #[test]
+    fn join_waits_for_every_handle()
+    {
+        let jobs = JobSystem::single_threaded();
+        let counter = Arc::new(AtomicUsize::new(0));
+
+        let handles : Vec<_> = (0..5).map(|_|
+        {
+            let counter = counter.clone();
+            jobs.spawn(move || { counter.fetch_add(1, Ordering::SeqCst); })
+        }).collect();
+
+        let joined = jobs.join(&handles);
+        assert!(!joined.is_complete());
+
+        joined.wait();
+        assert_eq!(counter.load(Ordering::SeqCst), 5);
+        assert!(handles.iter().all(|handle| handle.is_complete()));
+
+        jobs.join(&[]).wait();
+    }
+
+    #[test]
+    fn scoped_jobs_borrow_from_the_stack()
+    {
+        let jobs = JobSystem::single_threaded();
+        let mut values = vec![1, 2, 3, 4];
+        let total = AtomicUsize::new(0);
+
+        let returned = jobs.scope(|scope|
+        {
+            for value in values.iter_mut()
+            {
+                let total = &total;
+                scope.spawn(move ||
+                {
+                    *value *= 10;
+                    total.fetch_add(*value, Ordering::SeqCst);
+                });
+            }
+            "done"
+        });
+
+        assert_eq!(returned, "done");
+        assert_eq!(values, vec![10, 20, 30, 40]);
+        assert_eq!(total.load(Ordering::SeqCst), 100);
+    }
+
+    #[test]
+    fn parallel_for_visits_every_item_once()
+    {
+        let jobs = JobSystem::single_threaded();
+
+        for &chunk_size in [0, 1, 7, 10, 200].iter()
+        {
+            let mut items = vec![0usize; 103];
+            jobs.parallel_for(&mut items, chunk_size, |index, item| *item += index + 1);
+
+            assert!(items.iter().enumerate().all(|(index, &item)| item == index + 1), "chunk size {}", chunk_size);
+        }
+
+        let mut empty : Vec<usize> = Vec::new();
+        jobs.parallel_for(&mut empty, 4, |_, _| panic!("There are no items!"));
+    }
+
+    #[test]
+    fn panics_reach_whoever_waits()
+    {
+        let jobs = JobSystem::single_threaded();
+
+        let failing = jobs.spawn(|| panic!("Job failed on purpose"));
+        assert!(panics(|| failing.wait()));
+        assert!(failing.is_complete());
+
+        //the system keeps working after a job panicked
+        let counter = Arc::new(AtomicUsize::new(0));
+        let counting = counter.clone();
+        jobs.spawn(move || { counting.fetch_add(1, Ordering::SeqCst); }).wait();
+        assert_eq!(counter.load(Ordering::SeqCst), 1);
+
+        assert!(panics(|| jobs.scope(|scope| scope.spawn(|| panic!("Scoped job failed on purpose")))));
+        assert!(panics(|| jobs.parallel_for(&mut [0, 1, 2], 1, |index, _| if index == 1 { panic!("Item failed on purpose") })));
+    }
+
+    #[test]
+    fn jobs_after_a_panicked_job_do_not_run()
+    {
+        let jobs = JobSystem::single_threaded();
+        let ran = Arc::new(AtomicUsize::new(0));
+
+        let failing = jobs.spawn(|| panic!("Job failed on purpose"));
+        let fine = jobs.spawn(|| {});
+
+        let ran_dependent = ran.clone();
+        let dependent = jobs.spawn_after(&[fine.clone(), failing.clone()], move || { ran_dependent.fetch_add(1, Ordering::SeqCst); });
+        let ran_transitive = ran.clone();
+        let transitive = jobs.spawn_after(&[dependent.clone()], move || { ran_transitive.fetch_add(1, Ordering::SeqCst); });
+        let joined = jobs.join(&[fine.clone(), failing.clone()]);
+
+        assert!(panics(|| transitive.wait()));
+        assert!(dependent.is_complete());
+        assert!(panics(|| dependent.wait()));
+        assert!(panics(|| joined.wait()));
+        assert_eq!(ran.load(Ordering::SeqCst), 0);
+
+        //failing after the fact still propagates
+        let already_failed = jobs.spawn_after(&[failing], || {});
+        assert!(already_failed.is_complete());
+        assert!(panics(|| already_failed.wait()));
+
+        fine.wait();
+    }
+}
@@ -0,0 +1,5 @@
+//outside interface
+pub mod job_system;
+
+//internal use only
+mod fence;
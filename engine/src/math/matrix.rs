@@ -0,0 +1,519 @@
+use std::ops::Mul;
+
+use math::Vec3;
+use math::Vec4;
+use math::Quat;
+
+//all matrices are column major, matching glsl, and columns[i] is the i:th column
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Mat3
+{
+    pub columns : [Vec3; 3]
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Mat4
+{
+    pub columns : [Vec4; 4]
+}
+
+impl Mat3
+{
+    pub fn from_columns(x : Vec3, y : Vec3, z : Vec3) -> Mat3
+    {
+        Mat3{columns: [x, y, z]}
+    }
+
+    pub fn identity() -> Mat3
+    {
+        Mat3::from_columns(Vec3::unit_x(), Vec3::unit_y(), Vec3::unit_z())
+    }
+
+    pub fn from_scale(scale : Vec3) -> Mat3
+    {
+        Mat3::from_columns(Vec3::new(scale.x, 0.0, 0.0), Vec3::new(0.0, scale.y, 0.0), Vec3::new(0.0, 0.0, scale.z))
+    }
+
+    pub fn from_quat(rotation : Quat) -> Mat3
+    {
+        let Quat{x, y, z, w} = rotation;
+
+        Mat3::from_columns(
+            Vec3::new(1.0 - 2.0 * (y * y + z * z), 2.0 * (x * y + z * w), 2.0 * (x * z - y * w)),
+            Vec3::new(2.0 * (x * y - z * w), 1.0 - 2.0 * (x * x + z * z), 2.0 * (y * z + x * w)),
+            Vec3::new(2.0 * (x * z + y * w), 2.0 * (y * z - x * w), 1.0 - 2.0 * (x * x + y * y)))
+    }
+
+    pub fn row(&self, index : usize) -> Vec3
+    {
+        Vec3::new(self.columns[0][index], self.columns[1][index], self.columns[2][index])
+    }
+
+    pub fn transpose(&self) -> Mat3
+    {
+        Mat3::from_columns(self.row(0), self.row(1), self.row(2))
+    }
+
+    pub fn determinant(&self) -> f32
+    {
+        self.columns[0].dot(self.columns[1].cross(self.columns[2]))
+    }
+
+    //None if the matrix is singular
+    pub fn inverse(&self) -> Option<Mat3>
+    {
+        let determinant = self.determinant();
+        if determinant == 0.0
+        {
+            return None;
+        }
+
+        let [a, b, c] = self.columns;
+
+        //rows of the inverse are the cross products of the columns
+        Some(Mat3::from_columns(b.cross(c), c.cross(a), a.cross(b)).transpose() * (1.0 / determinant))
+    }
+
+    pub fn approx_eq(&self, other : &Mat3) -> bool
+    {
+        self.columns.iter().zip(other.columns.iter()).all(|(a, b)| a.approx_eq(*b))
+    }
+}
+
+impl Mat4
+{
+    pub fn from_columns(x : Vec4, y : Vec4, z : Vec4, w : Vec4) -> Mat4
+    {
+        Mat4{columns: [x, y, z, w]}
+    }
+
+    pub fn identity() -> Mat4
+    {
+        Mat4::from_columns(
+            Vec4::new(1.0, 0.0, 0.0, 0.0),
+            Vec4::new(0.0, 1.0, 0.0, 0.0),
+            Vec4::new(0.0, 0.0, 1.0, 0.0),
+            Vec4::new(0.0, 0.0, 0.0, 1.0))
+    }
+
+    pub fn from_mat3(matrix : Mat3) -> Mat4
+    {
+        Mat4::from_columns(
+            matrix.columns[0].extend(0.0),
+            matrix.columns[1].extend(0.0),
+            matrix.columns[2].extend(0.0),
+            Vec4::new(0.0, 0.0, 0.0, 1.0))
+    }
+
+    pub fn from_translation(translation : Vec3) -> Mat4
+    {
+        let mut matrix = Mat4::identity();
+        matrix.columns[3] = translation.extend(1.0);
+        matrix
+    }
+
+    pub fn from_scale(scale : Vec3) -> Mat4
+    {
+        Mat4::from_mat3(Mat3::from_scale(scale))
+    }
+
+    pub fn from_quat(rotation : Quat) -> Mat4
+    {
+        Mat4::from_mat3(Mat3::from_quat(rotation))
+    }
+
+    pub fn from_axis_angle(axis : Vec3, radians : f32) -> Mat4
+    {
+        Mat4::from_quat(Quat::from_axis_angle(axis, radians))
+    }
+
+    //scale first, then rotation, then translation
+    pub fn from_translation_rotation_scale(translation : Vec3, rotation : Quat, scale : Vec3) -> Mat4
+    {
+        let rotation = Mat3::from_quat(rotation);
+
+        Mat4::from_columns(
+            (rotation.columns[0] * scale.x).extend(0.0),
+            (rotation.columns[1] * scale.y).extend(0.0),
+            (rotation.columns[2] * scale.z).extend(0.0),
+            translation.extend(1.0))
+    }
+
+    //right handed view space looking down -z, into vulkan clip space: y points down and depth goes from 0 at near to 1 at far
+    pub fn perspective(vertical_fov_radians : f32, aspect : f32, near : f32, far : f32) -> Mat4
+    {
+        let focal_length = 1.0 / (vertical_fov_radians / 2.0).tan();
+
+        Mat4::from_columns(
+            Vec4::new(focal_length / aspect, 0.0, 0.0, 0.0),
+            Vec4::new(0.0, -focal_length, 0.0, 0.0),
+            Vec4::new(0.0, 0.0, far / (near - far), -1.0),
+            Vec4::new(0.0, 0.0, near * far / (near - far), 0.0))
+    }
+
+    //same conventions as perspective, top is the view space y that ends up at the top of the screen
+    pub fn orthographic(left : f32, right : f32, bottom : f32, top : f32, near : f32, far : f32) -> Mat4
+    {
+        Mat4::from_columns(
+            Vec4::new(2.0 / (right - left), 0.0, 0.0, 0.0),
+            Vec4::new(0.0, -2.0 / (top - bottom), 0.0, 0.0),
+            Vec4::new(0.0, 0.0, -1.0 / (far - near), 0.0),
+            Vec4::new(-(right + left) / (right - left), (top + bottom) / (top - bottom), -near / (far - near), 1.0))
+    }
+
+    //right handed view matrix, the eye ends up at the origin looking down -z
+    pub fn look_at(eye : Vec3, target : Vec3, up : Vec3) -> Mat4
+    {
+        let forward = (target - eye).normalize();
+        let side = forward.cross(up).normalize();
+        let up = side.cross(forward);
+
+        Mat4::from_columns(
+            Vec4::new(side.x, up.x, -forward.x, 0.0),
+            Vec4::new(side.y, up.y, -forward.y, 0.0),
+            Vec4::new(side.z, up.z, -forward.z, 0.0),
+            Vec4::new(-side.dot(eye), -up.dot(eye), forward.dot(eye), 1.0))
+    }
+
+    pub fn row(&self, index : usize) -> Vec4
+    {
+        Vec4::new(self.columns[0][index], self.columns[1][index], self.columns[2][index], self.columns[3][index])
+    }
+
+    pub fn transpose(&self) -> Mat4
+    {
+        Mat4::from_columns(self.row(0), self.row(1), self.row(2), self.row(3))
+    }
+
+    pub fn translation(&self) -> Vec3
+    {
+        self.columns[3].truncate()
+    }
+
+    //upper left 3x3, drops the translation
+    pub fn to_mat3(&self) -> Mat3
+    {
+        Mat3::from_columns(self.columns[0].truncate(), self.columns[1].truncate(), self.columns[2].truncate())
+    }
+
+    //for transforming normals, stays correct under non-uniform scale
+    pub fn normal_matrix(&self) -> Option<Mat3>
+    {
+        self.to_mat3().inverse().map(|inverse| inverse.transpose())
+    }
+
+    pub fn transform_point(&self, point : Vec3) -> Vec3
+    {
+        let transformed = *self * point.extend(1.0);
+        transformed.truncate() / transformed.w
+    }
+
+    pub fn transform_vector(&self, vector : Vec3) -> Vec3
+    {
+        (*self * vector.extend(0.0)).truncate()
+    }
+
+    fn to_flat(&self) -> [f32; 16]
+    {
+        let mut flat = [0.0; 16];
+        for column in 0..4
+        {
+            flat[column * 4 .. column * 4 + 4].copy_from_slice(self.columns[column].as_ref());
+        }
+        flat
+    }
+
+    fn from_flat(flat : &[f32; 16]) -> Mat4
+    {
+        Mat4::from_columns(
+            Vec4::new(flat[0], flat[1], flat[2], flat[3]),
+            Vec4::new(flat[4], flat[5], flat[6], flat[7]),
+            Vec4::new(flat[8], flat[9], flat[10], flat[11]),
+            Vec4::new(flat[12], flat[13], flat[14], flat[15]))
+    }
+
+    pub fn determinant(&self) -> f32
+    {
+        let m = self.to_flat();
+        let cofactors = Mat4::cofactors(&m);
+        m[0] * cofactors[0] + m[1] * cofactors[4] + m[2] * cofactors[8] + m[3] * cofactors[12]
+    }
+
+    //None if the matrix is singular
+    pub fn inverse(&self) -> Option<Mat4>
+    {
+        let m = self.to_flat();
+        let mut inverse = Mat4::cofactors(&m);
+
+        let determinant = m[0] * inverse[0] + m[1] * inverse[4] + m[2] * inverse[8] + m[3] * inverse[12];
+        if determinant == 0.0
+        {
+            return None;
+        }
+
+        for value in inverse.iter_mut()
+        {
+            *value /= determinant;
+        }
+
+        Some(Mat4::from_flat(&inverse))
+    }
+
+    //transposed cofactor matrix (the adjugate) by cofactor expansion
+    fn cofactors(m : &[f32; 16]) -> [f32; 16]
+    {
+        [
+            m[5] * m[10] * m[15] - m[5] * m[11] * m[14] - m[9] * m[6] * m[15] + m[9] * m[7] * m[14] + m[13] * m[6] * m[11] - m[13] * m[7] * m[10],
+            -m[1] * m[10] * m[15] + m[1] * m[11] * m[14] + m[9] * m[2] * m[15] - m[9] * m[3] * m[14] - m[13] * m[2] * m[11] + m[13] * m[3] * m[10],
+            m[1] * m[6] * m[15] - m[1] * m[7] * m[14] - m[5] * m[2] * m[15] + m[5] * m[3] * m[14] + m[13] * m[2] * m[7] - m[13] * m[3] * m[6],
+            -m[1] * m[6] * m[11] + m[1] * m[7] * m[10] + m[5] * m[2] * m[11] - m[5] * m[3] * m[10] - m[9] * m[2] * m[7] + m[9] * m[3] * m[6],
+
+            -m[4] * m[10] * m[15] + m[4] * m[11] * m[14] + m[8] * m[6] * m[15] - m[8] * m[7] * m[14] - m[12] * m[6] * m[11] + m[12] * m[7] * m[10],
+            m[0] * m[10] * m[15] - m[0] * m[11] * m[14] - m[8] * m[2] * m[15] + m[8] * m[3] * m[14] + m[12] * m[2] * m[11] - m[12] * m[3] * m[10],
+            -m[0] * m[6] * m[15] + m[0] * m[7] * m[14] + m[4] * m[2] * m[15] - m[4] * m[3] * m[14] - m[12] * m[2] * m[7] + m[12] * m[3] * m[6],
+            m[0] * m[6] * m[11] - m[0] * m[7] * m[10] - m[4] * m[2] * m[11] + m[4] * m[3] * m[10] + m[8] * m[2] * m[7] - m[8] * m[3] * m[6],
+
+            m[4] * m[9] * m[15] - m[4] * m[11] * m[13] - m[8] * m[5] * m[15] + m[8] * m[7] * m[13] + m[12] * m[5] * m[11] - m[12] * m[7] * m[9],
+            -m[0] * m[9] * m[15] + m[0] * m[11] * m[13] + m[8] * m[1] * m[15] - m[8] * m[3] * m[13] - m[12] * m[1] * m[11] + m[12] * m[3] * m[9],
+            m[0] * m[5] * m[15] - m[0] * m[7] * m[13] - m[4] * m[1] * m[15] + m[4] * m[3] * m[13] + m[12] * m[1] * m[7] - m[12] * m[3] * m[5],
+            -m[0] * m[5] * m[11] + m[0] * m[7] * m[9] + m[4] * m[1] * m[11] - m[4] * m[3] * m[9] - m[8] * m[1] * m[7] + m[8] * m[3] * m[5],
+
+            -m[4] * m[9] * m[14] + m[4] * m[10] * m[13] + m[8] * m[5] * m[14] - m[8] * m[6] * m[13] - m[12] * m[5] * m[10] + m[12] * m[6] * m[9],
+            m[0] * m[9] * m[14] - m[0] * m[10] * m[13] - m[8] * m[1] * m[14] + m[8] * m[2] * m[13] + m[12] * m[1] * m[10] - m[12] * m[2] * m[9],
+            -m[0] * m[5] * m[14] + m[0] * m[6] * m[13] + m[4] * m[1] * m[14] - m[4] * m[2] * m[13] - m[12] * m[1] * m[6] + m[12] * m[2] * m[5],
+            m[0] * m[5] * m[10] - m[0] * m[6] * m[9] - m[4] * m[1] * m[10] + m[4] * m[2] * m[9] + m[8] * m[1] * m[6] - m[8] * m[2] * m[5],
+        ]
+    }
+
+    pub fn approx_eq(&self, other : &Mat4) -> bool
+    {
+        self.columns.iter().zip(other.columns.iter()).all(|(a, b)| a.approx_eq(*b))
+    }
+}
+
+impl Mul for Mat3
+{
+    type Output = Mat3;
+
+    fn mul(self, other : Mat3) -> Mat3
+    {
+        Mat3::from_columns(self * other.columns[0], self * other.columns[1], self * other.columns[2])
+    }
+}
+
+impl Mul<Vec3> for Mat3
+{
+    type Output = Vec3;
+
+    fn mul(self, vector : Vec3) -> Vec3
+    {
+        self.columns[0] * vector.x + self.columns[1] * vector.y + self.columns[2] * vector.z
+    }
+}
+
+impl Mul<f32> for Mat3
+{
+    type Output = Mat3;
+
+    fn mul(self, scalar : f32) -> Mat3
+    {
+        Mat3::from_columns(self.columns[0] * scalar, self.columns[1] * scalar, self.columns[2] * scalar)
+    }
+}
+
+impl Mul for Mat4
+{
+    type Output = Mat4;
+
+    fn mul(self, other : Mat4) -> Mat4
+    {
+        Mat4::from_columns(self * other.columns[0], self * other.columns[1], self * other.columns[2], self * other.columns[3])
+    }
+}
+
+impl Mul<Vec4> for Mat4
+{
+    type Output = Vec4;
+
+    fn mul(self, vector : Vec4) -> Vec4
+    {
+        self.columns[0] * vector.x + self.columns[1] * vector.y + self.columns[2] * vector.z + self.columns[3] * vector.w
+    }
+}
+
+impl From<[[f32; 3]; 3]> for Mat3
+{
+    fn from(columns : [[f32; 3]; 3]) -> Mat3
+    {
+        Mat3::from_columns(columns[0].into(), columns[1].into(), columns[2].into())
+    }
+}
+
+impl From<Mat3> for [[f32; 3]; 3]
+{
+    fn from(matrix : Mat3) -> [[f32; 3]; 3]
+    {
+        [matrix.columns[0].into(), matrix.columns[1].into(), matrix.columns[2].into()]
+    }
+}
+
+impl From<[[f32; 4]; 4]> for Mat4
+{
+    fn from(columns : [[f32; 4]; 4]) -> Mat4
+    {
+        Mat4::from_columns(columns[0].into(), columns[1].into(), columns[2].into(), columns[3].into())
+    }
+}
+
+impl From<Mat4> for [[f32; 4]; 4]
+{
+    fn from(matrix : Mat4) -> [[f32; 4]; 4]
+    {
+        [matrix.columns[0].into(), matrix.columns[1].into(), matrix.columns[2].into(), matrix.columns[3].into()]
+    }
+}
+
+#[cfg(test)]
+mod tests
+{
+    use math::Mat3;
+    use math::Mat4;
+    use math::Quat;
+    use math::Vec3;
+    use math::Vec4;
+    use math::approx_eq;
+
+    use std::f32::consts::PI;
+
+    //ends up in clip space, divided by w
+    fn project(projection : Mat4, point : Vec3) -> Vec3
+    {
+        projection.transform_point(point)
+    }
+
+    #[test]
+    fn perspective_depth_goes_from_zero_at_near_to_one_at_far()
+    {
+        let projection = Mat4::perspective(PI / 2.0, 1.0, 0.1, 100.0);
+
+        assert!(approx_eq(project(projection, Vec3::new(0.0, 0.0, -0.1)).z, 0.0));
+        assert!(approx_eq(project(projection, Vec3::new(0.0, 0.0, -100.0)).z, 1.0));
+
+        let middle = project(projection, Vec3::new(0.0, 0.0, -1.0)).z;
+        assert!(middle > 0.0 && middle < 1.0);
+    }
+
+    #[test]
+    fn perspective_y_points_down()
+    {
+        //a 90 degree field of view puts the top edge at y = -z
+        let projection = Mat4::perspective(PI / 2.0, 2.0, 0.1, 100.0);
+
+        assert!(approx_eq(project(projection, Vec3::new(0.0, 1.0, -1.0)).y, -1.0));
+        assert!(approx_eq(project(projection, Vec3::new(0.0, -1.0, -1.0)).y, 1.0));
+        //x is divided by the aspect
+        assert!(approx_eq(project(projection, Vec3::new(2.0, 0.0, -1.0)).x, 1.0));
+    }
+
+    #[test]
+    fn orthographic_maps_the_box_to_clip_space()
+    {
+        let projection = Mat4::orthographic(-4.0, 4.0, -2.0, 2.0, 1.0, 11.0);
+
+        assert!(project(projection, Vec3::new(-4.0, 2.0, -1.0)).approx_eq(Vec3::new(-1.0, -1.0, 0.0)));
+        assert!(project(projection, Vec3::new(4.0, -2.0, -11.0)).approx_eq(Vec3::new(1.0, 1.0, 1.0)));
+        assert!(project(projection, Vec3::new(0.0, 0.0, -6.0)).approx_eq(Vec3::new(0.0, 0.0, 0.5)));
+    }
+
+    #[test]
+    fn look_at_puts_the_eye_at_the_origin_looking_down_negative_z()
+    {
+        let eye = Vec3::new(3.0, 4.0, 5.0);
+        let target = Vec3::new(3.0, 4.0, -5.0);
+        let view = Mat4::look_at(eye, target, Vec3::unit_y());
+
+        assert!(view.transform_point(eye).approx_eq(Vec3::zero()));
+        assert!(view.transform_point(target).approx_eq(Vec3::new(0.0, 0.0, -10.0)));
+        assert!(view.transform_point(eye + Vec3::unit_y()).approx_eq(Vec3::unit_y()));
+        assert!(view.transform_point(eye + Vec3::unit_x()).approx_eq(Vec3::unit_x()));
+    }
+
+    #[test]
+    fn look_at_from_the_side()
+    {
+        //looking down -x, so world -z is to the right
+        let view = Mat4::look_at(Vec3::new(10.0, 0.0, 0.0), Vec3::zero(), Vec3::unit_y());
+
+        assert!(view.transform_point(Vec3::zero()).approx_eq(Vec3::new(0.0, 0.0, -10.0)));
+        assert!(view.transform_vector(Vec3::unit_z()).approx_eq(-Vec3::unit_x()));
+        assert!(view.transform_vector(Vec3::unit_y()).approx_eq(Vec3::unit_y()));
+    }
+
+    #[test]
+    fn inverse_round_trips()
+    {
+        let matrix = Mat4::from_translation_rotation_scale(Vec3::new(1.0, -2.0, 3.0), Quat::from_axis_angle(Vec3::new(1.0, 1.0, 0.0), 0.7), Vec3::new(2.0, 0.5, 3.0));
+        let inverse = matrix.inverse().unwrap();
+
+        assert!((matrix * inverse).approx_eq(&Mat4::identity()));
+        assert!((inverse * matrix).approx_eq(&Mat4::identity()));
+        assert!(approx_eq(matrix.determinant() * inverse.determinant(), 1.0));
+
+        let perspective = Mat4::perspective(1.0, 1.5, 0.1, 50.0);
+        assert!((perspective * perspective.inverse().unwrap()).approx_eq(&Mat4::identity()));
+    }
+
+    #[test]
+    fn singular_matrices_have_no_inverse()
+    {
+        assert_eq!(Mat4::from_scale(Vec3::new(1.0, 0.0, 1.0)).inverse(), None);
+        assert_eq!(Mat3::from_scale(Vec3::new(0.0, 1.0, 1.0)).inverse(), None);
+    }
+
+    #[test]
+    fn mat3_inverse_round_trips()
+    {
+        let matrix = Mat3::from_quat(Quat::from_axis_angle(Vec3::unit_z(), 0.3)) * Mat3::from_scale(Vec3::new(1.0, 2.0, 4.0));
+
+        assert!((matrix * matrix.inverse().unwrap()).approx_eq(&Mat3::identity()));
+        assert!(approx_eq(matrix.determinant(), 8.0));
+    }
+
+    #[test]
+    fn translation_rotation_scale_applies_scale_then_rotation_then_translation()
+    {
+        let matrix = Mat4::from_translation_rotation_scale(Vec3::new(10.0, 0.0, 0.0), Quat::from_axis_angle(Vec3::unit_z(), PI / 2.0), Vec3::new(2.0, 1.0, 1.0));
+
+        assert!(matrix.transform_point(Vec3::unit_x()).approx_eq(Vec3::new(10.0, 2.0, 0.0)));
+        assert!(matrix.translation().approx_eq(Vec3::new(10.0, 0.0, 0.0)));
+    }
+
+    #[test]
+    fn normal_matrix_keeps_normals_perpendicular()
+    {
+        let matrix = Mat4::from_scale(Vec3::new(4.0, 1.0, 1.0));
+        //a plane tilted 45 degrees between x and y
+        let tangent = Vec3::new(1.0, -1.0, 0.0);
+        let normal = Vec3::new(1.0, 1.0, 0.0);
+
+        let transformed_tangent = matrix.transform_vector(tangent);
+        let transformed_normal = matrix.normal_matrix().unwrap() * normal;
+
+        assert!(approx_eq(transformed_tangent.dot(transformed_normal), 0.0));
+        //the plain model matrix does not keep them perpendicular
+        assert!(!approx_eq(transformed_tangent.dot(matrix.transform_vector(normal)), 0.0));
+    }
+
+    #[test]
+    fn columns_are_columns()
+    {
+        let matrix = Mat4::from_translation(Vec3::new(1.0, 2.0, 3.0));
+        let array : [[f32; 4]; 4] = matrix.into();
+
+        assert_eq!(array[3], [1.0, 2.0, 3.0, 1.0]);
+        assert_eq!(matrix.row(0), Vec4::new(1.0, 0.0, 0.0, 1.0));
+        assert_eq!(matrix.transpose().columns[0], Vec4::new(1.0, 0.0, 0.0, 1.0));
+        assert_eq!(Mat4::from(array), matrix);
+    }
+}
@@ -0,0 +1,18 @@
+//outside interface
+pub mod vector;
+pub mod matrix;
+pub mod quaternion;
+
+pub use self::vector::Vec2;
+pub use self::vector::Vec3;
+pub use self::vector::Vec4;
+pub use self::matrix::Mat3;
+pub use self::matrix::Mat4;
+pub use self::quaternion::Quat;
+
+pub const EPSILON : f32 = 0.00001;
+
+pub fn approx_eq(a : f32, b : f32) -> bool
+{
+    (a - b).abs() <= EPSILON * (1.0f32).max(a.abs()).max(b.abs())
+}
@@ -0,0 +1,298 @@
+use std::ops::Mul;
+
+use math::approx_eq;
+use math::Vec3;
+use math::Mat3;
+
+//unit quaternion describing a rotation, w is the scalar part
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Quat
+{
+    pub x : f32,
+    pub y : f32,
+    pub z : f32,
+    pub w : f32
+}
+
+impl Quat
+{
+    pub fn new(x : f32, y : f32, z : f32, w : f32) -> Quat
+    {
+        Quat{x, y, z, w}
+    }
+
+    pub fn identity() -> Quat
+    {
+        Quat::new(0.0, 0.0, 0.0, 1.0)
+    }
+
+    //counter clockwise when looking down the axis towards the origin
+    pub fn from_axis_angle(axis : Vec3, radians : f32) -> Quat
+    {
+        let axis = axis.normalize();
+        let (sin, cos) = (radians / 2.0).sin_cos();
+        Quat::new(axis.x * sin, axis.y * sin, axis.z * sin, cos)
+    }
+
+    //applied in the order roll (z), pitch (x), yaw (y)
+    pub fn from_euler(pitch : f32, yaw : f32, roll : f32) -> Quat
+    {
+        Quat::from_axis_angle(Vec3::unit_y(), yaw) * Quat::from_axis_angle(Vec3::unit_x(), pitch) * Quat::from_axis_angle(Vec3::unit_z(), roll)
+    }
+
+    //the rotation a pure rotation matrix describes
+    pub fn from_mat3(matrix : Mat3) -> Quat
+    {
+        let [x_axis, y_axis, z_axis] = matrix.columns;
+        let trace = x_axis.x + y_axis.y + z_axis.z;
+
+        let quat = if trace > 0.0
+        {
+            let s = (trace + 1.0).sqrt() * 2.0;
+            Quat::new((y_axis.z - z_axis.y) / s, (z_axis.x - x_axis.z) / s, (x_axis.y - y_axis.x) / s, 0.25 * s)
+        }
+        else if x_axis.x > y_axis.y && x_axis.x > z_axis.z
+        {
+            let s = (1.0 + x_axis.x - y_axis.y - z_axis.z).sqrt() * 2.0;
+            Quat::new(0.25 * s, (y_axis.x + x_axis.y) / s, (z_axis.x + x_axis.z) / s, (y_axis.z - z_axis.y) / s)
+        }
+        else if y_axis.y > z_axis.z
+        {
+            let s = (1.0 + y_axis.y - x_axis.x - z_axis.z).sqrt() * 2.0;
+            Quat::new((y_axis.x + x_axis.y) / s, 0.25 * s, (z_axis.y + y_axis.z) / s, (z_axis.x - x_axis.z) / s)
+        }
+        else
+        {
+            let s = (1.0 + z_axis.z - x_axis.x - y_axis.y).sqrt() * 2.0;
+            Quat::new((z_axis.x + x_axis.z) / s, (z_axis.y + y_axis.z) / s, 0.25 * s, (x_axis.y - y_axis.x) / s)
+        };
+
+        quat.normalize()
+    }
+
+    pub fn dot(self, other : Quat) -> f32
+    {
+        self.x * other.x + self.y * other.y + self.z * other.z + self.w * other.w
+    }
+
+    pub fn length(self) -> f32
+    {
+        self.dot(self).sqrt()
+    }
+
+    pub fn normalize(self) -> Quat
+    {
+        let length = self.length();
+        if length == 0.0
+        {
+            return Quat::identity();
+        }
+        Quat::new(self.x / length, self.y / length, self.z / length, self.w / length)
+    }
+
+    pub fn conjugate(self) -> Quat
+    {
+        Quat::new(-self.x, -self.y, -self.z, self.w)
+    }
+
+    pub fn inverse(self) -> Quat
+    {
+        let length_squared = self.dot(self);
+        let conjugate = self.conjugate();
+        Quat::new(conjugate.x / length_squared, conjugate.y / length_squared, conjugate.z / length_squared, conjugate.w / length_squared)
+    }
+
+    pub fn rotate(self, vector : Vec3) -> Vec3
+    {
+        let axis = Vec3::new(self.x, self.y, self.z);
+        let t = axis.cross(vector) * 2.0;
+        vector + t * self.w + axis.cross(t)
+    }
+
+    //shortest path spherical interpolation
+    pub fn slerp(self, other : Quat, t : f32) -> Quat
+    {
+        let mut other = other;
+        let mut cos_theta = self.dot(other);
+
+        if cos_theta < 0.0
+        {
+            other = Quat::new(-other.x, -other.y, -other.z, -other.w);
+            cos_theta = -cos_theta;
+        }
+
+        //nearly parallel, fall back to lerp to avoid dividing by sin(0)
+        if cos_theta > 0.9995
+        {
+            return Quat::new(
+                self.x + (other.x - self.x) * t,
+                self.y + (other.y - self.y) * t,
+                self.z + (other.z - self.z) * t,
+                self.w + (other.w - self.w) * t).normalize();
+        }
+
+        let theta = cos_theta.acos();
+        let sin_theta = theta.sin();
+        let a = ((1.0 - t) * theta).sin() / sin_theta;
+        let b = (t * theta).sin() / sin_theta;
+
+        Quat::new(
+            self.x * a + other.x * b,
+            self.y * a + other.y * b,
+            self.z * a + other.z * b,
+            self.w * a + other.w * b)
+    }
+
+    //q and -q are the same rotation
+    pub fn approx_eq(self, other : Quat) -> bool
+    {
+        let same = approx_eq(self.x, other.x) && approx_eq(self.y, other.y) && approx_eq(self.z, other.z) && approx_eq(self.w, other.w);
+        let negated = approx_eq(self.x, -other.x) && approx_eq(self.y, -other.y) && approx_eq(self.z, -other.z) && approx_eq(self.w, -other.w);
+        same || negated
+    }
+}
+
+impl Default for Quat
+{
+    fn default() -> Quat
+    {
+        Quat::identity()
+    }
+}
+
+//a * b rotates by b first, then by a
+impl Mul for Quat
+{
+    type Output = Quat;
+
+    fn mul(self, other : Quat) -> Quat
+    {
+        Quat::new(
+            self.w * other.x + self.x * other.w + self.y * other.z - self.z * other.y,
+            self.w * other.y - self.x * other.z + self.y * other.w + self.z * other.x,
+            self.w * other.z + self.x * other.y - self.y * other.x + self.z * other.w,
+            self.w * other.w - self.x * other.x - self.y * other.y - self.z * other.z)
+    }
+}
+
+impl Mul<Vec3> for Quat
+{
+    type Output = Vec3;
+
+    fn mul(self, vector : Vec3) -> Vec3
+    {
+        self.rotate(vector)
+    }
+}
+
+impl From<[f32; 4]> for Quat
+{
+    fn from(array : [f32; 4]) -> Quat
+    {
+        Quat::new(array[0], array[1], array[2], array[3])
+    }
+}
+
+impl From<Quat> for [f32; 4]
+{
+    fn from(quat : Quat) -> [f32; 4]
+    {
+        [quat.x, quat.y, quat.z, quat.w]
+    }
+}
+
+#[cfg(test)]
+mod tests
+{
+    use math::Mat3;
+    use math::Quat;
+    use math::Vec3;
+
+    use std::f32::consts::PI;
+
+    #[test]
+    fn axis_angle_rotates_counter_clockwise()
+    {
+        let rotation = Quat::from_axis_angle(Vec3::unit_z(), PI / 2.0);
+
+        assert!(rotation.rotate(Vec3::unit_x()).approx_eq(Vec3::unit_y()));
+        assert!((rotation * Vec3::unit_y()).approx_eq(-Vec3::unit_x()));
+    }
+
+    #[test]
+    fn multiplication_applies_the_right_hand_side_first()
+    {
+        let around_z = Quat::from_axis_angle(Vec3::unit_z(), PI / 2.0);
+        let around_x = Quat::from_axis_angle(Vec3::unit_x(), PI / 2.0);
+
+        //x goes to y, then y goes to z
+        assert!(((around_x * around_z) * Vec3::unit_x()).approx_eq(Vec3::unit_z()));
+        assert!(((around_z * around_x) * Vec3::unit_x()).approx_eq(Vec3::unit_y()));
+    }
+
+    #[test]
+    fn inverse_undoes_the_rotation()
+    {
+        let rotation = Quat::from_euler(0.3, 1.2, -0.5);
+        let vector = Vec3::new(1.0, 2.0, 3.0);
+
+        assert!(rotation.inverse().rotate(rotation.rotate(vector)).approx_eq(vector));
+        assert!((rotation * rotation.conjugate()).approx_eq(Quat::identity()));
+    }
+
+    #[test]
+    fn matrix_round_trips()
+    {
+        //one rotation for each branch of from_mat3: positive trace, then x, y and z being the largest diagonal
+        let rotations = [
+            Quat::from_axis_angle(Vec3::new(1.0, 2.0, 3.0), 0.4),
+            Quat::from_axis_angle(Vec3::unit_x(), 3.0),
+            Quat::from_axis_angle(Vec3::unit_y(), 3.0),
+            Quat::from_axis_angle(Vec3::unit_z(), 3.0),
+            Quat::from_axis_angle(Vec3::new(1.0, 1.0, 1.0), PI)
+        ];
+
+        for rotation in rotations.iter()
+        {
+            let matrix = Mat3::from_quat(*rotation);
+            let round_tripped = Quat::from_mat3(matrix);
+
+            assert!(round_tripped.approx_eq(*rotation), "{:?} came back as {:?}", rotation, round_tripped);
+            assert!(Mat3::from_quat(round_tripped).approx_eq(&matrix));
+            assert!((matrix * Vec3::new(1.0, -2.0, 0.5)).approx_eq(rotation.rotate(Vec3::new(1.0, -2.0, 0.5))));
+        }
+    }
+
+    #[test]
+    fn slerp_hits_its_endpoints()
+    {
+        let from = Quat::from_axis_angle(Vec3::unit_y(), 0.2);
+        let to = Quat::from_axis_angle(Vec3::unit_y(), 2.0);
+
+        assert!(from.slerp(to, 0.0).approx_eq(from));
+        assert!(from.slerp(to, 1.0).approx_eq(to));
+        assert!(from.slerp(to, 0.5).approx_eq(Quat::from_axis_angle(Vec3::unit_y(), 1.1)));
+    }
+
+    #[test]
+    fn slerp_takes_the_short_way_around()
+    {
+        let from = Quat::identity();
+        //the same rotation as 0.5 radians, from the other hemisphere
+        let to = Quat::from_axis_angle(Vec3::unit_x(), 0.5);
+        let negated = Quat::new(-to.x, -to.y, -to.z, -to.w);
+
+        assert!(from.slerp(negated, 0.5).approx_eq(Quat::from_axis_angle(Vec3::unit_x(), 0.25)));
+    }
+
+    #[test]
+    fn slerp_of_nearly_equal_rotations_stays_normalized()
+    {
+        let from = Quat::from_axis_angle(Vec3::unit_z(), 1.0);
+        let to = Quat::from_axis_angle(Vec3::unit_z(), 1.0001);
+
+        let halfway = from.slerp(to, 0.5);
+        assert!((halfway.length() - 1.0).abs() < 0.0001);
+        assert!(halfway.approx_eq(Quat::from_axis_angle(Vec3::unit_z(), 1.00005)));
+    }
+}
@@ -0,0 +1,350 @@
+use std::ops::Add;
+use std::ops::AddAssign;
+use std::ops::Div;
+use std::ops::Index;
+use std::ops::IndexMut;
+use std::ops::Mul;
+use std::ops::MulAssign;
+use std::ops::Neg;
+use std::ops::Sub;
+use std::ops::SubAssign;
+
+use math::approx_eq;
+
+//everything the vector types have in common, written once for any number of components
+macro_rules! impl_vector
+{
+    ($name:ident, $size:expr, $($field:ident),+) =>
+    {
+        #[derive(Debug, Clone, Copy, PartialEq, Default)]
+        #[repr(C)]
+        pub struct $name
+        {
+            $(pub $field : f32),+
+        }
+
+        impl $name
+        {
+            pub fn new($($field : f32),+) -> $name
+            {
+                $name { $($field),+ }
+            }
+
+            pub fn zero() -> $name
+            {
+                $name { $($field : 0.0),+ }
+            }
+
+            pub fn splat(value : f32) -> $name
+            {
+                $name { $($field : value),+ }
+            }
+
+            pub fn dot(self, other : $name) -> f32
+            {
+                0.0 $(+ self.$field * other.$field)+
+            }
+
+            pub fn length_squared(self) -> f32
+            {
+                self.dot(self)
+            }
+
+            pub fn length(self) -> f32
+            {
+                self.length_squared().sqrt()
+            }
+
+            //returns the zero vector unchanged instead of dividing by zero
+            pub fn normalize(self) -> $name
+            {
+                let length = self.length();
+                if length == 0.0
+                {
+                    return self;
+                }
+                self / length
+            }
+
+            pub fn lerp(self, other : $name, t : f32) -> $name
+            {
+                self + (other - self) * t
+            }
+
+            //component-wise product
+            pub fn scale(self, other : $name) -> $name
+            {
+                $name { $($field : self.$field * other.$field),+ }
+            }
+
+            pub fn approx_eq(self, other : $name) -> bool
+            {
+                true $(&& approx_eq(self.$field, other.$field))+
+            }
+        }
+
+        impl Add for $name
+        {
+            type Output = $name;
+
+            fn add(self, other : $name) -> $name
+            {
+                $name { $($field : self.$field + other.$field),+ }
+            }
+        }
+
+        impl Sub for $name
+        {
+            type Output = $name;
+
+            fn sub(self, other : $name) -> $name
+            {
+                $name { $($field : self.$field - other.$field),+ }
+            }
+        }
+
+        impl Mul<f32> for $name
+        {
+            type Output = $name;
+
+            fn mul(self, scalar : f32) -> $name
+            {
+                $name { $($field : self.$field * scalar),+ }
+            }
+        }
+
+        impl Mul<$name> for f32
+        {
+            type Output = $name;
+
+            fn mul(self, vector : $name) -> $name
+            {
+                vector * self
+            }
+        }
+
+        impl Div<f32> for $name
+        {
+            type Output = $name;
+
+            fn div(self, scalar : f32) -> $name
+            {
+                $name { $($field : self.$field / scalar),+ }
+            }
+        }
+
+        impl Neg for $name
+        {
+            type Output = $name;
+
+            fn neg(self) -> $name
+            {
+                $name { $($field : -self.$field),+ }
+            }
+        }
+
+        impl AddAssign for $name
+        {
+            fn add_assign(&mut self, other : $name)
+            {
+                *self = *self + other;
+            }
+        }
+
+        impl SubAssign for $name
+        {
+            fn sub_assign(&mut self, other : $name)
+            {
+                *self = *self - other;
+            }
+        }
+
+        impl MulAssign<f32> for $name
+        {
+            fn mul_assign(&mut self, scalar : f32)
+            {
+                *self = *self * scalar;
+            }
+        }
+
+        impl Index<usize> for $name
+        {
+            type Output = f32;
+
+            fn index(&self, index : usize) -> &f32
+            {
+                let components : &[f32; $size] = self.as_ref();
+                &components[index]
+            }
+        }
+
+        impl IndexMut<usize> for $name
+        {
+            fn index_mut(&mut self, index : usize) -> &mut f32
+            {
+                let components : &mut [f32; $size] = self.as_mut();
+                &mut components[index]
+            }
+        }
+
+        impl AsRef<[f32; $size]> for $name
+        {
+            fn as_ref(&self) -> &[f32; $size]
+            {
+                //repr(C) with only f32 fields has the same layout as the array
+                unsafe { &*(self as *const $name as *const [f32; $size]) }
+            }
+        }
+
+        impl AsMut<[f32; $size]> for $name
+        {
+            fn as_mut(&mut self) -> &mut [f32; $size]
+            {
+                unsafe { &mut *(self as *mut $name as *mut [f32; $size]) }
+            }
+        }
+
+        impl From<[f32; $size]> for $name
+        {
+            fn from(array : [f32; $size]) -> $name
+            {
+                let mut vector = $name::zero();
+                vector.as_mut().copy_from_slice(&array);
+                vector
+            }
+        }
+
+        impl From<$name> for [f32; $size]
+        {
+            fn from(vector : $name) -> [f32; $size]
+            {
+                *vector.as_ref()
+            }
+        }
+    }
+}
+
+impl_vector!(Vec2, 2, x, y);
+impl_vector!(Vec3, 3, x, y, z);
+impl_vector!(Vec4, 4, x, y, z, w);
+
+impl Vec2
+{
+    pub fn extend(self, z : f32) -> Vec3
+    {
+        Vec3::new(self.x, self.y, z)
+    }
+}
+
+impl Vec3
+{
+    pub fn unit_x() -> Vec3
+    {
+        Vec3::new(1.0, 0.0, 0.0)
+    }
+
+    pub fn unit_y() -> Vec3
+    {
+        Vec3::new(0.0, 1.0, 0.0)
+    }
+
+    pub fn unit_z() -> Vec3
+    {
+        Vec3::new(0.0, 0.0, 1.0)
+    }
+
+    pub fn cross(self, other : Vec3) -> Vec3
+    {
+        Vec3::new(
+            self.y * other.z - self.z * other.y,
+            self.z * other.x - self.x * other.z,
+            self.x * other.y - self.y * other.x)
+    }
+
+    pub fn extend(self, w : f32) -> Vec4
+    {
+        Vec4::new(self.x, self.y, self.z, w)
+    }
+
+    pub fn truncate(self) -> Vec2
+    {
+        Vec2::new(self.x, self.y)
+    }
+}
+
+impl Vec4
+{
+    pub fn truncate(self) -> Vec3
+    {
+        Vec3::new(self.x, self.y, self.z)
+    }
+}
+
+#[cfg(test)]
+mod tests
+{
+    use math::Vec2;
+    use math::Vec3;
+    use math::Vec4;
+
+    #[test]
+    fn dot_and_length()
+    {
+        assert_eq!(Vec3::new(1.0, 2.0, 3.0).dot(Vec3::new(4.0, -5.0, 6.0)), 12.0);
+        assert_eq!(Vec2::new(3.0, 4.0).length(), 5.0);
+        assert_eq!(Vec4::new(1.0, 1.0, 1.0, 1.0).length_squared(), 4.0);
+    }
+
+    #[test]
+    fn cross_is_right_handed()
+    {
+        assert_eq!(Vec3::unit_x().cross(Vec3::unit_y()), Vec3::unit_z());
+        assert_eq!(Vec3::unit_y().cross(Vec3::unit_z()), Vec3::unit_x());
+        assert_eq!(Vec3::unit_z().cross(Vec3::unit_x()), Vec3::unit_y());
+        assert_eq!(Vec3::unit_y().cross(Vec3::unit_x()), -Vec3::unit_z());
+    }
+
+    #[test]
+    fn normalize()
+    {
+        assert!(Vec3::new(0.0, 3.0, 4.0).normalize().approx_eq(Vec3::new(0.0, 0.6, 0.8)));
+        assert_eq!(Vec3::zero().normalize(), Vec3::zero());
+    }
+
+    #[test]
+    fn arithmetic()
+    {
+        let a = Vec3::new(1.0, 2.0, 3.0);
+        let b = Vec3::new(4.0, 5.0, 6.0);
+
+        assert_eq!(a + b, Vec3::new(5.0, 7.0, 9.0));
+        assert_eq!(b - a, Vec3::splat(3.0));
+        assert_eq!(a * 2.0, 2.0 * a);
+        assert_eq!(b / 2.0, Vec3::new(2.0, 2.5, 3.0));
+        assert_eq!(a.scale(b), Vec3::new(4.0, 10.0, 18.0));
+        assert_eq!(a.lerp(b, 0.5), Vec3::new(2.5, 3.5, 4.5));
+
+        let mut c = a;
+        c += b;
+        c -= a;
+        c *= 0.5;
+        assert_eq!(c, Vec3::new(2.0, 2.5, 3.0));
+    }
+
+    #[test]
+    fn components_and_conversions()
+    {
+        let mut vector = Vec4::new(1.0, 2.0, 3.0, 4.0);
+        assert_eq!(vector[2], 3.0);
+
+        vector[3] = 5.0;
+        assert_eq!(vector.w, 5.0);
+
+        let array : [f32; 4] = vector.into();
+        assert_eq!(array, [1.0, 2.0, 3.0, 5.0]);
+        assert_eq!(Vec4::from(array), vector);
+
+        assert_eq!(vector.truncate(), Vec3::new(1.0, 2.0, 3.0));
+        assert_eq!(vector.truncate().truncate().extend(7.0), Vec3::new(1.0, 2.0, 7.0));
+    }
+}
@@ -0,0 +1,3 @@
+//outside interface
+pub mod pipeline_key;
+pub mod pipeline_manager;
@@ -0,0 +1,125 @@
+use vulkano::framebuffer::RenderPassAbstract;
+use vulkano::pipeline::blend::AttachmentBlend;
+use vulkano::pipeline::depth_stencil::DepthStencil;
+use vulkano::pipeline::shader::ShaderModule;
+
+use std::any::TypeId;
+use std::hash::Hash;
+use std::hash::Hasher;
+use std::sync::Arc;
+use std::vec::Vec;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum BlendMode
+{
+    Opaque,
+    //straight alpha, drawn over what is already there
+    AlphaBlend
+}
+
+impl BlendMode
+{
+    //what the pipeline builder's blend_collective is given
+    pub fn attachment_blend(&self) -> AttachmentBlend
+    {
+        match *self
+        {
+            BlendMode::Opaque => AttachmentBlend::pass_through(),
+            BlendMode::AlphaBlend => AttachmentBlend::alpha_blending()
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum DepthMode
+{
+    //drawn in order, on top of everything before it
+    Disabled,
+    //tests against and writes to the depth buffer
+    TestAndWrite
+}
+
+impl DepthMode
+{
+    //what the pipeline builder's depth_stencil is given
+    pub fn depth_stencil(&self) -> DepthStencil
+    {
+        match *self
+        {
+            DepthMode::Disabled => DepthStencil::disabled(),
+            DepthMode::TestAndWrite => DepthStencil::simple_depth_test()
+        }
+    }
+}
+
+//the vertex type a pipeline reads, pipelines with the same shaders but different vertex buffers are not interchangeable
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct VertexLayout(TypeId);
+
+impl VertexLayout
+{
+    pub fn of<V : 'static>() -> VertexLayout
+    {
+        VertexLayout(TypeId::of::<V>())
+    }
+}
+
+//the fixed function state a drawer asks for, the same for every shader it reloads
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct PipelineState
+{
+    pub vertex_layout : VertexLayout,
+    pub blend : BlendMode,
+    pub depth : DepthMode
+}
+
+//everything a graphics pipeline is built from, two equal keys build pipelines that can be used in place of each other
+//shaders and render passes are compared by identity, the key holds on to them so their addresses can not be reused
+#[derive(Clone)]
+pub struct PipelineKey
+{
+    pub modules : Vec<Arc<ShaderModule>>,
+    pub render_pass : Arc<RenderPassAbstract + Send + Sync>,
+    pub state : PipelineState
+}
+
+impl PipelineKey
+{
+    //true when the key is the only thing still using one of its shaders, they were reloaded or their drawer is gone
+    pub fn is_stale(&self) -> bool
+    {
+        self.modules.iter().any(|module| Arc::strong_count(module) == 1)
+    }
+
+    fn render_pass_address(&self) -> usize
+    {
+        &*self.render_pass as *const (RenderPassAbstract + Send + Sync) as *const () as usize
+    }
+}
+
+impl PartialEq for PipelineKey
+{
+    fn eq(&self, other : &PipelineKey) -> bool
+    {
+        self.state == other.state
+            && self.render_pass_address() == other.render_pass_address()
+            && self.modules.len() == other.modules.len()
+            && self.modules.iter().zip(other.modules.iter()).all(|(module, other_module)| Arc::ptr_eq(module, other_module))
+    }
+}
+
+impl Eq for PipelineKey {}
+
+impl Hash for PipelineKey
+{
+    fn hash<H : Hasher>(&self, state : &mut H)
+    {
+        for module in self.modules.iter()
+        {
+            (&**module as *const ShaderModule as usize).hash(state);
+        }
+
+        self.render_pass_address().hash(state);
+        self.state.hash(state);
+    }
+}
@@ -0,0 +1,232 @@
+use vulkano::device::Device;
+use vulkano::pipeline::GraphicsPipelineAbstract;
+use vulkano::pipeline::cache::PipelineCache;
+
+use engine_error::EngineError;
+use pipelines::pipeline_key::PipelineKey;
+
+use std::collections::HashMap;
+use std::env;
+use std::fs;
+use std::io;
+use std::path::Path;
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::sync::Mutex;
+use std::vec::Vec;
+
+//VK_PIPELINE_CACHE_HEADER_VERSION_ONE, the only layout the header has
+const CACHE_HEADER_VERSION : u32 = 1;
+//length, version, vendor id, device id and the 16 byte cache uuid
+const CACHE_HEADER_LENGTH : usize = 32;
+
+//set to the file the pipeline cache is kept in to use it instead of the default one
+pub const CACHE_PATH_VARIABLE : &str = "MOTOR_PIPELINE_CACHE";
+//relative to the working directory, next to the build output when run through cargo
+const DEFAULT_CACHE_PATH : &str = "target/pipeline_cache.bin";
+
+//graphics pipelines shared by every drawer, a drawer asking for a pipeline another one already built gets the same one
+//the vulkan pipeline cache behind it is loaded from and saved to a file, so drivers can skip compiling between runs
+pub struct PipelineManager
+{
+    //vulkano's pipeline builder does not take a cache yet, so pipelines are built without it
+    //it is still round tripped so whatever the driver and future builders put in it survives
+    cache : Arc<PipelineCache>,
+    //None keeps the cache in memory only
+    cache_path : Option<PathBuf>,
+    pipelines : Mutex<HashMap<PipelineKey, Arc<GraphicsPipelineAbstract + Send + Sync>>>
+}
+
+impl PipelineManager
+{
+    //starts from the cache saved at cache_path, or an empty one when it is missing or was saved by another device or driver
+    pub fn new(device : Arc<Device>, cache_path : Option<PathBuf>) -> Result<PipelineManager, EngineError>
+    {
+        let saved = cache_path.as_ref().and_then(|path| read_cache_file(path, &device));
+
+        let cache = match saved
+        {
+            //the header matches this device, the driver validates the rest
+            Some(data) => unsafe { PipelineCache::with_data(device.clone(), &data)? },
+            None => PipelineCache::empty(device.clone())?
+        };
+
+        Ok(PipelineManager
+        {
+            cache,
+            cache_path,
+            pipelines : Mutex::new(HashMap::new())
+        })
+    }
+
+    //the pipeline built for key, build only runs when nobody has asked for an equal key before
+    //the lock is held while building so two drawers never build the same pipeline twice
+    pub fn get_or_build<F>(&self, key : PipelineKey, build : F) -> Result<Arc<GraphicsPipelineAbstract + Send + Sync>, EngineError>
+        where F : FnOnce(&PipelineKey) -> Result<Arc<GraphicsPipelineAbstract + Send + Sync>, EngineError>
+    {
+        let mut pipelines = self.pipelines.lock().unwrap();
+
+        if let Some(pipeline) = pipelines.get(&key)
+        {
+            return Ok(pipeline.clone());
+        }
+
+        let pipeline = build(&key)?;
+        pipelines.insert(key, pipeline.clone());
+
+        Ok(pipeline)
+    }
+
+    //forgets the pipelines built with shaders nothing uses anymore, call it after shaders are reloaded
+    pub fn remove_stale(&self)
+    {
+        self.pipelines.lock().unwrap().retain(|key, _| !key.is_stale());
+    }
+
+    //writes the cache to its file, does nothing when it has none
+    //a file that can not be written is only reported, the next run starts with an empty cache
+    pub fn save(&self) -> Result<(), EngineError>
+    {
+        let path = match self.cache_path
+        {
+            Some(ref path) => path,
+            None => return Ok(())
+        };
+
+        let data = self.cache.get_data()?;
+
+        if let Err(err) = write_cache_file(path, &data)
+        {
+            println!("Could not save pipeline cache to {}: {}", path.display(), err);
+        }
+
+        Ok(())
+    }
+}
+
+impl Drop for PipelineManager
+{
+    fn drop(&mut self)
+    {
+        if let Err(err) = self.save()
+        {
+            println!("Could not save pipeline cache: {}", err);
+        }
+    }
+}
+
+//the file from the environment, or the default one when it is not set
+pub fn cache_path_from_env() -> PathBuf
+{
+    env::var_os(CACHE_PATH_VARIABLE).filter(|path| !path.is_empty()).map(PathBuf::from).unwrap_or_else(|| PathBuf::from(DEFAULT_CACHE_PATH))
+}
+
+//None when there is no saved cache or it can not be used with this device
+fn read_cache_file(path : &Path, device : &Device) -> Option<Vec<u8>>
+{
+    let data = fs::read(path).ok()?;
+    let physical = device.physical_device();
+
+    if !header_matches(&data, physical.pci_vendor_id(), physical.pci_device_id(), physical.uuid())
+    {
+        println!("Ignoring pipeline cache {}, it was saved by another device or driver", path.display());
+        return None;
+    }
+
+    Some(data)
+}
+
+fn write_cache_file(path : &Path, data : &[u8]) -> io::Result<()>
+{
+    if let Some(directory) = path.parent()
+    {
+        fs::create_dir_all(directory)?;
+    }
+
+    //written next to the old one first so a crash while saving can not leave half a cache behind
+    let temporary_path = path.with_extension("tmp");
+    fs::write(&temporary_path, data)?;
+    fs::rename(&temporary_path, path)
+}
+
+//the uuid is the physical device's pipeline cache uuid, drivers change it whenever their caches stop being compatible
+fn header_matches(data : &[u8], vendor_id : u32, device_id : u32, uuid : &[u8; 16]) -> bool
+{
+    if data.len() < CACHE_HEADER_LENGTH
+    {
+        return false;
+    }
+
+    let read_u32 = |offset : usize| u32::from(data[offset]) | u32::from(data[offset + 1]) << 8 | u32::from(data[offset + 2]) << 16 | u32::from(data[offset + 3]) << 24;
+
+    read_u32(0) as usize >= CACHE_HEADER_LENGTH
+        && read_u32(4) == CACHE_HEADER_VERSION
+        && read_u32(8) == vendor_id
+        && read_u32(12) == device_id
+        && data[16 .. 32] == uuid[..]
+}
+
+#[cfg(test)]
+mod tests
+{
+    use pipelines::pipeline_manager::header_matches;
+
+    use std::vec::Vec;
+
+    const VENDOR_ID : u32 = 0x10de;
+    const DEVICE_ID : u32 = 0x1b80;
+    const UUID : [u8; 16] = [7; 16];
+
+    fn push_u32(data : &mut Vec<u8>, value : u32)
+    {
+        data.extend_from_slice(&[value as u8, (value >> 8) as u8, (value >> 16) as u8, (value >> 24) as u8]);
+    }
+
+    //a header as a driver would write it, followed by some driver data
+    fn header(length : u32, version : u32, vendor_id : u32, device_id : u32, uuid : [u8; 16]) -> Vec<u8>
+    {
+        let mut data = Vec::new();
+        push_u32(&mut data, length);
+        push_u32(&mut data, version);
+        push_u32(&mut data, vendor_id);
+        push_u32(&mut data, device_id);
+        data.extend_from_slice(&uuid);
+        data.extend_from_slice(&[0xab; 64]);
+        data
+    }
+
+    #[test]
+    fn a_header_of_this_device_matches()
+    {
+        assert!(header_matches(&header(32, 1, VENDOR_ID, DEVICE_ID, UUID), VENDOR_ID, DEVICE_ID, &UUID));
+        //drivers may write a longer header than the part that is read
+        assert!(header_matches(&header(48, 1, VENDOR_ID, DEVICE_ID, UUID), VENDOR_ID, DEVICE_ID, &UUID));
+    }
+
+    #[test]
+    fn data_shorter_than_a_header_does_not_match()
+    {
+        let data = header(32, 1, VENDOR_ID, DEVICE_ID, UUID);
+
+        assert!(!header_matches(&[], VENDOR_ID, DEVICE_ID, &UUID));
+        assert!(!header_matches(&data[.. 31], VENDOR_ID, DEVICE_ID, &UUID));
+        assert!(!header_matches(&header(16, 1, VENDOR_ID, DEVICE_ID, UUID), VENDOR_ID, DEVICE_ID, &UUID));
+    }
+
+    #[test]
+    fn another_version_does_not_match()
+    {
+        assert!(!header_matches(&header(32, 2, VENDOR_ID, DEVICE_ID, UUID), VENDOR_ID, DEVICE_ID, &UUID));
+    }
+
+    #[test]
+    fn another_device_or_driver_does_not_match()
+    {
+        let mut other_uuid = UUID;
+        other_uuid[15] = 8;
+
+        assert!(!header_matches(&header(32, 1, 0x1002, DEVICE_ID, UUID), VENDOR_ID, DEVICE_ID, &UUID));
+        assert!(!header_matches(&header(32, 1, VENDOR_ID, 0x1b81, UUID), VENDOR_ID, DEVICE_ID, &UUID));
+        assert!(!header_matches(&header(32, 1, VENDOR_ID, DEVICE_ID, other_uuid), VENDOR_ID, DEVICE_ID, &UUID));
+    }
+}
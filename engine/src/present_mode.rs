@@ -0,0 +1,133 @@
+use vulkano::swapchain::PresentMode;
+use vulkano::swapchain::SupportedPresentModes;
+
+use std::vec::Vec;
+
+//present modes in the order they are tried, the first one the surface supports is used
+#[derive(Debug, Clone, PartialEq)]
+pub struct PresentModeConfig
+{
+    pub preferred : Vec<PresentMode>
+}
+
+impl PresentModeConfig
+{
+    pub fn new(preferred : Vec<PresentMode>) -> PresentModeConfig
+    {
+        PresentModeConfig
+        {
+            preferred
+        }
+    }
+
+    //waits for vertical blank, the only mode every surface has to support
+    pub fn vsync() -> PresentModeConfig
+    {
+        PresentModeConfig::new(vec!(PresentMode::Fifo))
+    }
+
+    //like vsync, but a late frame is shown right away and tears instead of waiting a whole refresh
+    pub fn relaxed_vsync() -> PresentModeConfig
+    {
+        PresentModeConfig::new(vec!(PresentMode::Relaxed, PresentMode::Fifo))
+    }
+
+    //renders as fast as it can without tearing, only the newest frame is shown
+    pub fn mailbox() -> PresentModeConfig
+    {
+        PresentModeConfig::new(vec!(PresentMode::Mailbox, PresentMode::Fifo))
+    }
+
+    //no waiting at all, for measuring how fast frames really are
+    pub fn uncapped() -> PresentModeConfig
+    {
+        PresentModeConfig::new(vec!(PresentMode::Immediate, PresentMode::Mailbox, PresentMode::Fifo))
+    }
+
+    //vsync, relaxed, mailbox or immediate
+    pub fn from_name(name : &str) -> Option<PresentModeConfig>
+    {
+        match name
+        {
+            "vsync" | "fifo" => Some(PresentModeConfig::vsync()),
+            "relaxed" => Some(PresentModeConfig::relaxed_vsync()),
+            "mailbox" => Some(PresentModeConfig::mailbox()),
+            "immediate" | "uncapped" => Some(PresentModeConfig::uncapped()),
+            _ => None
+        }
+    }
+
+    //fifo when nothing in the list is supported, vulkan requires every surface to have it
+    pub fn choose(&self, supported : &SupportedPresentModes) -> PresentMode
+    {
+        self.preferred.iter().cloned().find(|&mode| supported.supports(mode)).unwrap_or(PresentMode::Fifo)
+    }
+}
+
+impl Default for PresentModeConfig
+{
+    fn default() -> PresentModeConfig
+    {
+        PresentModeConfig::mailbox()
+    }
+}
+
+#[cfg(test)]
+mod tests
+{
+    use vulkano::swapchain::PresentMode;
+    use vulkano::swapchain::SupportedPresentModes;
+
+    use present_mode::PresentModeConfig;
+
+    fn only_fifo() -> SupportedPresentModes
+    {
+        SupportedPresentModes{fifo: true, .. SupportedPresentModes::none()}
+    }
+
+    #[test]
+    fn mailbox_falls_back_to_fifo()
+    {
+        let config = PresentModeConfig::mailbox();
+
+        assert_eq!(config.choose(&SupportedPresentModes{mailbox: true, .. only_fifo()}), PresentMode::Mailbox);
+        assert_eq!(config.choose(&only_fifo()), PresentMode::Fifo);
+    }
+
+    #[test]
+    fn uncapped_tries_immediate_then_mailbox_then_fifo()
+    {
+        let config = PresentModeConfig::uncapped();
+
+        assert_eq!(config.choose(&SupportedPresentModes{immediate: true, mailbox: true, .. only_fifo()}), PresentMode::Immediate);
+        assert_eq!(config.choose(&SupportedPresentModes{mailbox: true, relaxed: true, .. only_fifo()}), PresentMode::Mailbox);
+        assert_eq!(config.choose(&SupportedPresentModes{relaxed: true, .. only_fifo()}), PresentMode::Fifo);
+    }
+
+    #[test]
+    fn fifo_is_chosen_when_nothing_preferred_is_supported()
+    {
+        let config = PresentModeConfig::new(vec!(PresentMode::Immediate));
+
+        assert_eq!(config.choose(&SupportedPresentModes::none()), PresentMode::Fifo);
+    }
+
+    #[test]
+    fn names_map_to_configs()
+    {
+        assert_eq!(PresentModeConfig::from_name("vsync"), Some(PresentModeConfig::vsync()));
+        assert_eq!(PresentModeConfig::from_name("fifo"), Some(PresentModeConfig::vsync()));
+        assert_eq!(PresentModeConfig::from_name("relaxed"), Some(PresentModeConfig::relaxed_vsync()));
+        assert_eq!(PresentModeConfig::from_name("mailbox"), Some(PresentModeConfig::mailbox()));
+        assert_eq!(PresentModeConfig::from_name("uncapped"), Some(PresentModeConfig::uncapped()));
+        assert_eq!(PresentModeConfig::from_name("immediate"), Some(PresentModeConfig::uncapped()));
+    }
+
+    #[test]
+    fn unknown_names_are_none()
+    {
+        assert_eq!(PresentModeConfig::from_name("turbo"), None);
+        assert_eq!(PresentModeConfig::from_name(""), None);
+        assert_eq!(PresentModeConfig::from_name("Mailbox"), None);
+    }
+}
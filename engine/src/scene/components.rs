@@ -0,0 +1,88 @@
+use drawers::drawer_mesh::Mesh;
+use textures::texture::Texture;
+use camera::Camera;
+use camera::Projection;
+use math::Mat3;
+use math::Mat4;
+use math::Quat;
+
+use std::f32::consts::PI;
+use std::sync::Arc;
+
+//drawn by the MeshDrawer with the node's world matrix as its model matrix
+#[derive(Clone)]
+pub struct MeshComponent
+{
+    pub mesh : Arc<Mesh>,
+    //None draws the mesh untextured
+    pub texture : Option<Arc<Texture>>,
+    //an sRGB color the mesh is multiplied with, white leaves it as it is
+    pub tint : [f32; 4]
+}
+
+impl MeshComponent
+{
+    pub fn new(mesh : Arc<Mesh>) -> MeshComponent
+    {
+        MeshComponent{mesh, texture: None, tint: [1.0; 4]}
+    }
+
+    pub fn textured(mesh : Arc<Mesh>, texture : Arc<Texture>) -> MeshComponent
+    {
+        MeshComponent{mesh, texture: Some(texture), tint: [1.0; 4]}
+    }
+}
+
+//the lens of a camera, where it is and where it looks comes from its node
+#[derive(Debug, Clone, PartialEq)]
+pub struct CameraComponent
+{
+    pub projection : Projection,
+    pub vertical_fov : f32,
+    pub near : f32,
+    pub far : f32,
+    pub ortho_size : f32
+}
+
+impl CameraComponent
+{
+    pub fn perspective(vertical_fov : f32, near : f32, far : f32) -> CameraComponent
+    {
+        CameraComponent{projection: Projection::Perspective, vertical_fov, near, far, ortho_size: 1.0}
+    }
+
+    pub fn orthographic(ortho_size : f32, near : f32, far : f32) -> CameraComponent
+    {
+        CameraComponent{projection: Projection::Orthographic, vertical_fov: PI / 3.0, near, far, ortho_size}
+    }
+
+    //scale in the world matrix is ignored, cameras only take its position and rotation
+    pub fn apply(&self, camera : &mut Camera, world_matrix : Mat4)
+    {
+        let rotation = world_matrix.to_mat3();
+
+        camera.position = world_matrix.translation();
+        camera.orientation = Quat::from_mat3(Mat3::from_columns(rotation.columns[0].normalize(), rotation.columns[1].normalize(), rotation.columns[2].normalize()));
+        camera.projection = self.projection;
+        camera.vertical_fov = self.vertical_fov;
+        camera.near = self.near;
+        camera.far = self.far;
+        camera.ortho_size = self.ortho_size;
+    }
+}
+
+//only directional lights for now, they shine down the node's -z axis like cameras look
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct LightComponent
+{
+    pub color : [f32; 3],
+    pub intensity : f32
+}
+
+impl LightComponent
+{
+    pub fn directional(color : [f32; 3], intensity : f32) -> LightComponent
+    {
+        LightComponent{color, intensity}
+    }
+}
@@ -0,0 +1,3 @@
+//outside interface
+pub mod scene_graph;
+pub mod components;
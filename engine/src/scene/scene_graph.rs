@@ -0,0 +1,433 @@
+use scene::components::CameraComponent;
+use scene::components::LightComponent;
+use scene::components::MeshComponent;
+use math::Mat4;
+use math::Quat;
+use math::Vec3;
+
+use std::vec::Vec;
+
+//refers to a node in the scene it was created by, stays invalid once that node is removed
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct NodeId
+{
+    index : usize,
+    generation : u32
+}
+
+pub struct Node
+{
+    pub name : String,
+    translation : Vec3,
+    rotation : Quat,
+    scale : Vec3,
+    parent : Option<NodeId>,
+    children : Vec<NodeId>,
+    world_matrix : Mat4,
+    //the local transform changed since the world matrix was last computed
+    dirty : bool,
+    pub mesh : Option<MeshComponent>,
+    pub camera : Option<CameraComponent>,
+    pub light : Option<LightComponent>
+}
+
+impl Node
+{
+    pub fn translation(&self) -> Vec3
+    {
+        self.translation
+    }
+
+    pub fn rotation(&self) -> Quat
+    {
+        self.rotation
+    }
+
+    pub fn scale(&self) -> Vec3
+    {
+        self.scale
+    }
+
+    pub fn parent(&self) -> Option<NodeId>
+    {
+        self.parent
+    }
+
+    pub fn children(&self) -> &[NodeId]
+    {
+        &self.children
+    }
+
+    pub fn local_matrix(&self) -> Mat4
+    {
+        Mat4::from_translation_rotation_scale(self.translation, self.rotation, self.scale)
+    }
+
+    //as of the last Scene::update_world_matrices
+    pub fn world_matrix(&self) -> Mat4
+    {
+        self.world_matrix
+    }
+}
+
+struct Slot
+{
+    generation : u32,
+    node : Option<Node>
+}
+
+//nodes live in one arena and point at each other through ids
+pub struct Scene
+{
+    slots : Vec<Slot>,
+    free_slots : Vec<usize>,
+    roots : Vec<NodeId>,
+    //what the renderer looks through, the first camera found if None
+    pub active_camera : Option<NodeId>
+}
+
+impl Scene
+{
+    pub fn new() -> Scene
+    {
+        Scene
+        {
+            slots : Vec::new(),
+            free_slots : Vec::new(),
+            roots : Vec::new(),
+            active_camera : None
+        }
+    }
+
+    //a parent that is no longer in the scene leaves the new node a root
+    pub fn add_node<S : Into<String>>(&mut self, name : S, parent : Option<NodeId>) -> NodeId
+    {
+        let node = Node
+        {
+            name : name.into(),
+            translation : Vec3::zero(),
+            rotation : Quat::identity(),
+            scale : Vec3::splat(1.0),
+            parent : None,
+            children : Vec::new(),
+            world_matrix : Mat4::identity(),
+            dirty : true,
+            mesh : None,
+            camera : None,
+            light : None
+        };
+
+        let id = match self.free_slots.pop()
+        {
+            Some(index) =>
+            {
+                let slot = &mut self.slots[index];
+                slot.node = Some(node);
+                NodeId{index, generation: slot.generation}
+            },
+            None =>
+            {
+                self.slots.push(Slot{generation: 0, node: Some(node)});
+                NodeId{index: self.slots.len() - 1, generation: 0}
+            }
+        };
+
+        self.roots.push(id);
+        self.set_parent(id, parent);
+
+        id
+    }
+
+    //removes the node together with everything below it
+    pub fn remove_node(&mut self, id : NodeId)
+    {
+        if !self.contains(id)
+        {
+            return;
+        }
+
+        self.set_parent(id, None);
+        self.roots.retain(|&root| root != id);
+
+        let mut pending = vec![id];
+        while let Some(id) = pending.pop()
+        {
+            let node =
+            {
+                let slot = &mut self.slots[id.index];
+                slot.generation += 1;
+                slot.node.take().unwrap()
+            };
+            self.free_slots.push(id.index);
+
+            if self.active_camera == Some(id)
+            {
+                self.active_camera = None;
+            }
+
+            pending.extend(node.children);
+        }
+    }
+
+    pub fn contains(&self, id : NodeId) -> bool
+    {
+        self.slots.get(id.index).map_or(false, |slot| slot.generation == id.generation && slot.node.is_some())
+    }
+
+    pub fn node(&self, id : NodeId) -> Option<&Node>
+    {
+        match self.slots.get(id.index)
+        {
+            Some(slot) if slot.generation == id.generation => slot.node.as_ref(),
+            _ => None
+        }
+    }
+
+    //components can be changed freely, transforms go through the setters so they get marked dirty
+    pub fn node_mut(&mut self, id : NodeId) -> Option<&mut Node>
+    {
+        match self.slots.get_mut(id.index)
+        {
+            Some(slot) if slot.generation == id.generation => slot.node.as_mut(),
+            _ => None
+        }
+    }
+
+    pub fn roots(&self) -> &[NodeId]
+    {
+        &self.roots
+    }
+
+    //every live node, parents are not guaranteed to come before their children
+    pub fn nodes<'a>(&'a self) -> Box<Iterator<Item = (NodeId, &'a Node)> + 'a>
+    {
+        Box::new(self.slots.iter().enumerate().filter_map(|(index, slot)|
+        {
+            slot.node.as_ref().map(|node| (NodeId{index, generation: slot.generation}, node))
+        }))
+    }
+
+    //None makes it a root, the local transform is kept so the node moves with its new parent
+    //false and nothing changes when either node is gone or the parent is the node itself or below it
+    pub fn set_parent(&mut self, id : NodeId, parent : Option<NodeId>) -> bool
+    {
+        let old_parent = match self.node(id)
+        {
+            Some(node) => node.parent,
+            None => return false
+        };
+
+        if let Some(parent) = parent
+        {
+            if !self.contains(parent) || self.is_ancestor_or_self(id, parent)
+            {
+                return false;
+            }
+        }
+
+        match old_parent.and_then(|old_parent| self.node_mut(old_parent))
+        {
+            Some(old_parent) => old_parent.children.retain(|&child| child != id),
+            None => self.roots.retain(|&root| root != id)
+        }
+
+        match parent.and_then(|parent| self.node_mut(parent))
+        {
+            Some(parent) => parent.children.push(id),
+            None => self.roots.push(id)
+        }
+
+        if let Some(node) = self.node_mut(id)
+        {
+            node.parent = parent;
+            node.dirty = true;
+        }
+
+        true
+    }
+
+    fn is_ancestor_or_self(&self, ancestor : NodeId, id : NodeId) -> bool
+    {
+        let mut current = Some(id);
+        while let Some(node) = current
+        {
+            if node == ancestor
+            {
+                return true;
+            }
+            current = self.node(node).and_then(|node| node.parent);
+        }
+        false
+    }
+
+    pub fn set_translation(&mut self, id : NodeId, translation : Vec3)
+    {
+        let node = self.node_mut(id).expect("Could not find node to move!");
+        node.translation = translation;
+        node.dirty = true;
+    }
+
+    pub fn set_rotation(&mut self, id : NodeId, rotation : Quat)
+    {
+        let node = self.node_mut(id).expect("Could not find node to rotate!");
+        node.rotation = rotation;
+        node.dirty = true;
+    }
+
+    pub fn set_scale(&mut self, id : NodeId, scale : Vec3)
+    {
+        let node = self.node_mut(id).expect("Could not find node to scale!");
+        node.scale = scale;
+        node.dirty = true;
+    }
+
+    pub fn set_transform(&mut self, id : NodeId, translation : Vec3, rotation : Quat, scale : Vec3)
+    {
+        let node = self.node_mut(id).expect("Could not find node to transform!");
+        node.translation = translation;
+        node.rotation = rotation;
+        node.scale = scale;
+        node.dirty = true;
+    }
+
+    //only dirty nodes and the ones below them are recomputed
+    pub fn update_world_matrices(&mut self)
+    {
+        let mut pending : Vec<(NodeId, Mat4, bool)> = self.roots.iter().map(|&root| (root, Mat4::identity(), false)).collect();
+
+        while let Some((id, parent_matrix, parent_changed)) = pending.pop()
+        {
+            let node = match self.slots[id.index].node.as_mut()
+            {
+                Some(node) => node,
+                None => continue
+            };
+
+            let changed = parent_changed || node.dirty;
+            if changed
+            {
+                node.world_matrix = parent_matrix * node.local_matrix();
+                node.dirty = false;
+            }
+
+            let world_matrix = node.world_matrix;
+            pending.extend(node.children.iter().map(|&child| (child, world_matrix, changed)));
+        }
+    }
+
+    //the active camera if it still has a camera component, otherwise the first node with one
+    pub fn camera_node(&self) -> Option<NodeId>
+    {
+        if let Some(active_camera) = self.active_camera
+        {
+            if self.node(active_camera).map_or(false, |node| node.camera.is_some())
+            {
+                return Some(active_camera);
+            }
+        }
+
+        self.nodes().find(|&(_, node)| node.camera.is_some()).map(|(id, _)| id)
+    }
+}
+
+#[cfg(test)]
+mod tests
+{
+    use math::Quat;
+    use math::Vec3;
+    use scene::scene_graph::Scene;
+
+    #[test]
+    fn moving_a_parent_moves_its_children()
+    {
+        let mut scene = Scene::new();
+        let parent = scene.add_node("parent", None);
+        let child = scene.add_node("child", Some(parent));
+        let sibling = scene.add_node("sibling", None);
+        scene.set_translation(child, Vec3::new(1.0, 0.0, 0.0));
+        scene.update_world_matrices();
+
+        assert!(!scene.node(child).unwrap().dirty);
+
+        scene.set_translation(parent, Vec3::new(0.0, 5.0, 0.0));
+        assert!(scene.node(parent).unwrap().dirty);
+        //only the node that changed is marked, its children are picked up while updating
+        assert!(!scene.node(child).unwrap().dirty);
+        assert!(scene.node(child).unwrap().world_matrix().translation().approx_eq(Vec3::new(1.0, 0.0, 0.0)));
+
+        scene.update_world_matrices();
+        assert!(!scene.node(parent).unwrap().dirty);
+        assert!(scene.node(child).unwrap().world_matrix().translation().approx_eq(Vec3::new(1.0, 5.0, 0.0)));
+        assert!(scene.node(sibling).unwrap().world_matrix().translation().approx_eq(Vec3::zero()));
+    }
+
+    #[test]
+    fn rotations_and_scales_reach_grandchildren()
+    {
+        let mut scene = Scene::new();
+        let root = scene.add_node("root", None);
+        let middle = scene.add_node("middle", Some(root));
+        let leaf = scene.add_node("leaf", Some(middle));
+        scene.set_translation(middle, Vec3::new(1.0, 0.0, 0.0));
+        scene.set_translation(leaf, Vec3::new(1.0, 0.0, 0.0));
+        scene.update_world_matrices();
+
+        scene.set_transform(root, Vec3::zero(), Quat::from_axis_angle(Vec3::unit_z(), ::std::f32::consts::PI / 2.0), Vec3::splat(2.0));
+        scene.update_world_matrices();
+
+        assert!(scene.node(leaf).unwrap().world_matrix().translation().approx_eq(Vec3::new(0.0, 4.0, 0.0)));
+    }
+
+    #[test]
+    fn reparenting_keeps_the_local_transform()
+    {
+        let mut scene = Scene::new();
+        let first = scene.add_node("first", None);
+        let second = scene.add_node("second", None);
+        let child = scene.add_node("child", Some(first));
+        scene.set_translation(first, Vec3::new(0.0, 5.0, 0.0));
+        scene.set_translation(second, Vec3::new(10.0, 0.0, 0.0));
+        scene.set_translation(child, Vec3::new(1.0, 0.0, 0.0));
+        scene.update_world_matrices();
+
+        assert!(scene.set_parent(child, Some(second)));
+        assert!(scene.node(child).unwrap().dirty);
+        scene.update_world_matrices();
+
+        let node = scene.node(child).unwrap();
+        assert_eq!(node.parent(), Some(second));
+        assert!(node.translation().approx_eq(Vec3::new(1.0, 0.0, 0.0)));
+        assert!(node.world_matrix().translation().approx_eq(Vec3::new(11.0, 0.0, 0.0)));
+        assert!(scene.node(first).unwrap().children().is_empty());
+        assert_eq!(scene.node(second).unwrap().children(), &[child]);
+
+        assert!(scene.set_parent(child, None));
+        scene.update_world_matrices();
+        assert!(scene.roots().contains(&child));
+        assert!(scene.node(child).unwrap().world_matrix().translation().approx_eq(Vec3::new(1.0, 0.0, 0.0)));
+    }
+
+    #[test]
+    fn cycles_and_removed_nodes_are_refused()
+    {
+        let mut scene = Scene::new();
+        let parent = scene.add_node("parent", None);
+        let child = scene.add_node("child", Some(parent));
+        let removed = scene.add_node("removed", None);
+        scene.remove_node(removed);
+
+        assert!(!scene.set_parent(parent, Some(parent)));
+        assert!(!scene.set_parent(parent, Some(child)));
+        assert!(!scene.set_parent(child, Some(removed)));
+        assert!(!scene.set_parent(removed, None));
+
+        //nothing changed
+        assert_eq!(scene.roots(), &[parent]);
+        assert_eq!(scene.node(child).unwrap().parent(), Some(parent));
+
+        //a stale parent leaves the new node a root
+        let orphan = scene.add_node("orphan", Some(removed));
+        assert_eq!(scene.node(orphan).unwrap().parent(), None);
+        assert!(scene.roots().contains(&orphan));
+    }
+}
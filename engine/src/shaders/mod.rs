@@ -0,0 +1,5 @@
+//outside interface
+pub mod shader_library;
+pub mod shader_interface;
+#[macro_use]
+pub mod reloadable_pipeline;
@@ -0,0 +1,133 @@
+use vulkano::framebuffer::RenderPassAbstract;
+use vulkano::pipeline::GraphicsPipelineAbstract;
+
+use engine_error::EngineError;
+use pipelines::pipeline_key::PipelineKey;
+use pipelines::pipeline_key::PipelineState;
+use pipelines::pipeline_manager::PipelineManager;
+use shaders::shader_library::ShaderHandle;
+
+use std::sync::Arc;
+use std::vec::Vec;
+
+//a drawer's pipeline, rebuilt when the render pass changes or one of its shaders is reloaded
+//built through the pipeline manager, so drawers asking for the same shaders and state share it
+pub struct ReloadablePipeline
+{
+    shaders : Vec<Arc<ShaderHandle>>,
+    state : PipelineState,
+    pipelines : Arc<PipelineManager>,
+    built : Option<BuiltPipeline>
+}
+
+struct BuiltPipeline
+{
+    render_pass : Arc<RenderPassAbstract + Send + Sync>,
+    //the shader generations it was last built, or tried to be built, with
+    generations : Vec<u64>,
+    pipeline : Arc<GraphicsPipelineAbstract + Send + Sync>
+}
+
+impl ReloadablePipeline
+{
+    pub fn new(shaders : Vec<Arc<ShaderHandle>>, state : PipelineState, pipelines : Arc<PipelineManager>) -> ReloadablePipeline
+    {
+        ReloadablePipeline
+        {
+            shaders,
+            state,
+            pipelines,
+            built : None
+        }
+    }
+
+    //build gets a key with the current module of every shader, in the order they were given to new
+    //it only runs when the pipeline manager has nothing built for that key yet
+    //if rebuilding after a reload fails the error is reported and the previous pipeline keeps drawing
+    pub fn get<F>(&mut self, render_pass : Arc<RenderPassAbstract + Send + Sync>, build : F) -> Result<Arc<GraphicsPipelineAbstract + Send + Sync>, EngineError>
+        where F : FnOnce(&PipelineKey) -> Result<Arc<GraphicsPipelineAbstract + Send + Sync>, EngineError>
+    {
+        let (modules, generations) : (Vec<_>, Vec<_>) = self.shaders.iter().map(|shader| shader.module()).unzip();
+
+        let same_render_pass = match self.built
+        {
+            Some(ref built) =>
+            {
+                if Arc::ptr_eq(&built.render_pass, &render_pass) && built.generations == generations
+                {
+                    return Ok(built.pipeline.clone());
+                }
+
+                Arc::ptr_eq(&built.render_pass, &render_pass)
+            },
+            None => false
+        };
+
+        let key = PipelineKey
+        {
+            modules,
+            render_pass : render_pass.clone(),
+            state : self.state
+        };
+
+        match self.pipelines.get_or_build(key, build)
+        {
+            Ok(pipeline) =>
+            {
+                self.built = Some(BuiltPipeline{render_pass, generations, pipeline : pipeline.clone()});
+                Ok(pipeline)
+            },
+            Err(err) =>
+            {
+                //a pipeline for another render pass can not be used, there is nothing to fall back to
+                if !same_render_pass
+                {
+                    return Err(err);
+                }
+
+                println!("Could not rebuild pipeline with the reloaded shaders, keeping the previous one: {}", err);
+
+                let built = self.built.as_mut().unwrap();
+                built.generations = generations;
+                Ok(built.pipeline.clone())
+            }
+        }
+    }
+}
+
+//what the build closure of ReloadablePipeline::get does for a drawer with one vertex and one fragment shader, given to new in that order
+//one buffer of $vertex, triangle lists in the first subpass, a dynamic viewport and the key's blend and depth state
+//$vs and $fs are the vulkano_shader_derive modules of the baked in copies, the fragment shader has to take ENCODE_SRGB
+//a macro because the pipeline builder changes type with every shader given to it
+macro_rules! build_drawer_pipeline
+{
+    ($device:expr, $key:expr, $vertex:ty, $vs:ident, $fs:ident) =>
+    {{
+        use $crate::shaders::shader_library::ShaderStage;
+
+        let key : &$crate::pipelines::pipeline_key::PipelineKey = $key;
+
+        //the modules are the baked in shaders, or reloads the shader library checked against their interfaces
+        let vertex_shader = unsafe { $crate::shaders::shader_library::graphics_entry_point(&key.modules[0], ShaderStage::Vertex, $vs::MainInput, $vs::MainOutput, $vs::Layout(ShaderStage::Vertex.stages())) };
+        let fragment_shader = unsafe { $crate::shaders::shader_library::graphics_entry_point(&key.modules[1], ShaderStage::Fragment, $fs::MainInput, $fs::MainOutput, $fs::Layout(ShaderStage::Fragment.stages())) };
+
+        let encode_srgb = if $crate::color::target_encodes_srgb(&*key.render_pass) { 0 } else { 1 };
+
+        match ::vulkano::framebuffer::Subpass::from(key.render_pass.clone(), 0)
+        {
+            Some(subpass) => ::vulkano::pipeline::GraphicsPipeline::start()
+                .vertex_input_single_buffer::<$vertex>()
+                .vertex_shader(vertex_shader, ())
+                .triangle_list()
+                .viewports_dynamic_scissors_irrelevant(1)
+                .fragment_shader(fragment_shader, $fs::SpecializationConstants{ENCODE_SRGB: encode_srgb})
+                .blend_collective(key.state.blend.attachment_blend())
+                .depth_stencil(key.state.depth.depth_stencil())
+                .render_pass(subpass)
+                .build($device)
+                .map(|pipeline| ::std::sync::Arc::new(pipeline) as ::std::sync::Arc<::vulkano::pipeline::GraphicsPipelineAbstract + Send + Sync>)
+                .map_err($crate::engine_error::EngineError::from),
+            None => Err($crate::engine_error::EngineError::NoSubpass)
+        }
+    }}
+}
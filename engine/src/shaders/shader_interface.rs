@@ -0,0 +1,638 @@
+use vulkano::descriptor::descriptor::DescriptorDescTy;
+use vulkano::descriptor::pipeline_layout::PipelineLayoutDesc;
+use vulkano::format::Format;
+use vulkano::pipeline::shader::ShaderInterfaceDef;
+use vulkano::pipeline::shader::ShaderInterfaceDefEntry;
+
+use std::collections::HashMap;
+use std::ops::Range;
+use std::string::String;
+use std::vec::Vec;
+
+const SPIRV_MAGIC : u32 = 0x0723_0203;
+const HEADER_WORDS : usize = 5;
+
+//the few opcodes, decorations and storage classes the interface is read from
+const OP_ENTRY_POINT : u32 = 15;
+const OP_TYPE_INT : u32 = 21;
+const OP_TYPE_FLOAT : u32 = 22;
+const OP_TYPE_VECTOR : u32 = 23;
+const OP_TYPE_MATRIX : u32 = 24;
+const OP_TYPE_IMAGE : u32 = 25;
+const OP_TYPE_SAMPLER : u32 = 26;
+const OP_TYPE_SAMPLED_IMAGE : u32 = 27;
+const OP_TYPE_ARRAY : u32 = 28;
+const OP_TYPE_RUNTIME_ARRAY : u32 = 29;
+const OP_TYPE_STRUCT : u32 = 30;
+const OP_TYPE_POINTER : u32 = 32;
+const OP_CONSTANT : u32 = 43;
+const OP_VARIABLE : u32 = 59;
+const OP_DECORATE : u32 = 71;
+const OP_MEMBER_DECORATE : u32 = 72;
+
+const DECORATION_BLOCK : u32 = 2;
+const DECORATION_BUFFER_BLOCK : u32 = 3;
+const DECORATION_ARRAY_STRIDE : u32 = 6;
+const DECORATION_MATRIX_STRIDE : u32 = 7;
+const DECORATION_BUILT_IN : u32 = 11;
+const DECORATION_LOCATION : u32 = 30;
+const DECORATION_BINDING : u32 = 33;
+const DECORATION_DESCRIPTOR_SET : u32 = 34;
+const DECORATION_OFFSET : u32 = 35;
+
+const STORAGE_UNIFORM_CONSTANT : u32 = 0;
+const STORAGE_INPUT : u32 = 1;
+const STORAGE_UNIFORM : u32 = 2;
+const STORAGE_OUTPUT : u32 = 3;
+const STORAGE_PUSH_CONSTANT : u32 = 9;
+const STORAGE_STORAGE_BUFFER : u32 = 12;
+
+const DIM_BUFFER : u32 = 5;
+const DIM_SUBPASS_DATA : u32 = 6;
+
+//a located input or output, matrices and arrays take a location per column or element
+#[derive(Debug, Clone, PartialEq)]
+pub struct InterfaceVariable
+{
+    pub locations : Range<u32>,
+    pub format : Format
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DescriptorKind
+{
+    Sampler,
+    CombinedImageSampler,
+    SampledImage,
+    StorageImage,
+    UniformTexelBuffer,
+    StorageTexelBuffer,
+    UniformBuffer,
+    StorageBuffer,
+    InputAttachment
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Descriptor
+{
+    pub set : u32,
+    pub binding : u32,
+    pub kind : DescriptorKind,
+    pub array_count : u32
+}
+
+//what a shader reads and writes, a reloaded shader has to fit the interface its drawer's pipeline was written for
+#[derive(Debug, Clone, PartialEq)]
+pub struct ShaderInterface
+{
+    //sorted by location
+    pub inputs : Vec<InterfaceVariable>,
+    pub outputs : Vec<InterfaceVariable>,
+    //sorted by set, then binding
+    pub descriptors : Vec<Descriptor>,
+    //bytes of push constants the shader can read, 0 without any
+    pub push_constant_size : u32
+}
+
+impl ShaderInterface
+{
+    //the interface vulkano_shader_derive generated for a baked in shader, what graphics_entry_point is given
+    pub fn of<I : ShaderInterfaceDef, O : ShaderInterfaceDef, L : PipelineLayoutDesc>(input : &I, output : &O, layout : &L) -> ShaderInterface
+    {
+        let mut descriptors = Vec::new();
+        for set in 0 .. layout.num_sets()
+        {
+            for binding in 0 .. layout.num_bindings_in_set(set).unwrap_or(0)
+            {
+                if let Some(descriptor) = layout.descriptor(set, binding)
+                {
+                    descriptors.push(Descriptor
+                    {
+                        set : set as u32,
+                        binding : binding as u32,
+                        kind : descriptor_kind(&descriptor.ty),
+                        array_count : descriptor.array_count
+                    });
+                }
+            }
+        }
+
+        let push_constant_size = (0 .. layout.num_push_constants_ranges())
+            .filter_map(|index| layout.push_constants_range(index))
+            .map(|range| (range.offset + range.size) as u32)
+            .max().unwrap_or(0);
+
+        ShaderInterface
+        {
+            inputs : sorted_variables(input.elements()),
+            outputs : sorted_variables(output.elements()),
+            descriptors,
+            push_constant_size
+        }
+    }
+
+    //reads the interface of the main entry point, or says what could not be read
+    pub fn from_spirv(spirv : &[u8]) -> Result<ShaderInterface, String>
+    {
+        Reflection::parse(spirv)?.interface()
+    }
+
+    //None if a shader with this interface can replace one with expected, otherwise what differs
+    //inputs and outputs have to stay the same, descriptors the shader stops using and push constants it reads less of are fine
+    pub fn mismatch(&self, expected : &ShaderInterface) -> Option<String>
+    {
+        if self.inputs != expected.inputs
+        {
+            return Some(format!("inputs changed from {:?} to {:?}", expected.inputs, self.inputs));
+        }
+
+        if self.outputs != expected.outputs
+        {
+            return Some(format!("outputs changed from {:?} to {:?}", expected.outputs, self.outputs));
+        }
+
+        for descriptor in self.descriptors.iter()
+        {
+            if !expected.descriptors.contains(descriptor)
+            {
+                return Some(format!("descriptor {:?} is not in the pipeline layout {:?}", descriptor, expected.descriptors));
+            }
+        }
+
+        if self.push_constant_size > expected.push_constant_size
+        {
+            return Some(format!("reads {} bytes of push constants, the pipeline layout only has {}", self.push_constant_size, expected.push_constant_size));
+        }
+
+        None
+    }
+}
+
+fn sorted_variables<I : Iterator<Item = ShaderInterfaceDefEntry>>(elements : I) -> Vec<InterfaceVariable>
+{
+    let mut variables : Vec<InterfaceVariable> = elements.map(|element| InterfaceVariable{locations : element.location, format : element.format}).collect();
+    variables.sort_by_key(|variable| variable.locations.start);
+    variables
+}
+
+fn descriptor_kind(ty : &DescriptorDescTy) -> DescriptorKind
+{
+    match *ty
+    {
+        DescriptorDescTy::Sampler => DescriptorKind::Sampler,
+        DescriptorDescTy::CombinedImageSampler(_) => DescriptorKind::CombinedImageSampler,
+        DescriptorDescTy::Image(ref image) => if image.sampled { DescriptorKind::SampledImage } else { DescriptorKind::StorageImage },
+        DescriptorDescTy::TexelBuffer { storage, .. } => if storage { DescriptorKind::StorageTexelBuffer } else { DescriptorKind::UniformTexelBuffer },
+        DescriptorDescTy::InputAttachment { .. } => DescriptorKind::InputAttachment,
+        DescriptorDescTy::Buffer(ref buffer) => if buffer.storage { DescriptorKind::StorageBuffer } else { DescriptorKind::UniformBuffer }
+    }
+}
+
+//the parts of a module's instructions the interface is built from, by result id
+#[derive(Default)]
+struct Reflection
+{
+    interface_ids : Option<Vec<u32>>,
+    //opcode and operands after the result id
+    types : HashMap<u32, (u32, Vec<u32>)>,
+    constants : HashMap<u32, u32>,
+    //result id to type id and storage class
+    variables : Vec<(u32, u32, u32)>,
+    decorations : HashMap<(u32, u32), u32>,
+    member_decorations : HashMap<(u32, u32, u32), u32>
+}
+
+impl Reflection
+{
+    fn parse(spirv : &[u8]) -> Result<Reflection, String>
+    {
+        if spirv.len() % 4 != 0 || spirv.len() < HEADER_WORDS * 4
+        {
+            return Err("not a whole number of words".to_string());
+        }
+
+        let little_endian : Vec<u32> = spirv.chunks(4).map(|bytes| u32::from(bytes[0]) | u32::from(bytes[1]) << 8 | u32::from(bytes[2]) << 16 | u32::from(bytes[3]) << 24).collect();
+        let words = if little_endian[0] == SPIRV_MAGIC
+        {
+            little_endian
+        }
+        else if little_endian[0].swap_bytes() == SPIRV_MAGIC
+        {
+            little_endian.iter().map(|word| word.swap_bytes()).collect()
+        }
+        else
+        {
+            return Err("missing the spir-v magic number".to_string());
+        };
+
+        let mut reflection = Reflection::default();
+
+        let mut position = HEADER_WORDS;
+        while position < words.len()
+        {
+            let word_count = (words[position] >> 16) as usize;
+            let opcode = words[position] & 0xffff;
+
+            if word_count == 0 || position + word_count > words.len()
+            {
+                return Err(format!("truncated instruction at word {}", position));
+            }
+
+            let operands = &words[position + 1 .. position + word_count];
+            reflection.add_instruction(opcode, operands)?;
+            position += word_count;
+        }
+
+        Ok(reflection)
+    }
+
+    fn add_instruction(&mut self, opcode : u32, operands : &[u32]) -> Result<(), String>
+    {
+        let operand = |index : usize| operands.get(index).cloned().ok_or_else(|| format!("instruction {} is missing operands", opcode));
+
+        match opcode
+        {
+            OP_ENTRY_POINT =>
+            {
+                //execution model, function id, the name as a nul terminated string packed into words, then the interface
+                let name_words = operands.iter().skip(2).position(|word| word >> 24 == 0).ok_or("unterminated entry point name".to_string())? + 1;
+                let name : Vec<u8> = operands[2 .. 2 + name_words].iter()
+                    .flat_map(|word| vec![*word as u8, (word >> 8) as u8, (word >> 16) as u8, (word >> 24) as u8])
+                    .take_while(|&byte| byte != 0).collect();
+
+                if name == b"main"
+                {
+                    self.interface_ids = Some(operands[2 + name_words ..].to_vec());
+                }
+            },
+            OP_TYPE_INT | OP_TYPE_FLOAT | OP_TYPE_VECTOR | OP_TYPE_MATRIX | OP_TYPE_IMAGE | OP_TYPE_SAMPLER |
+            OP_TYPE_SAMPLED_IMAGE | OP_TYPE_ARRAY | OP_TYPE_RUNTIME_ARRAY | OP_TYPE_STRUCT | OP_TYPE_POINTER =>
+            {
+                //checked here so the types can be indexed into later
+                let required = match opcode
+                {
+                    OP_TYPE_IMAGE => 7,
+                    OP_TYPE_INT | OP_TYPE_VECTOR | OP_TYPE_MATRIX | OP_TYPE_ARRAY | OP_TYPE_POINTER => 2,
+                    OP_TYPE_FLOAT | OP_TYPE_SAMPLED_IMAGE | OP_TYPE_RUNTIME_ARRAY => 1,
+                    _ => 0
+                };
+
+                if operands.len() < required + 1
+                {
+                    return Err(format!("instruction {} is missing operands", opcode));
+                }
+
+                //types are declared before they are used, which also keeps them from containing themselves
+                let used_types = match opcode
+                {
+                    OP_TYPE_STRUCT => &operands[1 ..],
+                    OP_TYPE_POINTER => &operands[2 ..],
+                    OP_TYPE_VECTOR | OP_TYPE_MATRIX | OP_TYPE_IMAGE | OP_TYPE_SAMPLED_IMAGE | OP_TYPE_ARRAY | OP_TYPE_RUNTIME_ARRAY => &operands[1 .. 2],
+                    _ => &operands[.. 0]
+                };
+
+                if let Some(undeclared) = used_types.iter().find(|used| !self.types.contains_key(used))
+                {
+                    return Err(format!("type {} is used before it is declared", undeclared));
+                }
+
+                self.types.insert(operands[0], (opcode, operands[1 ..].to_vec()));
+            },
+            OP_CONSTANT =>
+            {
+                self.constants.insert(operand(1)?, operand(2)?);
+            },
+            OP_VARIABLE =>
+            {
+                self.variables.push((operand(1)?, operand(0)?, operand(2)?));
+            },
+            OP_DECORATE =>
+            {
+                self.decorations.insert((operand(0)?, operand(1)?), operands.get(2).cloned().unwrap_or(0));
+            },
+            OP_MEMBER_DECORATE =>
+            {
+                self.member_decorations.insert((operand(0)?, operand(1)?, operand(2)?), operands.get(3).cloned().unwrap_or(0));
+            },
+            _ => {}
+        }
+
+        Ok(())
+    }
+
+    fn interface(&self) -> Result<ShaderInterface, String>
+    {
+        let interface_ids = self.interface_ids.as_ref().ok_or("no main entry point".to_string())?;
+
+        let mut inputs = Vec::new();
+        let mut outputs = Vec::new();
+        let mut descriptors = Vec::new();
+        let mut push_constant_size : u32 = 0;
+
+        for &(id, pointer_type, storage_class) in self.variables.iter()
+        {
+            let pointee = self.pointee(pointer_type)?;
+
+            match storage_class
+            {
+                STORAGE_INPUT | STORAGE_OUTPUT =>
+                {
+                    //built ins like gl_Position are not part of what the pipeline connects
+                    if !interface_ids.contains(&id) || self.is_built_in(id, pointee)
+                    {
+                        continue;
+                    }
+
+                    let location = *self.decorations.get(&(id, DECORATION_LOCATION)).ok_or(format!("interface variable {} has no location", id))?;
+                    let (format, location_count) = self.location_format(pointee)?;
+                    let end = location.checked_add(location_count).ok_or(format!("interface variable {} is out of locations", id))?;
+                    let variable = InterfaceVariable{locations: location .. end, format};
+
+                    if storage_class == STORAGE_INPUT { inputs.push(variable) } else { outputs.push(variable) }
+                },
+                STORAGE_UNIFORM_CONSTANT | STORAGE_UNIFORM | STORAGE_STORAGE_BUFFER =>
+                {
+                    let set = self.decorations.get(&(id, DECORATION_DESCRIPTOR_SET)).cloned().unwrap_or(0);
+                    let binding = *self.decorations.get(&(id, DECORATION_BINDING)).ok_or(format!("descriptor {} has no binding", id))?;
+
+                    let (element, array_count) = match self.types.get(&pointee)
+                    {
+                        Some(&(OP_TYPE_ARRAY, ref operands)) => (operands[0], self.constant(operands[1])?),
+                        _ => (pointee, 1)
+                    };
+
+                    descriptors.push(Descriptor{set, binding, kind: self.descriptor_kind(element, storage_class)?, array_count});
+                },
+                STORAGE_PUSH_CONSTANT =>
+                {
+                    push_constant_size = push_constant_size.max(self.size_of(pointee, None)?);
+                },
+                _ => {}
+            }
+        }
+
+        inputs.sort_by_key(|variable : &InterfaceVariable| variable.locations.start);
+        outputs.sort_by_key(|variable : &InterfaceVariable| variable.locations.start);
+        descriptors.sort_by_key(|descriptor| (descriptor.set, descriptor.binding));
+
+        Ok(ShaderInterface{inputs, outputs, descriptors, push_constant_size})
+    }
+
+    fn type_of(&self, id : u32) -> Result<(u32, &[u32]), String>
+    {
+        self.types.get(&id).map(|&(opcode, ref operands)| (opcode, &operands[..])).ok_or(format!("unknown type {}", id))
+    }
+
+    fn pointee(&self, pointer_type : u32) -> Result<u32, String>
+    {
+        match self.type_of(pointer_type)?
+        {
+            (OP_TYPE_POINTER, operands) if operands.len() == 2 => Ok(operands[1]),
+            _ => Err(format!("variable type {} is not a pointer", pointer_type))
+        }
+    }
+
+    fn constant(&self, id : u32) -> Result<u32, String>
+    {
+        self.constants.get(&id).cloned().ok_or(format!("array length {} is not a constant", id))
+    }
+
+    //a built in variable, or a block of built ins like gl_PerVertex
+    fn is_built_in(&self, id : u32, pointee : u32) -> bool
+    {
+        let block = match self.types.get(&pointee)
+        {
+            Some(&(OP_TYPE_ARRAY, ref operands)) => operands[0],
+            _ => pointee
+        };
+
+        self.decorations.contains_key(&(id, DECORATION_BUILT_IN))
+            || self.member_decorations.keys().any(|&(structure, _, decoration)| structure == block && decoration == DECORATION_BUILT_IN)
+    }
+
+    //the format of every location the type takes, and how many it takes
+    fn location_format(&self, ty : u32) -> Result<(Format, u32), String>
+    {
+        match self.type_of(ty)?
+        {
+            (OP_TYPE_MATRIX, operands) => Ok((self.location_format(operands[0])?.0, operands[1])),
+            (OP_TYPE_ARRAY, operands) =>
+            {
+                let (format, element_locations) = self.location_format(operands[0])?;
+                let locations = element_locations.checked_mul(self.constant(operands[1])?).ok_or(format!("array {} is too long", ty))?;
+                Ok((format, locations))
+            },
+            (OP_TYPE_VECTOR, operands) => Ok((self.vector_format(operands[0], operands[1])?, 1)),
+            _ => Ok((self.vector_format(ty, 1)?, 1))
+        }
+    }
+
+    fn vector_format(&self, component : u32, count : u32) -> Result<Format, String>
+    {
+        let formats = match self.type_of(component)?
+        {
+            (OP_TYPE_FLOAT, operands) if operands[0] == 32 => [Format::R32Sfloat, Format::R32G32Sfloat, Format::R32G32B32Sfloat, Format::R32G32B32A32Sfloat],
+            (OP_TYPE_FLOAT, operands) if operands[0] == 64 => [Format::R64Sfloat, Format::R64G64Sfloat, Format::R64G64B64Sfloat, Format::R64G64B64A64Sfloat],
+            (OP_TYPE_INT, operands) if operands[0] == 32 && operands[1] == 1 => [Format::R32Sint, Format::R32G32Sint, Format::R32G32B32Sint, Format::R32G32B32A32Sint],
+            (OP_TYPE_INT, operands) if operands[0] == 32 => [Format::R32Uint, Format::R32G32Uint, Format::R32G32B32Uint, Format::R32G32B32A32Uint],
+            _ => return Err(format!("type {} can not be an input or output", component))
+        };
+
+        (count as usize).checked_sub(1).and_then(|index| formats.get(index)).cloned().ok_or(format!("vectors of {} components can not be an input or output", count))
+    }
+
+    fn descriptor_kind(&self, ty : u32, storage_class : u32) -> Result<DescriptorKind, String>
+    {
+        let kind = match (storage_class, self.type_of(ty)?)
+        {
+            (STORAGE_STORAGE_BUFFER, _) => DescriptorKind::StorageBuffer,
+            (STORAGE_UNIFORM, _) if self.decorations.contains_key(&(ty, DECORATION_BUFFER_BLOCK)) => DescriptorKind::StorageBuffer,
+            (STORAGE_UNIFORM, _) if self.decorations.contains_key(&(ty, DECORATION_BLOCK)) => DescriptorKind::UniformBuffer,
+            (_, (OP_TYPE_SAMPLER, _)) => DescriptorKind::Sampler,
+            (_, (OP_TYPE_SAMPLED_IMAGE, _)) => DescriptorKind::CombinedImageSampler,
+            //sampled type, dim, depth, arrayed, multisampled, sampled
+            (_, (OP_TYPE_IMAGE, operands)) => match (operands[1], operands[5])
+            {
+                (DIM_SUBPASS_DATA, _) => DescriptorKind::InputAttachment,
+                (DIM_BUFFER, 2) => DescriptorKind::StorageTexelBuffer,
+                (DIM_BUFFER, _) => DescriptorKind::UniformTexelBuffer,
+                (_, 2) => DescriptorKind::StorageImage,
+                _ => DescriptorKind::SampledImage
+            },
+            _ => return Err(format!("descriptor type {} is not supported", ty))
+        };
+
+        Ok(kind)
+    }
+
+    //bytes up to the end of the last member, laid out by the offsets and strides in the spir-v
+    //matrix_stride comes from the struct member the matrix is in
+    fn size_of(&self, ty : u32, matrix_stride : Option<u32>) -> Result<u32, String>
+    {
+        let too_big = || format!("type {} is too big", ty);
+
+        match self.type_of(ty)?
+        {
+            (OP_TYPE_INT, operands) | (OP_TYPE_FLOAT, operands) => Ok(operands[0] / 8),
+            (OP_TYPE_VECTOR, operands) => self.size_of(operands[0], None)?.checked_mul(operands[1]).ok_or_else(too_big),
+            (OP_TYPE_MATRIX, operands) =>
+            {
+                let column_size = self.size_of(operands[0], None)?;
+                matrix_stride.unwrap_or(column_size).checked_mul(operands[1].saturating_sub(1)).and_then(|size| size.checked_add(column_size)).ok_or_else(too_big)
+            },
+            (OP_TYPE_ARRAY, operands) =>
+            {
+                let element_size = self.size_of(operands[0], matrix_stride)?;
+                let stride = self.decorations.get(&(ty, DECORATION_ARRAY_STRIDE)).cloned().unwrap_or(element_size);
+                stride.checked_mul(self.constant(operands[1])?.saturating_sub(1)).and_then(|size| size.checked_add(element_size)).ok_or_else(too_big)
+            },
+            (OP_TYPE_STRUCT, members) =>
+            {
+                let mut size : u32 = 0;
+                for (member, &member_type) in members.iter().enumerate()
+                {
+                    let member = member as u32;
+                    let offset = self.member_decorations.get(&(ty, member, DECORATION_OFFSET)).cloned().unwrap_or(size);
+                    let stride = self.member_decorations.get(&(ty, member, DECORATION_MATRIX_STRIDE)).cloned();
+                    size = size.max(offset.checked_add(self.size_of(member_type, stride)?).ok_or_else(too_big)?);
+                }
+                Ok(size)
+            },
+            _ => Err(format!("type {} has no size", ty))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests
+{
+    use glsl_to_spirv;
+
+    use vulkano::format::Format;
+
+    use shaders::shader_interface::Descriptor;
+    use shaders::shader_interface::DescriptorKind;
+    use shaders::shader_interface::InterfaceVariable;
+    use shaders::shader_interface::ShaderInterface;
+
+    use std::io::Read;
+    use std::ops::Range;
+
+    fn compile(source : &str, ty : glsl_to_spirv::ShaderType) -> Vec<u8>
+    {
+        let mut spirv = Vec::new();
+        glsl_to_spirv::compile(source, ty).unwrap().read_to_end(&mut spirv).unwrap();
+        spirv
+    }
+
+    fn variable(locations : Range<u32>, format : Format) -> InterfaceVariable
+    {
+        InterfaceVariable{locations, format}
+    }
+
+    fn uniform_buffer(set : u32, binding : u32) -> Descriptor
+    {
+        Descriptor{set, binding, kind: DescriptorKind::UniformBuffer, array_count: 1}
+    }
+
+    fn mesh_vertex_interface() -> ShaderInterface
+    {
+        let source = include_str!(concat!(env!("CARGO_MANIFEST_DIR"), "/assets/shaders/mesh.vert"));
+        ShaderInterface::from_spirv(&compile(source, glsl_to_spirv::ShaderType::Vertex)).unwrap()
+    }
+
+    #[test]
+    fn the_mesh_vertex_shader_is_reflected()
+    {
+        let interface = mesh_vertex_interface();
+
+        assert_eq!(interface.inputs, vec![variable(0 .. 1, Format::R32G32B32Sfloat), variable(1 .. 2, Format::R32G32B32Sfloat), variable(2 .. 3, Format::R32G32Sfloat)]);
+        //gl_Position is a built in and not part of it
+        assert_eq!(interface.outputs, vec![variable(0 .. 1, Format::R32G32B32Sfloat), variable(1 .. 2, Format::R32G32Sfloat), variable(2 .. 3, Format::R32G32B32A32Sfloat)]);
+        assert_eq!(interface.descriptors, vec![uniform_buffer(0, 0), uniform_buffer(0, 1)]);
+        //a mat4, three vec4 and a vec4
+        assert_eq!(interface.push_constant_size, 64 + 48 + 16);
+    }
+
+    #[test]
+    fn the_mesh_fragment_shader_samples_a_texture()
+    {
+        let source = include_str!(concat!(env!("CARGO_MANIFEST_DIR"), "/assets/shaders/mesh.frag"));
+        let interface = ShaderInterface::from_spirv(&compile(source, glsl_to_spirv::ShaderType::Fragment)).unwrap();
+
+        assert_eq!(interface.outputs, vec![variable(0 .. 1, Format::R32G32B32A32Sfloat)]);
+        assert_eq!(interface.descriptors, vec![Descriptor{set: 1, binding: 0, kind: DescriptorKind::CombinedImageSampler, array_count: 1}, uniform_buffer(1, 1)]);
+        assert_eq!(interface.push_constant_size, 0);
+    }
+
+    #[test]
+    fn matrices_and_arrays_take_a_location_per_column_and_element()
+    {
+        let source = "#version 450\n\
+            layout(location = 0) in mat4 transform;\n\
+            layout(location = 4) in ivec2 offsets[3];\n\
+            layout(set = 0, binding = 2) uniform sampler2D layers[4];\n\
+            void main() { gl_Position = transform * texture(layers[1], vec2(offsets[0] + offsets[2])); }\n";
+
+        let interface = ShaderInterface::from_spirv(&compile(source, glsl_to_spirv::ShaderType::Vertex)).unwrap();
+
+        assert_eq!(interface.inputs, vec![variable(0 .. 4, Format::R32G32B32A32Sfloat), variable(4 .. 7, Format::R32G32Sint)]);
+        assert_eq!(interface.descriptors, vec![Descriptor{set: 0, binding: 2, kind: DescriptorKind::CombinedImageSampler, array_count: 4}]);
+    }
+
+    #[test]
+    fn broken_spirv_is_an_error()
+    {
+        let spirv = compile(include_str!(concat!(env!("CARGO_MANIFEST_DIR"), "/assets/shaders/mesh.vert")), glsl_to_spirv::ShaderType::Vertex);
+
+        assert!(ShaderInterface::from_spirv(&spirv[.. 6]).is_err());
+        assert!(ShaderInterface::from_spirv(&[0; 32]).is_err());
+        //the header and half of the first instruction
+        assert!(ShaderInterface::from_spirv(&spirv[.. 24]).is_err());
+    }
+
+    #[test]
+    fn a_shader_fits_its_own_interface()
+    {
+        let interface = mesh_vertex_interface();
+
+        assert_eq!(interface.mismatch(&interface), None);
+    }
+
+    #[test]
+    fn changed_inputs_and_outputs_do_not_fit()
+    {
+        let expected = mesh_vertex_interface();
+
+        let mut changed_input = expected.clone();
+        changed_input.inputs[2].format = Format::R32G32B32Sfloat;
+        assert!(changed_input.mismatch(&expected).is_some());
+
+        let mut dropped_output = expected.clone();
+        dropped_output.outputs.pop();
+        assert!(dropped_output.mismatch(&expected).is_some());
+    }
+
+    #[test]
+    fn descriptors_and_push_constants_have_to_be_in_the_layout()
+    {
+        let expected = mesh_vertex_interface();
+
+        let mut fewer = expected.clone();
+        fewer.descriptors.remove(0);
+        fewer.push_constant_size = 64;
+        assert_eq!(fewer.mismatch(&expected), None);
+
+        let mut extra_descriptor = expected.clone();
+        extra_descriptor.descriptors.push(uniform_buffer(2, 0));
+        assert!(extra_descriptor.mismatch(&expected).is_some());
+
+        let mut other_kind = expected.clone();
+        other_kind.descriptors[1].kind = DescriptorKind::StorageBuffer;
+        assert!(other_kind.mismatch(&expected).is_some());
+
+        let mut more_push_constants = expected.clone();
+        more_push_constants.push_constant_size += 16;
+        assert!(more_push_constants.mismatch(&expected).is_some());
+    }
+}
@@ -0,0 +1,453 @@
+use glsl_to_spirv;
+
+use vulkano::OomError;
+use vulkano::descriptor::descriptor::ShaderStages;
+use vulkano::device::Device;
+use vulkano::pipeline::shader::GraphicsEntryPoint;
+use vulkano::pipeline::shader::GraphicsShaderType;
+use vulkano::pipeline::shader::ShaderModule;
+
+use std::env;
+use std::error;
+use std::ffi::CStr;
+use std::fmt;
+use std::fs;
+use std::io;
+use std::io::Read;
+use std::path::Path;
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::sync::Mutex;
+use std::time::Duration;
+use std::time::Instant;
+use std::time::SystemTime;
+use std::vec::Vec;
+
+use shaders::shader_interface::ShaderInterface;
+
+//how often the shader files are checked for changes
+const POLL_INTERVAL_MILLISECONDS : u64 = 500;
+
+//set to the directory shaders are loaded from to use it instead of the default one
+pub const SHADER_DIRECTORY_VARIABLE : &str = "MOTOR_SHADER_DIRECTORY";
+//relative to the working directory, cargo run from the crate finds the shader sources there
+const DEFAULT_SHADER_DIRECTORY : &str = "assets/shaders";
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ShaderStage
+{
+    Vertex,
+    Fragment
+}
+
+impl ShaderStage
+{
+    //what a shader's Layout is created with
+    pub fn stages(&self) -> ShaderStages
+    {
+        match *self
+        {
+            ShaderStage::Vertex => ShaderStages { vertex: true, .. ShaderStages::none() },
+            ShaderStage::Fragment => ShaderStages { fragment: true, .. ShaderStages::none() }
+        }
+    }
+
+    fn glsl_type(&self) -> glsl_to_spirv::ShaderType
+    {
+        match *self
+        {
+            ShaderStage::Vertex => glsl_to_spirv::ShaderType::Vertex,
+            ShaderStage::Fragment => glsl_to_spirv::ShaderType::Fragment
+        }
+    }
+
+    fn graphics_type(&self) -> GraphicsShaderType
+    {
+        match *self
+        {
+            ShaderStage::Vertex => GraphicsShaderType::Vertex,
+            ShaderStage::Fragment => GraphicsShaderType::Fragment
+        }
+    }
+}
+
+#[derive(Debug)]
+pub enum ShaderError
+{
+    Io(io::Error),
+    //the compiler's output, with the file and line of every error
+    Compile(String),
+    //spir-v has to be a whole number of 32 bit words
+    InvalidSpirv,
+    //the shader reads or writes something the pipeline it is used in was not made for
+    Interface(String),
+    Module(OomError)
+}
+
+impl error::Error for ShaderError
+{
+    fn description(&self) -> &str
+    {
+        match *self
+        {
+            ShaderError::Io(_) => "error while reading the shader",
+            ShaderError::Compile(_) => "error while compiling the shader",
+            ShaderError::InvalidSpirv => "the shader is not valid spir-v",
+            ShaderError::Interface(_) => "the shader does not fit the interface it replaces",
+            ShaderError::Module(_) => "error while creating the shader module"
+        }
+    }
+
+    fn cause(&self) -> Option<&error::Error>
+    {
+        match *self
+        {
+            ShaderError::Io(ref err) => Some(err),
+            ShaderError::Module(ref err) => Some(err),
+            _ => None
+        }
+    }
+}
+
+impl fmt::Display for ShaderError
+{
+    fn fmt(&self, fmt : &mut fmt::Formatter) -> Result<(), fmt::Error>
+    {
+        match *self
+        {
+            ShaderError::Io(ref err) => write!(fmt, "{}: {}", error::Error::description(self), err),
+            ShaderError::Compile(ref log) => write!(fmt, "{}:\n{}", error::Error::description(self), log),
+            ShaderError::InvalidSpirv => write!(fmt, "{}", error::Error::description(self)),
+            ShaderError::Interface(ref difference) => write!(fmt, "{}: {}", error::Error::description(self), difference),
+            ShaderError::Module(ref err) => write!(fmt, "{}: {}", error::Error::description(self), err)
+        }
+    }
+}
+
+impl From<io::Error> for ShaderError
+{
+    fn from(err : io::Error) -> ShaderError
+    {
+        ShaderError::Io(err)
+    }
+}
+
+impl From<OomError> for ShaderError
+{
+    fn from(err : OomError) -> ShaderError
+    {
+        ShaderError::Module(err)
+    }
+}
+
+//the directory from the environment, or the default one when it is not set
+pub fn directory_from_env() -> PathBuf
+{
+    env::var_os(SHADER_DIRECTORY_VARIABLE).filter(|directory| !directory.is_empty()).map(PathBuf::from).unwrap_or_else(|| PathBuf::from(DEFAULT_SHADER_DIRECTORY))
+}
+
+//.spv files are loaded as they are, anything else is compiled as glsl for the stage
+pub fn load_spirv(path : &Path, stage : ShaderStage) -> Result<Vec<u8>, ShaderError>
+{
+    let mut spirv = Vec::new();
+
+    if path.extension().map_or(false, |extension| extension == "spv")
+    {
+        fs::File::open(path)?.read_to_end(&mut spirv)?;
+    }
+    else
+    {
+        let source = fs::read_to_string(path)?;
+        let mut compiled = glsl_to_spirv::compile(&source, stage.glsl_type()).map_err(|log| ShaderError::Compile(format!("{}: {}", path.display(), log)))?;
+        compiled.read_to_end(&mut spirv)?;
+    }
+
+    if spirv.is_empty() || spirv.len() % 4 != 0
+    {
+        return Err(ShaderError::InvalidSpirv);
+    }
+
+    Ok(spirv)
+}
+
+//the interface and layout come from the baked in copy of the shader, vulkano does not check the module matches them
+//modules from a ShaderHandle were checked against the ShaderInterface they were loaded with, anything else is up to the caller
+pub unsafe fn graphics_entry_point<'a, S, I, O, L>(module : &'a ShaderModule, stage : ShaderStage, input : I, output : O, layout : L) -> GraphicsEntryPoint<'a, S, I, O, L>
+{
+    module.graphics_entry_point(CStr::from_bytes_with_nul_unchecked(b"main\0"), input, output, layout, stage.graphics_type())
+}
+
+//a shader file that may be swapped out while running, drawers compare generations to know when to rebuild their pipelines
+pub struct ShaderHandle
+{
+    current : Mutex<(Arc<ShaderModule>, u64)>
+}
+
+impl ShaderHandle
+{
+    //the newest module that compiled, and how many times it has been replaced
+    pub fn module(&self) -> (Arc<ShaderModule>, u64)
+    {
+        self.current.lock().unwrap().clone()
+    }
+
+    fn replace(&self, module : Arc<ShaderModule>)
+    {
+        let mut current = self.current.lock().unwrap();
+        current.0 = module;
+        current.1 += 1;
+    }
+}
+
+struct WatchedShader
+{
+    path : PathBuf,
+    stage : ShaderStage,
+    //what every module that replaces the current one has to match
+    interface : ShaderInterface,
+    handle : Arc<ShaderHandle>,
+    modified : Option<SystemTime>
+}
+
+//loads shaders from a directory and reloads them when their files change
+pub struct ShaderLibrary
+{
+    device : Arc<Device>,
+    directory : PathBuf,
+    shaders : Vec<WatchedShader>,
+    last_poll : Instant
+}
+
+impl ShaderLibrary
+{
+    pub fn new<P : AsRef<Path>>(device : Arc<Device>, directory : P) -> ShaderLibrary
+    {
+        ShaderLibrary
+        {
+            device,
+            directory : directory.as_ref().to_path_buf(),
+            shaders : Vec::new(),
+            last_poll : Instant::now()
+        }
+    }
+
+    //name is relative to the library's directory, the fallback is used until the file exists, compiles and matches interface
+    //usually the fallback is the baked in copy of the same file and interface is ShaderInterface::of its generated types
+    pub fn load(&mut self, name : &str, stage : ShaderStage, interface : ShaderInterface, fallback : Arc<ShaderModule>) -> Arc<ShaderHandle>
+    {
+        let path = self.directory.join(name);
+
+        if let Some(watched) = self.shaders.iter().find(|watched| watched.path == path && watched.stage == stage)
+        {
+            return watched.handle.clone();
+        }
+
+        let handle = Arc::new(ShaderHandle
+        {
+            current : Mutex::new((fallback, 0))
+        });
+
+        let modified = modified_time(&path);
+
+        //files that are missing now get picked up by poll once they show up
+        if modified.is_some()
+        {
+            match create_module(self.device.clone(), &path, stage, &interface)
+            {
+                Ok(module) => handle.replace(module),
+                Err(err) => println!("Could not load shader {}, using the built in one: {}", path.display(), err)
+            }
+        }
+
+        self.shaders.push(WatchedShader
+        {
+            path,
+            stage,
+            interface,
+            handle : handle.clone(),
+            modified
+        });
+
+        handle
+    }
+
+    //reloads every shader whose file changed since it was last loaded, returns the ones that were replaced
+    //a shader that fails to compile or no longer matches its interface is reported and the previous one stays in use
+    pub fn poll(&mut self) -> Vec<PathBuf>
+    {
+        if self.last_poll.elapsed() < Duration::from_millis(POLL_INTERVAL_MILLISECONDS)
+        {
+            return Vec::new();
+        }
+        self.last_poll = Instant::now();
+
+        self.reload_changed()
+    }
+
+    fn reload_changed(&mut self) -> Vec<PathBuf>
+    {
+        let mut reloaded = Vec::new();
+
+        for watched in self.shaders.iter_mut()
+        {
+            let modified = modified_time(&watched.path);
+
+            if modified.is_none() || modified == watched.modified
+            {
+                continue;
+            }
+
+            //only try again once the file changes again
+            watched.modified = modified;
+
+            match create_module(self.device.clone(), &watched.path, watched.stage, &watched.interface)
+            {
+                Ok(module) =>
+                {
+                    println!("Reloaded shader {}", watched.path.display());
+                    watched.handle.replace(module);
+                    reloaded.push(watched.path.clone());
+                },
+                Err(err) => println!("Could not reload shader {}, keeping the previous one: {}", watched.path.display(), err)
+            }
+        }
+
+        reloaded
+    }
+}
+
+fn modified_time(path : &Path) -> Option<SystemTime>
+{
+    fs::metadata(path).and_then(|metadata| metadata.modified()).ok()
+}
+
+fn create_module(device : Arc<Device>, path : &Path, stage : ShaderStage, interface : &ShaderInterface) -> Result<Arc<ShaderModule>, ShaderError>
+{
+    let spirv = load_spirv(path, stage)?;
+
+    let reflected = ShaderInterface::from_spirv(&spirv).map_err(ShaderError::Interface)?;
+    if let Some(difference) = reflected.mismatch(interface)
+    {
+        return Err(ShaderError::Interface(difference));
+    }
+
+    //past the interface the spir-v is only checked by the driver, a broken module fails once a pipeline is built with it
+    Ok(unsafe { ShaderModule::new(device, &spirv)? })
+}
+
+#[cfg(test)]
+mod tests
+{
+    use vulkano::device::Device;
+    use vulkano::device::DeviceExtensions;
+    use vulkano::instance::Features;
+    use vulkano::instance::Instance;
+    use vulkano::instance::InstanceExtensions;
+    use vulkano::instance::PhysicalDevice;
+    use vulkano::pipeline::shader::ShaderModule;
+
+    use shaders::shader_interface::ShaderInterface;
+    use shaders::shader_library::load_spirv;
+    use shaders::shader_library::ShaderLibrary;
+    use shaders::shader_library::ShaderStage;
+
+    use std::env;
+    use std::fs;
+    use std::path::Path;
+    use std::process;
+    use std::sync::Arc;
+
+    const SHADER_NAME : &str = "reload.vert";
+
+    const ORIGINAL : &str = "#version 450\n\
+        layout(location = 0) in vec2 position;\n\
+        layout(location = 0) out vec4 v_color;\n\
+        void main() { v_color = vec4(1.0); gl_Position = vec4(position, 0.0, 1.0); }\n";
+
+    const EDITED : &str = "#version 450\n\
+        layout(location = 0) in vec2 position;\n\
+        layout(location = 0) out vec4 v_color;\n\
+        void main() { v_color = vec4(position, 0.0, 1.0); gl_Position = vec4(position * 0.5, 0.0, 1.0); }\n";
+
+    const BROKEN : &str = "#version 450\n\
+        void main() { gl_Position = undeclared; }\n";
+
+    //compiles, but reads a vertex attribute the pipeline does not provide
+    const MISMATCHED : &str = "#version 450\n\
+        layout(location = 0) in vec2 position;\n\
+        layout(location = 1) in vec4 color;\n\
+        layout(location = 0) out vec4 v_color;\n\
+        void main() { v_color = color; gl_Position = vec4(position, 0.0, 1.0); }\n";
+
+    //None on machines without a vulkan driver, the test has nothing to create modules with there
+    fn create_device() -> Option<Arc<Device>>
+    {
+        let instance = Instance::new(None, &InstanceExtensions::none(), None).ok()?;
+        let physical = PhysicalDevice::enumerate(&instance).next()?;
+        let queue_family = physical.queue_families().next()?;
+
+        Device::new(physical, &Features::none(), &DeviceExtensions::none(), [(queue_family, 0.5)].iter().cloned()).ok().map(|(device, _)| device)
+    }
+
+    //file timestamps can be too coarse to tell writes in the same test apart, so the library is told the file changed
+    fn write_and_reload(library : &mut ShaderLibrary, path : &Path, source : &str) -> usize
+    {
+        fs::write(path, source).unwrap();
+        library.shaders[0].modified = None;
+        library.reload_changed().len()
+    }
+
+    #[test]
+    fn poll_reloads_changed_shaders_and_keeps_the_previous_one_on_failure()
+    {
+        let device = match create_device()
+        {
+            Some(device) => device,
+            None =>
+            {
+                println!("No vulkan device, skipping the shader reload test");
+                return;
+            }
+        };
+
+        let directory = env::temp_dir().join(format!("motor_shader_reload_{}", process::id()));
+        fs::create_dir_all(&directory).unwrap();
+        let path = directory.join(SHADER_NAME);
+        fs::write(&path, ORIGINAL).unwrap();
+
+        let spirv = load_spirv(&path, ShaderStage::Vertex).unwrap();
+        let interface = ShaderInterface::from_spirv(&spirv).unwrap();
+        let fallback = unsafe { ShaderModule::new(device.clone(), &spirv).unwrap() };
+
+        let mut library = ShaderLibrary::new(device, &directory);
+        let handle = library.load(SHADER_NAME, ShaderStage::Vertex, interface, fallback.clone());
+
+        //the file existed, so it replaced the fallback
+        let (loaded, generation) = handle.module();
+        assert_eq!(generation, 1);
+        assert!(!Arc::ptr_eq(&loaded, &fallback));
+
+        //nothing changed since it was loaded
+        assert!(library.reload_changed().is_empty());
+        assert_eq!(handle.module().1, 1);
+
+        assert_eq!(write_and_reload(&mut library, &path, EDITED), 1);
+        let (edited, generation) = handle.module();
+        assert_eq!(generation, 2);
+        assert!(!Arc::ptr_eq(&edited, &loaded));
+
+        for source in [BROKEN, MISMATCHED].iter()
+        {
+            assert_eq!(write_and_reload(&mut library, &path, source), 0);
+            let (kept, generation) = handle.module();
+            assert_eq!(generation, 2);
+            assert!(Arc::ptr_eq(&kept, &edited));
+        }
+
+        //fixing the file picks it up again
+        assert_eq!(write_and_reload(&mut library, &path, ORIGINAL), 1);
+        assert_eq!(handle.module().1, 3);
+
+        fs::remove_dir_all(&directory).unwrap();
+    }
+}
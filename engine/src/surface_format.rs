@@ -0,0 +1,129 @@
+use vulkano::format::Format;
+use vulkano::swapchain::ColorSpace;
+
+use color;
+
+use std::vec::Vec;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum SurfaceFormatPreference
+{
+    //8 bit sRGB, encoded by the hardware
+    Srgb,
+    //10 bit or half float output where the surface offers it, sRGB otherwise
+    //vulkano only creates swapchains in the sRGB non linear color space, so this buys precision and not a wider gamut
+    Hdr
+}
+
+impl Default for SurfaceFormatPreference
+{
+    fn default() -> SurfaceFormatPreference
+    {
+        SurfaceFormatPreference::Srgb
+    }
+}
+
+const SRGB_FORMATS : [Format; 3] = [Format::B8G8R8A8Srgb, Format::R8G8B8A8Srgb, Format::A8B8G8R8SrgbPack32];
+
+const HDR_FORMATS : [Format; 3] = [Format::A2B10G10R10UnormPack32, Format::A2R10G10B10UnormPack32, Format::R16G16B16A16Sfloat];
+
+//the formats a swapchain can be created with, see SurfaceFormatPreference::Hdr
+fn usable(supported : &[(Format, ColorSpace)]) -> Vec<Format>
+{
+    supported.iter().filter(|&&(_, color_space)| color_space == ColorSpace::SrgbNonLinear).map(|&(format, _)| format).collect()
+}
+
+//true when SurfaceFormatPreference::Hdr would get anything other than 8 bit sRGB
+pub fn hdr_available(supported : &[(Format, ColorSpace)]) -> bool
+{
+    usable(supported).iter().any(|format| HDR_FORMATS.contains(format))
+}
+
+//the preferred formats in order, then any sRGB format, then whatever the surface lists first
+//None when the surface lists nothing in the sRGB color space
+pub fn choose_surface_format(supported : &[(Format, ColorSpace)], preference : SurfaceFormatPreference) -> Option<Format>
+{
+    let usable = usable(supported);
+
+    let preferred = match preference
+    {
+        SurfaceFormatPreference::Hdr => HDR_FORMATS.iter().chain(SRGB_FORMATS.iter()).cloned().collect::<Vec<_>>(),
+        SurfaceFormatPreference::Srgb => SRGB_FORMATS.to_vec()
+    };
+
+    preferred.into_iter().find(|format| usable.contains(format))
+        .or_else(|| usable.iter().cloned().find(|&format| color::is_srgb_format(format)))
+        .or_else(|| usable.first().cloned())
+}
+
+#[cfg(test)]
+mod tests
+{
+    use vulkano::format::Format;
+    use vulkano::swapchain::ColorSpace;
+
+    use surface_format::choose_surface_format;
+    use surface_format::hdr_available;
+    use surface_format::SurfaceFormatPreference;
+
+    fn srgb_space(formats : &[Format]) -> Vec<(Format, ColorSpace)>
+    {
+        formats.iter().map(|&format| (format, ColorSpace::SrgbNonLinear)).collect()
+    }
+
+    #[test]
+    fn srgb_is_preferred_over_unorm_listed_first()
+    {
+        let supported = srgb_space(&[Format::B8G8R8A8Unorm, Format::B8G8R8A8Srgb]);
+
+        assert_eq!(choose_surface_format(&supported, SurfaceFormatPreference::Srgb), Some(Format::B8G8R8A8Srgb));
+    }
+
+    #[test]
+    fn srgb_is_preferred_over_whatever_the_surface_lists_first()
+    {
+        let supported = srgb_space(&[Format::R16G16B16A16Sfloat, Format::A2B10G10R10UnormPack32, Format::R8G8B8A8Srgb]);
+
+        assert_eq!(choose_surface_format(&supported, SurfaceFormatPreference::Srgb), Some(Format::R8G8B8A8Srgb));
+    }
+
+    #[test]
+    fn the_first_format_is_used_without_any_srgb_one()
+    {
+        let supported = srgb_space(&[Format::B8G8R8A8Unorm, Format::R8G8B8A8Unorm]);
+
+        assert_eq!(choose_surface_format(&supported, SurfaceFormatPreference::Srgb), Some(Format::B8G8R8A8Unorm));
+    }
+
+    #[test]
+    fn hdr_is_used_where_the_surface_offers_it()
+    {
+        let supported = srgb_space(&[Format::B8G8R8A8Srgb, Format::A2B10G10R10UnormPack32]);
+
+        assert!(hdr_available(&supported));
+        assert_eq!(choose_surface_format(&supported, SurfaceFormatPreference::Hdr), Some(Format::A2B10G10R10UnormPack32));
+        assert_eq!(choose_surface_format(&supported, SurfaceFormatPreference::Srgb), Some(Format::B8G8R8A8Srgb));
+    }
+
+    #[test]
+    fn hdr_falls_back_to_srgb()
+    {
+        let supported = srgb_space(&[Format::B8G8R8A8Unorm, Format::B8G8R8A8Srgb]);
+
+        assert!(!hdr_available(&supported));
+        assert_eq!(choose_surface_format(&supported, SurfaceFormatPreference::Hdr), Some(Format::B8G8R8A8Srgb));
+    }
+
+    #[test]
+    fn other_color_spaces_are_left_out()
+    {
+        let supported = vec!(
+            (Format::R16G16B16A16Sfloat, ColorSpace::ExtendedSrgbLinear),
+            (Format::A2B10G10R10UnormPack32, ColorSpace::DisplayP3NonLinear),
+            (Format::R8G8B8A8Srgb, ColorSpace::SrgbNonLinear));
+
+        assert!(!hdr_available(&supported));
+        assert_eq!(choose_surface_format(&supported, SurfaceFormatPreference::Hdr), Some(Format::R8G8B8A8Srgb));
+        assert_eq!(choose_surface_format(&supported[.. 2], SurfaceFormatPreference::Srgb), None);
+    }
+}
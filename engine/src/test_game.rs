@@ -0,0 +1,192 @@
+use ecs::join::Join;
+use ecs::scheduler::Scheduler;
+use ecs::system::RenderSystem;
+use ecs::system::System;
+use ecs::system::SystemAccess;
+use ecs::world::World;
+use engine_error::EngineError;
+use jobs::job_system::JobSystem;
+use vulkano_instance::PipelineImplementer;
+use drawers::drawer_mesh::Mesh;
+use assets::obj;
+use math::Mat4;
+use math::Quat;
+use math::Vec3;
+
+use std::path::Path;
+use std::sync::Arc;
+use std::time::Instant;
+
+//seconds since the previous tick
+pub struct DeltaTime(pub f32);
+
+pub struct Position(pub Vec3);
+
+pub struct Velocity(pub Vec3);
+
+pub struct Rotation(pub Quat);
+
+pub struct Spin
+{
+    pub axis : Vec3,
+    pub radians_per_second : f32
+}
+
+pub struct Renderable
+{
+    pub mesh : Arc<Mesh>,
+    pub scale : f32
+}
+
+//keeps everything that moves inside a box around the origin
+const ARENA_HALF_SIZE : f32 = 4.0;
+
+struct MovementSystem;
+
+impl System for MovementSystem
+{
+    fn access(&self) -> SystemAccess
+    {
+        SystemAccess::new().write::<Position>().write::<Velocity>().read::<DeltaTime>()
+    }
+
+    fn run(&mut self, world : &World)
+    {
+        let delta_time = world.read_resource::<DeltaTime>().0;
+        let mut positions = world.write::<Position>();
+        let mut velocities = world.write::<Velocity>();
+
+        for (_, (position, velocity)) in (&mut *positions, &mut *velocities).join()
+        {
+            position.0 = position.0 + velocity.0 * delta_time;
+
+            //bounce off the walls of the arena
+            for axis in 0..3
+            {
+                if position.0[axis].abs() > ARENA_HALF_SIZE
+                {
+                    position.0[axis] = position.0[axis].max(-ARENA_HALF_SIZE).min(ARENA_HALF_SIZE);
+                    velocity.0[axis] = -velocity.0[axis];
+                }
+            }
+        }
+    }
+}
+
+//does not touch what MovementSystem writes, so the two run at the same time
+struct SpinSystem;
+
+impl System for SpinSystem
+{
+    fn access(&self) -> SystemAccess
+    {
+        SystemAccess::new().write::<Rotation>().read::<Spin>().read::<DeltaTime>()
+    }
+
+    fn run(&mut self, world : &World)
+    {
+        let delta_time = world.read_resource::<DeltaTime>().0;
+        let mut rotations = world.write::<Rotation>();
+        let spins = world.read::<Spin>();
+
+        for (_, (rotation, spin)) in (&mut *rotations, &*spins).join()
+        {
+            rotation.0 = (Quat::from_axis_angle(spin.axis, spin.radians_per_second * delta_time) * rotation.0).normalize();
+        }
+    }
+}
+
+struct MeshRenderSystem;
+
+impl RenderSystem for MeshRenderSystem
+{
+    fn render(&mut self, world : &World, renderer : &mut PipelineImplementer)
+    {
+        let positions = world.read::<Position>();
+        let rotations = world.read::<Rotation>();
+        let renderables = world.read::<Renderable>();
+
+        for (_, (position, rotation, renderable)) in (&*positions, &*rotations, &*renderables).join()
+        {
+            renderer.draw_mesh(renderable.mesh.clone(), Mat4::from_translation_rotation_scale(position.0, rotation.0, Vec3::splat(renderable.scale)));
+        }
+    }
+}
+
+//cubes bouncing around an arena, a small game to drive the engine with
+pub struct TestGame
+{
+    world : World,
+    scheduler : Scheduler,
+    last_tick : Instant
+}
+
+impl TestGame
+{
+    //systems that do not conflict run as jobs on the given job system
+    pub fn new(renderer : &mut PipelineImplementer, jobs : Arc<JobSystem>) -> Result<TestGame, EngineError>
+    {
+        let model = obj::load_obj(Path::new(concat!(env!("CARGO_MANIFEST_DIR"), "/assets/models/cube.obj")))?;
+        let mesh_data = model.merged_mesh();
+        let mesh = renderer.create_mesh(&mesh_data.vertices, &mesh_data.indices)?;
+
+        let mut world = World::new();
+        world.insert_resource(DeltaTime(0.0));
+
+        //spread out on a ring, with speeds and spins that differ enough to tell them apart
+        let cube_count = 24;
+        for index in 0..cube_count
+        {
+            let angle = index as f32 / cube_count as f32 * 2.0 * ::std::f32::consts::PI;
+            let direction = Vec3::new(angle.cos(), (angle * 3.0).sin() * 0.5, angle.sin());
+
+            let entity = world.create_entity();
+            world.add_component(entity, Position(direction * 2.0));
+            world.add_component(entity, Velocity(direction * (1.0 + (index % 4) as f32 * 0.5)));
+            world.add_component(entity, Rotation(Quat::identity()));
+            world.add_component(entity, Spin{axis: Vec3::new(direction.z, 1.0, direction.x).normalize(), radians_per_second: 1.0 + (index % 3) as f32});
+            world.add_component(entity, Renderable{mesh: mesh.clone(), scale: 0.3});
+        }
+
+        let mut scheduler = Scheduler::new(jobs);
+        scheduler.add_system(MovementSystem);
+        scheduler.add_system(SpinSystem);
+        scheduler.add_render_system(MeshRenderSystem);
+
+        {
+            let camera = renderer.camera();
+            camera.position = Vec3::new(0.0, 3.0, ARENA_HALF_SIZE * 3.0);
+            camera.look_at(Vec3::zero(), Vec3::unit_y());
+        }
+
+        Ok(TestGame
+        {
+            world,
+            scheduler,
+            last_tick : Instant::now()
+        })
+    }
+
+    //one frame of game logic and rendering
+    //a frame that had to be skipped is not an error
+    pub fn tick(&mut self, renderer : &mut PipelineImplementer) -> Result<(), EngineError>
+    {
+        let now = Instant::now();
+        let elapsed = now - self.last_tick;
+        self.last_tick = now;
+
+        //a long hitch should not launch everything through the walls
+        self.world.write_resource::<DeltaTime>().0 = (elapsed.as_secs() as f32 + elapsed.subsec_nanos() as f32 * 1e-9).min(0.1);
+
+        match renderer.begin_render()
+        {
+            Ok(()) => {},
+            Err(EngineError::SwapchainOutOfDate) => return Ok(()),
+            Err(err) => return Err(err)
+        }
+
+        self.scheduler.run(&self.world, renderer);
+
+        renderer.end_render()
+    }
+}
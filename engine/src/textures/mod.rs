@@ -0,0 +1,3 @@
+//outside interface
+pub mod texture;
+pub mod texture_cache;
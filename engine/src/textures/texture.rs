@@ -0,0 +1,313 @@
+extern crate image;
+
+use vulkano::buffer::BufferUsage;
+use vulkano::buffer::CpuAccessibleBuffer;
+use vulkano::command_buffer::AutoCommandBufferBuilder;
+use vulkano::command_buffer::CommandBuffer;
+use vulkano::device::Queue;
+use vulkano::format::Format;
+use vulkano::image::Dimensions;
+use vulkano::image::ImageLayout;
+use vulkano::image::ImageUsage;
+use vulkano::image::ImmutableImage;
+use vulkano::sampler::Filter;
+use vulkano::sampler::MipmapMode;
+use vulkano::sampler::Sampler;
+use vulkano::sampler::SamplerAddressMode;
+use vulkano::sync::GpuFuture;
+
+use color;
+use engine_error::EngineError;
+
+use std::error;
+use std::fmt;
+use std::path::Path;
+use std::sync::Arc;
+use std::vec::Vec;
+
+//color textures, sampling decodes them to linear
+const TEXTURE_FORMAT : Format = Format::R8G8B8A8Srgb;
+
+//an image the drawers can sample, with the sampler it is meant to be read with
+pub struct Texture
+{
+    image : Arc<ImmutableImage<Format>>,
+    sampler : Arc<Sampler>,
+    dimensions : [u32; 2],
+    mip_levels : u32
+}
+
+impl Texture
+{
+    //pixels are tightly packed RGBA8 rows, top row first
+    //the returned future finishes the upload and has to run before the texture is sampled
+    pub fn from_pixels(queue : Arc<Queue>, dimensions : [u32; 2], pixels : &[u8], mipmaps : bool) -> Result<(Arc<Texture>, Box<GpuFuture>), EngineError>
+    {
+        check_pixels(dimensions, pixels)?;
+
+        let levels = if mipmaps
+        {
+            generate_mip_chain(dimensions, pixels)
+        }
+        else
+        {
+            vec![(dimensions, pixels.to_vec())]
+        };
+
+        let device = queue.device().clone();
+
+        let usage = ImageUsage
+        {
+            transfer_destination: true,
+            sampled: true,
+            .. ImageUsage::none()
+        };
+
+        let (image, initialization) = ImmutableImage::uninitialized(device.clone(),
+            Dimensions::Dim2d { width: dimensions[0], height: dimensions[1] },
+            TEXTURE_FORMAT, levels.len() as u32, usage, ImageLayout::ShaderReadOnlyOptimal,
+            Some(queue.family()))?;
+
+        //every mip level is copied into the same image
+        let initialization = Arc::new(initialization);
+
+        let mut command_buffer_builder = AutoCommandBufferBuilder::new(device.clone(), queue.family())?;
+
+        for (level, &(level_dimensions, ref level_pixels)) in levels.iter().enumerate()
+        {
+            let buffer = CpuAccessibleBuffer::from_iter(device.clone(), BufferUsage::transfer_source(), level_pixels.iter().cloned())?;
+
+            command_buffer_builder = command_buffer_builder.copy_buffer_to_image_dimensions(buffer, initialization.clone(),
+                [0, 0, 0], [level_dimensions[0], level_dimensions[1], 1], 0, 1, level as u32).map_err(EngineError::command_buffer)?;
+        }
+
+        let future = command_buffer_builder.build().map_err(EngineError::command_buffer)?.execute(queue.clone())?;
+
+        let mip_levels = levels.len() as u32;
+
+        let sampler = Sampler::new(device, Filter::Linear, Filter::Linear, MipmapMode::Linear,
+            SamplerAddressMode::Repeat, SamplerAddressMode::Repeat, SamplerAddressMode::Repeat,
+            0.0, 1.0, 0.0, (mip_levels - 1) as f32)?;
+
+        Ok((Arc::new(Texture
+        {
+            image,
+            sampler,
+            dimensions,
+            mip_levels
+        }), Box::new(future) as Box<GpuFuture>))
+    }
+
+    pub fn image(&self) -> Arc<ImmutableImage<Format>>
+    {
+        self.image.clone()
+    }
+
+    pub fn sampler(&self) -> Arc<Sampler>
+    {
+        self.sampler.clone()
+    }
+
+    pub fn dimensions(&self) -> [u32; 2]
+    {
+        self.dimensions
+    }
+
+    pub fn mip_levels(&self) -> u32
+    {
+        self.mip_levels
+    }
+}
+
+#[derive(Debug)]
+pub enum TextureError
+{
+    //the file could not be read or is not a png, tga or bmp we understand
+    Decode(image::ImageError),
+    //a width or height of 0
+    EmptyDimensions([u32; 2]),
+    //the pixels are not dimensions worth of RGBA8
+    PixelCount{expected : usize, actual : usize}
+}
+
+impl error::Error for TextureError
+{
+    fn description(&self) -> &str
+    {
+        match *self
+        {
+            TextureError::Decode(_) => "error while decoding the texture",
+            TextureError::EmptyDimensions(_) => "the texture has no pixels",
+            TextureError::PixelCount{..} => "the pixels do not match the texture's dimensions"
+        }
+    }
+
+    fn cause(&self) -> Option<&error::Error>
+    {
+        match *self
+        {
+            TextureError::Decode(ref err) => Some(err),
+            _ => None
+        }
+    }
+}
+
+impl fmt::Display for TextureError
+{
+    fn fmt(&self, fmt : &mut fmt::Formatter) -> Result<(), fmt::Error>
+    {
+        match *self
+        {
+            TextureError::Decode(ref err) => write!(fmt, "{}: {}", error::Error::description(self), err),
+            TextureError::EmptyDimensions(dimensions) => write!(fmt, "{} ({}x{})", error::Error::description(self), dimensions[0], dimensions[1]),
+            TextureError::PixelCount{expected, actual} => write!(fmt, "{} (expected {} bytes, got {})", error::Error::description(self), expected, actual)
+        }
+    }
+}
+
+impl From<image::ImageError> for TextureError
+{
+    fn from(err : image::ImageError) -> TextureError
+    {
+        TextureError::Decode(err)
+    }
+}
+
+//what from_pixels accepts, tightly packed RGBA8 for at least one pixel
+pub fn check_pixels(dimensions : [u32; 2], pixels : &[u8]) -> Result<(), TextureError>
+{
+    if dimensions[0] == 0 || dimensions[1] == 0
+    {
+        return Err(TextureError::EmptyDimensions(dimensions));
+    }
+
+    let expected = dimensions[0] as usize * dimensions[1] as usize * 4;
+    if pixels.len() != expected
+    {
+        return Err(TextureError::PixelCount{expected, actual: pixels.len()});
+    }
+
+    Ok(())
+}
+
+//decodes a png, tga or bmp file to tightly packed RGBA8
+pub fn decode_file<P : AsRef<Path>>(path : P) -> Result<([u32; 2], Vec<u8>), TextureError>
+{
+    let image = image::open(path)?.to_rgba();
+
+    Ok(([image.width(), image.height()], image.into_raw()))
+}
+
+//every level down to 1x1, each one a box filtered half of the previous, the full image first
+//the pixels are sRGB like TEXTURE_FORMAT, so colors are averaged in linear space and encoded again, alpha is linear already
+pub fn generate_mip_chain(dimensions : [u32; 2], pixels : &[u8]) -> Vec<([u32; 2], Vec<u8>)>
+{
+    let mut levels = vec![(dimensions, pixels.to_vec())];
+
+    let to_linear : Vec<f32> = (0 .. 256).map(|value| color::srgb_to_linear(value as f32 / 255.0)).collect();
+
+    loop
+    {
+        let next =
+        {
+            let &(source_dimensions, ref source) = levels.last().unwrap();
+            if source_dimensions == [1, 1]
+            {
+                break;
+            }
+
+            let width = (source_dimensions[0] / 2).max(1);
+            let height = (source_dimensions[1] / 2).max(1);
+
+            //odd sizes clamp to the last row or column
+            let texel = |x : u32, y : u32, channel : u32|
+            {
+                let x = x.min(source_dimensions[0] - 1);
+                let y = y.min(source_dimensions[1] - 1);
+                let value = source[((y * source_dimensions[0] + x) * 4 + channel) as usize];
+                if channel == 3 { value as f32 / 255.0 } else { to_linear[value as usize] }
+            };
+
+            let mut pixels = Vec::with_capacity((width * height * 4) as usize);
+            for y in 0..height
+            {
+                for x in 0..width
+                {
+                    for channel in 0..4
+                    {
+                        let average = (texel(x * 2, y * 2, channel) + texel(x * 2 + 1, y * 2, channel)
+                            + texel(x * 2, y * 2 + 1, channel) + texel(x * 2 + 1, y * 2 + 1, channel)) / 4.0;
+                        let encoded = if channel == 3 { average } else { color::linear_to_srgb(average) };
+                        pixels.push((encoded * 255.0 + 0.5).max(0.0).min(255.0) as u8);
+                    }
+                }
+            }
+
+            ([width, height], pixels)
+        };
+
+        levels.push(next);
+    }
+
+    levels
+}
+
+#[cfg(test)]
+mod tests
+{
+    use textures::texture::check_pixels;
+    use textures::texture::generate_mip_chain;
+    use textures::texture::TextureError;
+
+    #[test]
+    fn mip_chain_goes_down_to_one_pixel()
+    {
+        let levels = generate_mip_chain([5, 2], &[128; 5 * 2 * 4]);
+        let dimensions : Vec<[u32; 2]> = levels.iter().map(|&(dimensions, _)| dimensions).collect();
+
+        assert_eq!(dimensions, vec![[5, 2], [2, 1], [1, 1]]);
+        for &(dimensions, ref pixels) in levels.iter()
+        {
+            assert_eq!(pixels.len(), (dimensions[0] * dimensions[1] * 4) as usize);
+            //a flat color stays the same color
+            assert!(pixels.iter().all(|&value| value == 128));
+        }
+    }
+
+    #[test]
+    fn mip_chain_averages_colors_in_linear_space()
+    {
+        //a black and white checker with half transparent black
+        let pixels = [
+            255, 255, 255, 255,   0, 0, 0, 0,
+            0, 0, 0, 0,           255, 255, 255, 255
+        ];
+        let levels = generate_mip_chain([2, 2], &pixels);
+        let smallest = &levels[1].1;
+
+        //half of linear white is about 188 once encoded, averaging the encoded bytes would give 128
+        for &value in smallest[0 .. 3].iter()
+        {
+            assert!(value >= 187 && value <= 188, "got {}", value);
+        }
+        assert_eq!(smallest[3], 128);
+    }
+
+    #[test]
+    fn pixels_have_to_match_the_dimensions()
+    {
+        assert!(check_pixels([2, 1], &[0; 8]).is_ok());
+
+        match check_pixels([2, 2], &[0; 8])
+        {
+            Err(TextureError::PixelCount{expected: 16, actual: 8}) => {},
+            other => panic!("expected a pixel count error, got {:?}", other)
+        }
+
+        match check_pixels([0, 2], &[])
+        {
+            Err(TextureError::EmptyDimensions([0, 2])) => {},
+            other => panic!("expected an empty dimensions error, got {:?}", other)
+        }
+    }
+}
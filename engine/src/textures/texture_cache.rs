@@ -0,0 +1,57 @@
+use textures::texture;
+use textures::texture::Texture;
+
+use vulkano::device::Queue;
+use vulkano::sync::GpuFuture;
+
+use engine_error::EngineError;
+
+use std::collections::HashMap;
+use std::path::Path;
+use std::path::PathBuf;
+use std::sync::Arc;
+
+//keeps every texture loaded from disk alive so asking for the same file again is free
+pub struct TextureCache
+{
+    queue : Arc<Queue>,
+    textures : HashMap<(PathBuf, bool), Arc<Texture>>
+}
+
+impl TextureCache
+{
+    pub fn new(queue : Arc<Queue>) -> TextureCache
+    {
+        TextureCache
+        {
+            queue,
+            textures : HashMap::new()
+        }
+    }
+
+    //the future is only returned when the texture had to be uploaded
+    pub fn load<P : AsRef<Path>>(&mut self, path : P, mipmaps : bool) -> Result<(Arc<Texture>, Option<Box<GpuFuture>>), EngineError>
+    {
+        //different spellings of the same file share one texture
+        let path = path.as_ref().canonicalize().unwrap_or(path.as_ref().to_path_buf());
+        let key = (path, mipmaps);
+
+        if let Some(texture) = self.textures.get(&key)
+        {
+            return Ok((texture.clone(), None));
+        }
+
+        let (dimensions, pixels) = texture::decode_file(&key.0)?;
+        let (texture, future) = Texture::from_pixels(self.queue.clone(), dimensions, &pixels, mipmaps)?;
+
+        self.textures.insert(key, texture.clone());
+
+        Ok((texture, Some(future)))
+    }
+
+    //textures still used elsewhere stay alive until those users drop them
+    pub fn clear(&mut self)
+    {
+        self.textures.clear();
+    }
+}
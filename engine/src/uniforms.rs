@@ -0,0 +1,273 @@
+use vulkano::descriptor::DescriptorSet;
+use vulkano::descriptor::descriptor_set::PersistentDescriptorSet;
+use vulkano::pipeline::GraphicsPipelineAbstract;
+
+use camera::Camera;
+use engine_error::EngineError;
+use frames::frame_context::FrameContext;
+use frames::frame_context::UniformUpload;
+use math::Mat4;
+
+use std::error;
+use std::fmt;
+use std::mem;
+use std::string::String;
+use std::sync::Arc;
+use std::vec::Vec;
+
+//data every shader can read, laid out std140 so the structs here match the glsl blocks byte for byte
+//per frame and per view data live in SHARED_SET, per object data is a push constant
+//the blocks are declared by the shaders themselves, see assets/shaders/mesh.vert, drawers check their copy with check_block
+
+//the descriptor set drawers leave to the shared uniforms, their own descriptors go in the sets after it
+pub const SHARED_SET : usize = 0;
+
+#[repr(C)]
+#[derive(Debug, Clone, Copy)]
+pub struct FrameUniforms
+{
+    //seconds since the renderer was created
+    pub time : f32,
+    pub delta_time : f32,
+    pub frame_number : u32,
+    _padding : u32
+}
+
+impl FrameUniforms
+{
+    pub fn layout() -> BlockLayout
+    {
+        BlockLayout::of(|block : &FrameUniforms| vec![field_offset(block, &block.time), field_offset(block, &block.delta_time), field_offset(block, &block.frame_number)])
+    }
+
+    pub fn new(time : f32, delta_time : f32, frame_number : u32) -> FrameUniforms
+    {
+        FrameUniforms
+        {
+            time,
+            delta_time,
+            frame_number,
+            _padding : 0
+        }
+    }
+}
+
+#[repr(C)]
+#[derive(Debug, Clone, Copy)]
+pub struct ViewUniforms
+{
+    pub view : [[f32; 4]; 4],
+    pub projection : [[f32; 4]; 4],
+    pub view_projection : [[f32; 4]; 4],
+    pub camera_position : [f32; 4]
+}
+
+impl ViewUniforms
+{
+    pub fn layout() -> BlockLayout
+    {
+        BlockLayout::of(|block : &ViewUniforms| vec![field_offset(block, &block.view), field_offset(block, &block.projection), field_offset(block, &block.view_projection), field_offset(block, &block.camera_position)])
+    }
+
+    pub fn from_camera(camera : &Camera) -> ViewUniforms
+    {
+        ViewUniforms
+        {
+            view : camera.view_matrix().into(),
+            projection : camera.projection_matrix().into(),
+            view_projection : camera.view_projection().into(),
+            camera_position : camera.position.extend(1.0).into()
+        }
+    }
+}
+
+//128 bytes, all the push constant space every device is guaranteed to have
+#[repr(C)]
+#[derive(Debug, Clone, Copy)]
+pub struct ObjectUniforms
+{
+    pub model : [[f32; 4]; 4],
+    //the inverse transpose of the model's upper 3x3, so normals stay perpendicular under non-uniform scale
+    //a vec4 per column, the padding std140 gives a mat3 anyway
+    pub normal_matrix : [[f32; 4]; 3],
+    //linear, multiplied with whatever the drawer outputs
+    pub tint : [f32; 4]
+}
+
+impl ObjectUniforms
+{
+    pub fn layout() -> BlockLayout
+    {
+        BlockLayout::of(|block : &ObjectUniforms| vec![field_offset(block, &block.model), field_offset(block, &block.normal_matrix), field_offset(block, &block.tint)])
+    }
+
+    pub fn new(model : Mat4, tint : [f32; 4]) -> ObjectUniforms
+    {
+        //a model scaled flat has no inverse, its normals are meaningless anyway
+        let normal_matrix = model.normal_matrix().unwrap_or_else(|| model.to_mat3());
+
+        ObjectUniforms
+        {
+            model : model.into(),
+            normal_matrix : [normal_matrix.columns[0].extend(0.0).into(), normal_matrix.columns[1].extend(0.0).into(), normal_matrix.columns[2].extend(0.0).into()],
+            tint
+        }
+    }
+}
+
+//blocks made only of floats, ints and arrays of them, so that all zero bytes are a valid value
+//unsafe to implement, BlockLayout::of builds a zeroed one
+pub unsafe trait PlainBlock : Copy
+{
+}
+
+unsafe impl PlainBlock for FrameUniforms
+{
+}
+
+unsafe impl PlainBlock for ViewUniforms
+{
+}
+
+unsafe impl PlainBlock for ObjectUniforms
+{
+}
+
+//the size of a uniform block and where each of its fields starts, in declaration order
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BlockLayout
+{
+    pub size : usize,
+    pub offsets : Vec<usize>
+}
+
+impl BlockLayout
+{
+    //fields returns the offsets of every field of the block it is given, through field_offset
+    pub fn of<Block : PlainBlock, F : FnOnce(&Block) -> Vec<usize>>(fields : F) -> BlockLayout
+    {
+        //fine zeroed, see PlainBlock, and only the addresses of the fields are used
+        let block : Block = unsafe { mem::zeroed() };
+
+        BlockLayout
+        {
+            size : mem::size_of::<Block>(),
+            offsets : fields(&block)
+        }
+    }
+}
+
+pub fn field_offset<Block, Field>(block : &Block, field : &Field) -> usize
+{
+    field as *const Field as usize - block as *const Block as usize
+}
+
+//a shader's declaration of a block does not lay it out like the struct uploaded into it
+#[derive(Debug)]
+pub struct BlockMismatch
+{
+    pub name : String,
+    pub shader : BlockLayout,
+    pub expected : BlockLayout
+}
+
+impl error::Error for BlockMismatch
+{
+    fn description(&self) -> &str
+    {
+        "a shader's uniform block does not match its struct"
+    }
+}
+
+impl fmt::Display for BlockMismatch
+{
+    fn fmt(&self, fmt : &mut fmt::Formatter) -> Result<(), fmt::Error>
+    {
+        write!(fmt, "shader block {} is {} bytes with fields at {:?}, uniforms::{} is {} bytes with fields at {:?}",
+            self.name, self.shader.size, self.shader.offsets, self.name, self.expected.size, self.expected.offsets)
+    }
+}
+
+//the shader blocks are generated from the glsl, a mismatch means a drawer's copy of a declaration went out of date
+pub fn check_block(name : &str, shader : BlockLayout, expected : BlockLayout) -> Result<(), EngineError>
+{
+    if shader != expected
+    {
+        return Err(EngineError::UniformBlock(BlockMismatch{name: name.to_string(), shader, expected}));
+    }
+
+    Ok(())
+}
+
+//uploaded once per frame into the frame's ring, shared by every drawer recording it
+pub struct SharedUniforms
+{
+    frame : Arc<UniformUpload<FrameUniforms>>,
+    view : Arc<UniformUpload<ViewUniforms>>
+}
+
+impl SharedUniforms
+{
+    pub fn upload(frame_context : &FrameContext, frame : FrameUniforms, view : ViewUniforms) -> Result<SharedUniforms, EngineError>
+    {
+        Ok(SharedUniforms
+        {
+            frame : Arc::new(frame_context.upload_uniform(frame)?),
+            view : Arc::new(frame_context.upload_uniform(view)?)
+        })
+    }
+
+    //SHARED_SET for the pipeline, the pipeline's shaders have to declare both FrameUniforms and ViewUniforms
+    pub fn descriptor_set(&self, pipeline : Arc<GraphicsPipelineAbstract + Send + Sync>) -> Result<Arc<DescriptorSet + Send + Sync>, EngineError>
+    {
+        Ok(Arc::new(PersistentDescriptorSet::start(pipeline, SHARED_SET)
+            .add_buffer(self.frame.clone()).map_err(EngineError::command_buffer)?
+            .add_buffer(self.view.clone()).map_err(EngineError::command_buffer)?
+            .build().map_err(EngineError::command_buffer)?))
+    }
+}
+
+#[cfg(test)]
+mod tests
+{
+    use uniforms::BlockLayout;
+    use uniforms::FrameUniforms;
+    use uniforms::ObjectUniforms;
+    use uniforms::PlainBlock;
+    use uniforms::ViewUniforms;
+    use uniforms::check_block;
+    use uniforms::field_offset;
+
+    #[test]
+    fn blocks_are_laid_out_std140()
+    {
+        assert_eq!(FrameUniforms::layout(), BlockLayout{size: 16, offsets: vec![0, 4, 8]});
+        assert_eq!(ViewUniforms::layout(), BlockLayout{size: 208, offsets: vec![0, 64, 128, 192]});
+        assert_eq!(ObjectUniforms::layout(), BlockLayout{size: 128, offsets: vec![0, 64, 112]});
+    }
+
+    #[test]
+    fn fields_in_a_different_place_do_not_match()
+    {
+        //as big as FrameUniforms, with the frame number first
+        #[repr(C)]
+        #[derive(Clone, Copy)]
+        struct Reordered
+        {
+            frame_number : u32,
+            time : f32,
+            delta_time : f32,
+            _padding : u32
+        }
+
+        unsafe impl PlainBlock for Reordered
+        {
+        }
+
+        let reordered = BlockLayout::of(|block : &Reordered| vec![field_offset(block, &block.time), field_offset(block, &block.delta_time), field_offset(block, &block.frame_number)]);
+        assert_eq!(reordered.size, FrameUniforms::layout().size);
+
+        assert!(check_block("FrameUniforms", FrameUniforms::layout(), FrameUniforms::layout()).is_ok());
+        assert!(check_block("FrameUniforms", reordered, FrameUniforms::layout()).is_err());
+    }
+}
@@ -1,11 +1,17 @@
 extern crate winit;
 
 use vulkano::instance::Instance;
+use vulkano::instance::InstanceExtensions;
 use vulkano::instance::PhysicalDevice;
+use vulkano::instance::QueueFamily;
+use vulkano::instance::Features;
 use vulkano::device::Device;
 use vulkano::device::DeviceExtensions;
+use vulkano::format::Format;
 use vulkano::framebuffer::Framebuffer;
+use vulkano::framebuffer::FramebufferAbstract;
 use vulkano::framebuffer::RenderPassAbstract;
+use vulkano::framebuffer::Subpass;
 use vulkano::swapchain;
 use vulkano::swapchain::Surface;
 use vulkano::swapchain::SurfaceTransform;
@@ -14,16 +20,20 @@ use vulkano::swapchain::Swapchain;
 use vulkano::swapchain::SwapchainAcquireFuture;
 use vulkano::swapchain::SwapchainCreationError;
 use vulkano::swapchain::AcquireError;
+use vulkano::image::AttachmentImage;
+use vulkano::image::ImageUsage;
 use vulkano::image::swapchain::SwapchainImage;
 
+use vulkano::command_buffer::AutoCommandBuffer;
 use vulkano::command_buffer::AutoCommandBufferBuilder;
+use vulkano::buffer::BufferUsage;
+use vulkano::buffer::CpuAccessibleBuffer;
 
 use vulkano::device::Queue; 
 use winit::Window;
 
 use vulkano::sync::now;
 use vulkano::sync::GpuFuture;
-use vulkano::sync::FlushError;
 
 use vulkano_win_frankenstein::vulkano_win_frankenstein;
 use vulkano_win_frankenstein::vulkano_win_frankenstein::VkSurfaceBuild;
@@ -33,250 +43,903 @@ use std::boxed::Box;
 use std::mem;
 use std::vec::Vec;
 use std::option::Option;
+use std::path::Path;
+use std::time::Duration;
+use std::time::Instant;
 
 use drawers::drawer_base::Drawer;
-use drawers::drawer_triangle;
-
-pub enum RenderError
+use drawers::drawer_registry::DrawerRegistry;
+use drawers::drawer_triangle::TriangleDrawer;
+use drawers::drawer_mesh::Mesh;
+use drawers::drawer_mesh::MeshDrawer;
+use drawers::drawer_mesh::MeshVertex;
+
+use device_selection;
+use device_selection::DeviceDescription;
+use device_selection::DeviceRequirements;
+use color;
+use engine_error::EngineError;
+use frames::frame_context::FrameContext;
+use frames::frame_ring::DEFAULT_FRAMES_IN_FLIGHT;
+use frames::frame_ring::FrameFence;
+use frames::frame_ring::FrameRing;
+use pipelines::pipeline_manager;
+use pipelines::pipeline_manager::PipelineManager;
+use present_mode::PresentModeConfig;
+use shaders::shader_library;
+use shaders::shader_library::ShaderLibrary;
+use surface_format;
+use surface_format::SurfaceFormatPreference;
+use frame_capture::CapturedFrame;
+use jobs::job_system::JobSystem;
+use textures::texture::Texture;
+use textures::texture_cache::TextureCache;
+use camera::Camera;
+use math::Mat4;
+use math::Vec3;
+use scene::scene_graph::Scene;
+use uniforms::FrameUniforms;
+use uniforms::SharedUniforms;
+use uniforms::ViewUniforms;
+
+//format of the image the headless renderer draws into, encoded like a window's so captures match
+const OFFSCREEN_FORMAT : Format = Format::R8G8B8A8Srgb;
+
+//cornflower blue, in sRGB
+const CLEAR_COLOR : [f32; 4] = [100f32 / 255f32, 149f32 / 255f32, 237f32 / 255f32, 1.0];
+
+const DEPTH_FORMAT : Format = Format::D16Unorm;
+
+//names and orders of the drawers every renderer starts with, 2d triangles go on top of the meshes
+pub const MESH_DRAWER_NAME : &str = "meshes";
+pub const MESH_DRAWER_ORDER : i32 = 0;
+pub const TRIANGLE_DRAWER_NAME : &str = "triangles";
+pub const TRIANGLE_DRAWER_ORDER : i32 = 100;
+
+//what the render pass ends up drawing into
+enum RenderTarget
 {
-    SwapchainOutOfDate
+    Window
+    {
+        surface : Arc<Surface<Window>>,
+        swapchain : Arc<Swapchain<Window>>,
+        images : Vec<Arc<SwapchainImage<Window>>>,
+        acquire_future : Option<SwapchainAcquireFuture<Window>>,
+        //applied whenever the swapchain is recreated
+        present_modes : PresentModeConfig,
+    },
+    Offscreen
+    {
+        image : Arc<AttachmentImage>,
+    }
 }
 
 pub struct VulkanoInstance
 {
     device : Arc<Device>,
     previous_frame_end_future : Option<Box<GpuFuture>>,
-    window : Arc<Surface<Window>>,
-    swapchain : Arc<Swapchain<Window>>,
-    images : Vec<Arc<SwapchainImage<Window>>>,
+    render_target : RenderTarget,
     render_pass : Arc<RenderPassAbstract + Send + Sync>,
+    depth_buffer : Arc<AttachmentImage>,
+    //one per swapchain image, or the single offscreen one, indexed by image_index
+    framebuffers : Vec<Arc<FramebufferAbstract + Send + Sync>>,
+    //what the frames the gpu has not finished yet are still using
+    frames : FrameRing,
+    //for the frame uniforms
+    start_time : Instant,
+    last_frame_time : Instant,
+    frame_number : u32,
     graphics_queue : Arc<Queue>,
     dimensions : [u32; 2],
+    camera : Camera,
     image_index : usize,
-    acquire_future : Option<SwapchainAcquireFuture<Window>>,
     command_buffer_builder : Option<AutoCommandBufferBuilder>, //maybe not option?
-    triangle_drawer : drawer_triangle::TriangleDrawer,
+    drawers : DrawerRegistry,
+    //drawers record their command buffers on it
+    jobs : Arc<JobSystem>,
+    texture_cache : TextureCache,
+    //the drawers' shaders, reloaded when their files change
+    shaders : ShaderLibrary,
+    //every drawer's pipelines, shared between drawers asking for the same one
+    pipelines : Arc<PipelineManager>,
+    capture_requested : bool,
+    captured_frame : Option<CapturedFrame>,
     pub should_recreate_swapchain : bool
 }
 
 impl VulkanoInstance
 {
-    pub fn new( event_loop : &mut winit::EventsLoop) -> VulkanoInstance
+    pub fn new( event_loop : &mut winit::EventsLoop, jobs : Arc<JobSystem>, present_modes : PresentModeConfig, format_preference : SurfaceFormatPreference) -> Result<VulkanoInstance, EngineError>
     {
         let vulkano_instance =
         {
             let extensions = vulkano_win_frankenstein::required_extensions();
-            Instance::new(None, &extensions, None).expect("Could not create Vulkan instance!")
+            Instance::new(None, &extensions, None)?
         };
         
         //move this to window ?
-        let window = winit::WindowBuilder::new().build_vk_surface(&event_loop, vulkano_instance.clone()).expect("Could not create window!");
+        let window = winit::WindowBuilder::new().build_vk_surface(&event_loop, vulkano_instance.clone())?;
         
-        let physical = PhysicalDevice::enumerate(&vulkano_instance).next().expect("Could not get physical device!");
-        println!("Using device: {}, (type: {:?})", physical.name(), physical.ty());
-
-        let queue_family = physical.queue_families().find(|&q|
+        let requirements = DeviceRequirements
         {
-            q.supports_graphics() && window.is_supported(q).unwrap_or(false)
-        }).expect("Could not find a queue that supports graphics commands!");
-
-        let(device, mut graphics_queues) = 
-        {
-            let device_ext = DeviceExtensions
+            extensions : DeviceExtensions
             {
                 khr_swapchain: true,
                 .. DeviceExtensions::none()
-            };
-
-            Device::new(physical, physical.supported_features(), &device_ext, 
-            [(queue_family, 0.5)].iter().cloned()).expect("Could not create device!")
+            },
+            features : Features::none()
         };
 
-        let graphics_queue = graphics_queues.next().expect("Could not get graphics queue from list!");
+        let (physical, queue_family) = choose_physical_device(&vulkano_instance, &requirements, |q|
+        {
+            q.supports_graphics() && window.is_supported(q).unwrap_or(false)
+        })?;
+
+        let(device, mut graphics_queues) = Device::new(physical, &requirements.features, &requirements.extensions, 
+            [(queue_family, 0.5)].iter().cloned())?;
+
+        let graphics_queue = graphics_queues.next().ok_or(EngineError::NoGraphicsQueue)?;
 
         let mut dimensions = 
         {   
-            let (width, height) = window.window().get_inner_size().expect("Could not get window inner size!");
+            let (width, height) = window.window().get_inner_size().ok_or(EngineError::WindowClosed)?;
             [width, height]
         };
 
         let (swapchain, images) = 
         {
-            let surface_capabilities = window.capabilities(physical).expect("Could not get surface capabilities");
+            let surface_capabilities = window.capabilities(physical)?;
 
             dimensions = surface_capabilities.current_extent.unwrap_or(dimensions);
 
-            let alpha_mode = surface_capabilities.supported_composite_alpha.iter().next().expect("No supported alpha mode for surface!");
-
-            let format = surface_capabilities.supported_formats[0].0;
-
-            Swapchain::new(
-                device.clone(), 
-                window.clone(), 
-                surface_capabilities.min_image_count, 
-                format, 
-                dimensions, 
-                1, 
-                surface_capabilities.supported_usage_flags,
-                &graphics_queue, 
-                SurfaceTransform::Identity, 
-                alpha_mode, 
-                PresentMode::Mailbox, 
-                true, 
-                None).expect("Could not create swapchain!")
+            let format = surface_format::choose_surface_format(&surface_capabilities.supported_formats, format_preference).ok_or(EngineError::UnsupportedSurface)?;
+            println!("Surface format: {:?}, (hdr available: {})", format, surface_format::hdr_available(&surface_capabilities.supported_formats));
+
+            create_swapchain(&device, &window, &graphics_queue, format, dimensions, &present_modes, None)?
         };
-        let previous_frame_end_future = Box::new(now(device.clone())) as Box<GpuFuture>;
-
-        let render_pass = Arc::new(single_pass_renderpass!(device.clone(),
-            attachments: {
-                color: {
-                    load: Clear,
-                    store: Store,
-                    format: swapchain.format(),
-                    samples: 1,
-                }
-            },
-            pass: {
-                color: [color],
-                depth_stencil: {}
-            }
-        ).expect("Could not create render pass!"));
 
-        let triangle_drawer = drawer_triangle::TriangleDrawer::new(device.clone());
+        let render_pass = create_render_pass(device.clone(), swapchain.format())?;
 
-        VulkanoInstance{
-            device,
-            previous_frame_end_future : Some(previous_frame_end_future),
-            window,
+        let render_target = RenderTarget::Window
+        {
+            surface : window,
             swapchain,
             images,
+            acquire_future : None,
+            present_modes,
+        };
+
+        VulkanoInstance::from_parts(device, graphics_queue, render_pass, render_target, dimensions, jobs)
+    }
+
+    //renders into an offscreen image instead of a window, for machines without a display
+    pub fn new_headless(dimensions : [u32; 2], jobs : Arc<JobSystem>) -> Result<VulkanoInstance, EngineError>
+    {
+        let vulkano_instance = Instance::new(None, &InstanceExtensions::none(), None)?;
+
+        let requirements = DeviceRequirements
+        {
+            extensions : DeviceExtensions::none(),
+            features : Features::none()
+        };
+
+        let (physical, queue_family) = choose_physical_device(&vulkano_instance, &requirements, |q| q.supports_graphics())?;
+
+        let(device, mut graphics_queues) = Device::new(physical, &requirements.features, &requirements.extensions,
+            [(queue_family, 0.5)].iter().cloned())?;
+
+        let graphics_queue = graphics_queues.next().ok_or(EngineError::NoGraphicsQueue)?;
+
+        let image_usage = ImageUsage
+        {
+            color_attachment: true,
+            transfer_source: true,
+            .. ImageUsage::none()
+        };
+
+        let image = AttachmentImage::with_usage(device.clone(), dimensions, OFFSCREEN_FORMAT, image_usage)?;
+
+        let render_pass = create_render_pass(device.clone(), OFFSCREEN_FORMAT)?;
+
+        VulkanoInstance::from_parts(device, graphics_queue, render_pass, RenderTarget::Offscreen { image }, dimensions, jobs)
+    }
+
+    fn from_parts(device : Arc<Device>, graphics_queue : Arc<Queue>, render_pass : Arc<RenderPassAbstract + Send + Sync>, render_target : RenderTarget, dimensions : [u32; 2], jobs : Arc<JobSystem>) -> Result<VulkanoInstance, EngineError>
+    {
+        //what the drawers sample when nothing is textured
+        let (white_texture, white_texture_upload) = Texture::from_pixels(graphics_queue.clone(), [1, 1], &[255, 255, 255, 255], false)?;
+
+        let previous_frame_end_future = Box::new(now(device.clone()).join(white_texture_upload)) as Box<GpuFuture>;
+
+        let depth_buffer = AttachmentImage::transient(device.clone(), dimensions, DEPTH_FORMAT)?;
+
+        //the built in drawers' shader sources, edits to them show up without restarting
+        let mut shaders = ShaderLibrary::new(device.clone(), shader_library::directory_from_env());
+        let pipelines = Arc::new(PipelineManager::new(device.clone(), Some(pipeline_manager::cache_path_from_env()))?);
+
+        let mut drawers = DrawerRegistry::new();
+        drawers.add_built_in(MESH_DRAWER_NAME, MESH_DRAWER_ORDER, MeshDrawer::new(device.clone(), white_texture.clone(), &mut shaders, pipelines.clone())?);
+        drawers.add_built_in(TRIANGLE_DRAWER_NAME, TRIANGLE_DRAWER_ORDER, TriangleDrawer::new(device.clone(), white_texture, &mut shaders, pipelines.clone())?);
+
+        let texture_cache = TextureCache::new(graphics_queue.clone());
+
+        let frames = FrameRing::new(device.clone(), DEFAULT_FRAMES_IN_FLIGHT)?;
+
+        Ok(VulkanoInstance{
+            device,
+            previous_frame_end_future : Some(previous_frame_end_future),
+            render_target,
             render_pass,
+            depth_buffer,
+            framebuffers : Vec::new(),
+            frames,
+            start_time : Instant::now(),
+            last_frame_time : Instant::now(),
+            frame_number : 0,
             graphics_queue,
             dimensions,
+            camera : Camera::new(dimensions),
             image_index : 0usize,
-            acquire_future : None,
             should_recreate_swapchain : false,
-            triangle_drawer,
+            drawers,
+            jobs,
+            texture_cache,
+            shaders,
+            pipelines,
             command_buffer_builder : None,
-       }
+            capture_requested : false,
+            captured_frame : None,
+       })
+    }
+
+    pub fn is_headless(&self) -> bool
+    {
+        match self.render_target
+        {
+            RenderTarget::Offscreen { .. } => true,
+            RenderTarget::Window { .. } => false
+        }
+    }
+
+    fn target_format(&self) -> Format
+    {
+        match self.render_target
+        {
+            RenderTarget::Window { ref swapchain, .. } => swapchain.format(),
+            RenderTarget::Offscreen { .. } => OFFSCREEN_FORMAT
+        }
+    }
+
+    //the render pass drawing into every image of the target, in swapchain image order
+    fn create_framebuffers(&self) -> Result<Vec<Arc<FramebufferAbstract + Send + Sync>>, EngineError>
+    {
+        let render_pass = self.render_pass.clone();
+        let depth_buffer = self.depth_buffer.clone();
+
+        match self.render_target
+        {
+            RenderTarget::Window { ref images, .. } => images.iter().map(|image| -> Result<Arc<FramebufferAbstract + Send + Sync>, EngineError>
+            {
+                Ok(Arc::new(Framebuffer::start(render_pass.clone()).add(image.clone())?.add(depth_buffer.clone())?.build()?) as Arc<FramebufferAbstract + Send + Sync>)
+            }).collect(),
+            RenderTarget::Offscreen { ref image } =>
+            {
+                Ok(vec![Arc::new(Framebuffer::start(render_pass.clone()).add(image.clone())?.add(depth_buffer.clone())?.build()?) as Arc<FramebufferAbstract + Send + Sync>])
+            }
+        }
     }
+
+    //uploads run before the next frame that gets submitted
+    fn add_upload_future(&mut self, upload : Box<GpuFuture>) -> Result<(), EngineError>
+    {
+        let previous = self.previous_frame_end_future.take().ok_or(EngineError::MissingFrameFuture)?;
+        self.previous_frame_end_future = Some(Box::new(previous.join(upload)) as Box<GpuFuture>);
+        Ok(())
+    }
+
+    //looked up by name so drawers of the same type added by users are left alone
+    //the registry refuses to remove built in drawers or add another under their names, so they are always there
+    fn triangle_drawer(&mut self) -> &mut TriangleDrawer
+    {
+        self.drawers.by_name_as_mut::<TriangleDrawer>(TRIANGLE_DRAWER_NAME).expect("The built in triangle drawer went missing!")
+    }
+
+    fn mesh_drawer(&mut self) -> &mut MeshDrawer
+    {
+        self.drawers.by_name_as_mut::<MeshDrawer>(MESH_DRAWER_NAME).expect("The built in mesh drawer went missing!")
+    }
+
+    //time since the renderer was created and since the previous frame
+    fn next_frame_uniforms(&mut self) -> FrameUniforms
+    {
+        let now = Instant::now();
+        let time = now - self.start_time;
+        let delta_time = now - self.last_frame_time;
+
+        self.last_frame_time = now;
+        self.frame_number = self.frame_number.wrapping_add(1);
+
+        FrameUniforms::new(seconds(time), seconds(delta_time), self.frame_number)
+    }
+
+    //every enabled drawer records into its own secondary command buffer as a job, returned in the order they are drawn
+    fn record_drawers(&mut self) -> Result<Vec<AutoCommandBuffer>, EngineError>
+    {
+        let frame_uniforms = self.next_frame_uniforms();
+        let view_uniforms = ViewUniforms::from_camera(&self.camera);
+        let shared_uniforms = SharedUniforms::upload(self.frames.current(), frame_uniforms, view_uniforms)?;
+        self.frames.current_mut().set_shared_uniforms(shared_uniforms);
+
+        let mut drawers = self.drawers.prepare_frame();
+        let mut command_buffers : Vec<Option<Result<AutoCommandBuffer, EngineError>>> = drawers.iter().map(|_| None).collect();
+
+        {
+            let camera = &self.camera;
+            let device = &self.device;
+            let graphics_queue = &self.graphics_queue;
+            let render_pass = &self.render_pass;
+            let frame = self.frames.current();
+
+            self.jobs.scope(|scope|
+            {
+                for (drawer, command_buffer) in drawers.iter_mut().zip(command_buffers.iter_mut())
+                {
+                    scope.spawn(move || *command_buffer = Some(record_drawer(&mut **drawer, camera, frame, device.clone(), graphics_queue, render_pass.clone())));
+                }
+            });
+        }
+
+        command_buffers.into_iter().map(|command_buffer| command_buffer.unwrap()).collect()
+    }
+}
+
+fn seconds(duration : Duration) -> f32
+{
+    duration.as_secs() as f32 + duration.subsec_nanos() as f32 * 1e-9
+}
+
+//command pools belong to the thread that created them, so the command buffer is built on the worker that recorded it
+fn record_drawer(drawer : &mut Drawer, camera : &Camera, frame : &FrameContext, device : Arc<Device>, queue : &Queue, render_pass : Arc<RenderPassAbstract + Send + Sync>) -> Result<AutoCommandBuffer, EngineError>
+{
+    let subpass = Subpass::from(render_pass.clone(), 0).ok_or(EngineError::NoSubpass)?;
+    let command_buffer_builder = AutoCommandBufferBuilder::secondary_graphics_one_time_submit(device, queue.family(), subpass)?;
+
+    drawer.render(camera, frame, command_buffer_builder, render_pass)?.build().map_err(EngineError::command_buffer)
+}
+
+//submits the frame behind a fence, blocking until the gpu is done with it when we need its results right away
+fn finish_frame_future(future : Box<GpuFuture>, wait : bool) -> Result<FrameFence, EngineError>
+{
+    let fence = Arc::new(future.then_signal_fence_and_flush()?);
+
+    if wait
+    {
+        fence.wait(None)?;
+    }
+
+    Ok(fence)
+}
+
+//the best device that can draw with a queue passing usable_queue, or the one picked through MOTOR_DEVICE
+//returned with the queue family that passed, the graphics queue goes there
+fn choose_physical_device<'a, F>(instance : &'a Arc<Instance>, requirements : &DeviceRequirements, usable_queue : F) -> Result<(PhysicalDevice<'a>, QueueFamily<'a>), EngineError>
+    where F : Fn(QueueFamily) -> bool
+{
+    let candidates : Vec<DeviceDescription> = PhysicalDevice::enumerate(instance).map(|physical|
+    {
+        DeviceDescription::from_physical(physical, physical.queue_families().find(|&q| usable_queue(q)).map(|q| q.id()))
+    }).collect();
+
+    let preference = device_selection::preference_from_env();
+    let selection = device_selection::select_device(&candidates, requirements, preference.as_ref().map(|preference| preference.as_str()));
+    selection.log();
+
+    let chosen = selection.chosen.ok_or(EngineError::NoPhysicalDevice)?;
+    let physical = PhysicalDevice::from_index(instance, chosen.index).ok_or(EngineError::NoPhysicalDevice)?;
+    let queue_family = physical.queue_family_by_id(chosen.queue_family).ok_or(EngineError::NoGraphicsQueue)?;
+    println!("Using device: {}, (type: {:?})", physical.name(), physical.ty());
+
+    Ok((physical, queue_family))
+}
+
+//the first present mode in the config the surface supports, old_swapchain is handed over to the new one
+fn create_swapchain(device : &Arc<Device>, surface : &Arc<Surface<Window>>, queue : &Arc<Queue>, format : Format, dimensions : [u32; 2], present_modes : &PresentModeConfig, old_swapchain : Option<&Arc<Swapchain<Window>>>)
+    -> Result<(Arc<Swapchain<Window>>, Vec<Arc<SwapchainImage<Window>>>), EngineError>
+{
+    let surface_capabilities = surface.capabilities(device.physical_device())?;
+
+    let alpha_mode = surface_capabilities.supported_composite_alpha.iter().next().ok_or(EngineError::UnsupportedSurface)?;
+
+    let present_mode = present_modes.choose(&surface_capabilities.present_modes);
+    println!("Presenting with {:?}", present_mode);
+
+    let swapchain = Swapchain::new(
+        device.clone(), 
+        surface.clone(), 
+        surface_capabilities.min_image_count, 
+        format, 
+        dimensions, 
+        1, 
+        surface_capabilities.supported_usage_flags,
+        queue, 
+        SurfaceTransform::Identity, 
+        alpha_mode, 
+        present_mode, 
+        true, 
+        old_swapchain)?;
+
+    Ok(swapchain)
+}
+
+fn create_render_pass(device : Arc<Device>, format : Format) -> Result<Arc<RenderPassAbstract + Send + Sync>, EngineError>
+{
+    Ok(Arc::new(single_pass_renderpass!(device,
+        attachments: {
+            color: {
+                load: Clear,
+                store: Store,
+                format: format,
+                samples: 1,
+            },
+            depth: {
+                load: Clear,
+                store: DontCare,
+                format: DEPTH_FORMAT,
+                samples: 1,
+            }
+        },
+        pass: {
+            color: [color],
+            depth_stencil: {depth}
+        }
+    )?))
 }
 
 pub trait PipelineImplementer
 {
-    fn recreate_swapchain(&mut self);
+    fn recreate_swapchain(&mut self) -> Result<(), EngineError>;
 
-    fn begin_render(&mut self) -> Result<(), RenderError>;
+    //EngineError::SwapchainOutOfDate means the frame should be skipped, anything else is fatal
+    fn begin_render(&mut self) -> Result<(), EngineError>;
 
-    fn end_render(&mut self);
+    fn end_render(&mut self) -> Result<(), EngineError>;
 
     fn draw_triangle(&mut self, points : [[f32; 2]; 3]);
+
+    fn draw_triangle_colored(&mut self, points : [[f32; 2]; 3], color : [f32; 4]);
+
+    fn draw_triangle_gradient(&mut self, points : [[f32; 2]; 3], colors : [[f32; 4]; 3]);
+
+    fn draw_triangle_textured(&mut self, points : [[f32; 2]; 3], uvs : [[f32; 2]; 3], texture : Arc<Texture>);
+
+    fn draw_sprite(&mut self, min : [f32; 2], max : [f32; 2], texture : Arc<Texture>);
+
+    //png, tga or bmp, loading the same file twice returns the cached texture
+    fn load_texture(&mut self, path : &Path, mipmaps : bool) -> Result<Arc<Texture>, EngineError>;
+
+    //pixels are tightly packed RGBA8 rows, top row first
+    fn create_texture(&mut self, dimensions : [u32; 2], pixels : &[u8], mipmaps : bool) -> Result<Arc<Texture>, EngineError>;
+
+    fn create_mesh(&mut self, vertices : &[MeshVertex], indices : &[u32]) -> Result<Arc<Mesh>, EngineError>;
+
+    fn draw_mesh(&mut self, mesh : Arc<Mesh>, model : Mat4);
+
+    fn draw_mesh_textured(&mut self, mesh : Arc<Mesh>, model : Mat4, texture : Arc<Texture>);
+
+    //tint is an sRGB color the mesh gets multiplied with
+    fn draw_mesh_tinted(&mut self, mesh : Arc<Mesh>, model : Mat4, tint : [f32; 4]);
+
+    //looks through the scene's camera and hands every component to the drawer that renders it
+    fn draw_scene(&mut self, scene : &mut Scene);
+
+    fn camera(&mut self) -> &mut Camera;
+
+    //what drawers are created with
+    fn device(&self) -> Arc<Device>;
+
+    //takes effect when the swapchain is recreated before the next frame, does nothing headless
+    fn set_present_modes(&mut self, present_modes : PresentModeConfig);
+
+    //what the swapchain actually presents with, None when headless
+    fn present_mode(&self) -> Option<PresentMode>;
+
+    //how many frames the cpu may record ahead of the gpu
+    fn frames_in_flight(&self) -> usize;
+
+    //waits for the gpu to finish every frame in flight before changing the count
+    //fails for 0 and between begin_render and end_render, the frame being recorded belongs to the current ring
+    fn set_frames_in_flight(&mut self, frames_in_flight : usize) -> Result<(), EngineError>;
+
+    //add your own drawers here, or turn off the built in ones
+    fn drawers(&mut self) -> &mut DrawerRegistry;
+
+    //where drawers load their shaders from, so they get hot reloaded too
+    fn shaders(&mut self) -> &mut ShaderLibrary;
+
+    //build your drawers' pipelines through it to share them with the built in drawers and the on disk cache
+    fn pipelines(&self) -> Arc<PipelineManager>;
+
+    //copies the next frame finished by end_render back to the cpu
+    fn capture_next_frame(&mut self);
+
+    fn take_captured_frame(&mut self) -> Option<CapturedFrame>;
 }
 
 impl PipelineImplementer for VulkanoInstance
 {
-    fn recreate_swapchain(&mut self)
+    fn recreate_swapchain(&mut self) -> Result<(), EngineError>
     {
+        //the offscreen image never changes size
+        let (surface, swapchain, images, present_modes) = match self.render_target
+        {
+            RenderTarget::Window { ref surface, ref mut swapchain, ref mut images, ref present_modes, .. } => (surface, swapchain, images, present_modes),
+            RenderTarget::Offscreen { .. } =>
+            {
+                self.should_recreate_swapchain = false;
+                return Ok(());
+            }
+        };
+
         self.dimensions = 
         {
-            let (width, height) = self.window.window().get_inner_size().expect("Could not get window inner size!");
+            let (width, height) = surface.window().get_inner_size().ok_or(EngineError::WindowClosed)?;
             [width, height]
         };
 
         if self.dimensions[0] <= 0 || self.dimensions[1] <= 0
         {
-            return;
+            return Ok(());
         }
 
-        let (new_swapchain, new_images) = match self.swapchain.recreate_with_dimension(self.dimensions) 
+        //a full recreation rather than recreate_with_dimension, the present mode may have changed
+        let (new_swapchain, new_images) = match create_swapchain(&self.device, surface, &self.graphics_queue, swapchain.format(), self.dimensions, present_modes, Some(&*swapchain))
         {
             Ok(r) => r,
-            Err(SwapchainCreationError::UnsupportedDimensions) => {
-                return;
+            Err(EngineError::Swapchain(SwapchainCreationError::UnsupportedDimensions)) => {
+                return Ok(());
             }
-            Err(err) => panic!("{:?}", err)
+            Err(err) => return Err(err)
         };
 
-        mem::replace(&mut self.swapchain, new_swapchain);
-        mem::replace(&mut self.images, new_images);
+        mem::replace(swapchain, new_swapchain);
+        mem::replace(images, new_images);
+
+        //frames still in flight may be drawing into the old ones
+        let old_framebuffers = mem::replace(&mut self.framebuffers, Vec::new());
+        let old_depth_buffer = mem::replace(&mut self.depth_buffer, AttachmentImage::transient(self.device.clone(), self.dimensions, DEPTH_FORMAT)?);
+        self.frames.current().destroy_later((old_framebuffers, old_depth_buffer));
+        self.camera.set_viewport_dimensions(self.dimensions);
 
         self.should_recreate_swapchain = false;
+
+        Ok(())
     }
 
-    fn begin_render(&mut self) -> Result<(), RenderError>
+    fn begin_render(&mut self) -> Result<(), EngineError>
     {
-        let mut framebuffers : Option<Vec<Arc<Framebuffer<_,_>>>> = None;
+        self.previous_frame_end_future.as_mut().ok_or(EngineError::MissingFrameFuture)?.cleanup_finished();
+        self.frames.cleanup_finished();
+
+        //pipelines using a reloaded shader rebuild themselves the next time they draw
+        if !self.shaders.poll().is_empty()
+        {
+            self.pipelines.remove_stale();
+        }
 
-        self.previous_frame_end_future.as_mut().unwrap().cleanup_finished();
+        //blocks while the gpu is still busy with the frame that last used this one's resources
+        self.frames.begin_frame()?;
 
         if self.should_recreate_swapchain
         {
-            self.recreate_swapchain();
+            self.recreate_swapchain()?;
         }
 
-        let (image_index, acquire_future) = match swapchain::acquire_next_image(self.swapchain.clone(), None)
+        //clear values skip the shaders, so an sRGB target needs the color in linear for it to look the same
+        let clear_color = if color::is_srgb_format(self.target_format()) { color::srgb_color_to_linear(CLEAR_COLOR) } else { CLEAR_COLOR };
+
+        //built once per image, recreate_swapchain throws them away
+        if self.framebuffers.is_empty()
         {
-            Ok(r) => r,
-            Err(AcquireError::OutOfDate) => 
-            {
-                self.should_recreate_swapchain = true;
-                return Err(RenderError::SwapchainOutOfDate);
-            },
-            Err(err) => panic!("{:?}", err)
-        };
+            self.framebuffers = self.create_framebuffers()?;
+        }
 
-        if framebuffers.is_none()
+        self.image_index = match self.render_target
         {
-            let new_framebuffers = Some(self.images.iter().map(|image| 
+            RenderTarget::Window { ref swapchain, ref mut acquire_future, .. } =>
             {
-                Arc::new(Framebuffer::start(self.render_pass.clone()).add(image.clone()).unwrap().build().unwrap())
-            }).collect::<Vec<_>>());
-
-            mem::replace(&mut framebuffers, new_framebuffers);
-        }
+                let (image_index, new_acquire_future) = match swapchain::acquire_next_image(swapchain.clone(), None)
+                {
+                    Ok(r) => r,
+                    Err(AcquireError::OutOfDate) =>
+                    {
+                        self.should_recreate_swapchain = true;
+                        return Err(EngineError::SwapchainOutOfDate);
+                    },
+                    Err(err) => return Err(err.into())
+                };
+
+                *acquire_future = Some(new_acquire_future);
+
+                image_index
+            },
+            RenderTarget::Offscreen { .. } => 0
+        };
 
-        self.acquire_future = Some(acquire_future);
-        self.image_index = image_index;
+        let framebuffer = self.framebuffers[self.image_index].clone();
 
-        self.command_buffer_builder = Some(AutoCommandBufferBuilder::primary_one_time_submit(self.device.clone(), self.graphics_queue.family()).unwrap()
-            .begin_render_pass(framebuffers.as_ref().unwrap()[image_index].clone(), false, 
-            vec![[100f32 / 255f32, 149f32 / 255f32, 237f32 / 255f32, 1.0].into()]).unwrap());
+        self.command_buffer_builder = Some(AutoCommandBufferBuilder::primary_one_time_submit(self.device.clone(), self.graphics_queue.family())?
+            .begin_render_pass(framebuffer, true,
+            vec![clear_color.into(), 1f32.into()]).map_err(EngineError::command_buffer)?);
 
         Ok(())
     }
 
-    fn end_render(&mut self)
+    fn end_render(&mut self) -> Result<(), EngineError>
     {
-        self.command_buffer_builder = Some(self.triangle_drawer.render(self.dimensions, self.command_buffer_builder.take().unwrap(), self.render_pass.clone()));
+        let mut command_buffer_builder = self.command_buffer_builder.take().ok_or(EngineError::NoFrameStarted)?;
+
+        let secondary_command_buffers = self.record_drawers()?;
+
+        //the render pass was begun for secondary command buffers, so everything drawn has to go through them
+        for secondary_command_buffer in secondary_command_buffers
+        {
+            command_buffer_builder = command_buffer_builder.execute_commands(secondary_command_buffer).map_err(EngineError::command_buffer)?;
+        }
 
-        let mut command_buffer_builder = self.command_buffer_builder.take();
-        command_buffer_builder = Some(command_buffer_builder.unwrap().end_render_pass().unwrap());
+        command_buffer_builder = command_buffer_builder.end_render_pass().map_err(EngineError::command_buffer)?;
 
-        let command_buffer = command_buffer_builder.unwrap().build().unwrap();
+        let capture_buffer = if self.capture_requested
+        {
+            self.capture_requested = false;
 
-        let acquire = self.acquire_future.take();
+            let pixel_count = (self.dimensions[0] * self.dimensions[1] * 4) as usize;
+            let buffer = CpuAccessibleBuffer::from_iter(self.device.clone(), BufferUsage::all(), (0 .. pixel_count).map(|_| 0u8))?;
+
+            command_buffer_builder = match self.render_target
+            {
+                RenderTarget::Window { ref images, .. } => command_buffer_builder.copy_image_to_buffer(images[self.image_index].clone(), buffer.clone()),
+                RenderTarget::Offscreen { ref image } => command_buffer_builder.copy_image_to_buffer(image.clone(), buffer.clone())
+            }.map_err(EngineError::command_buffer)?;
+
+            Some(buffer)
+        }
+        else
+        {
+            None
+        };
 
-        let future  = self.previous_frame_end_future.take().unwrap().join(acquire.unwrap());
-        let future  = future.then_execute(self.graphics_queue.clone(), command_buffer).unwrap();
-        let future  = future.then_swapchain_present(self.graphics_queue.clone(), self.swapchain.clone(), self.image_index);
-        let future  = future.then_signal_fence_and_flush();
+        let command_buffer = command_buffer_builder.build().map_err(EngineError::command_buffer)?;
+
+        let graphics_queue = self.graphics_queue.clone();
+        let image_index = self.image_index;
+        let wait = capture_buffer.is_some();
+
+        //nothing may return early between taking the previous future and putting a new one back
+        let previous = self.previous_frame_end_future.take().ok_or(EngineError::MissingFrameFuture)?;
+
+        let future : Result<FrameFence, EngineError> = match self.render_target
+        {
+            RenderTarget::Window { ref swapchain, ref mut acquire_future, .. } =>
+            {
+                let future  = previous.join(acquire_future.take().unwrap());
+                future.then_execute(graphics_queue.clone(), command_buffer).map_err(EngineError::from).and_then(|future|
+                {
+                    let future  = future.then_swapchain_present(graphics_queue, swapchain.clone(), image_index);
+                    finish_frame_future(Box::new(future), wait)
+                })
+            },
+            RenderTarget::Offscreen { .. } =>
+            {
+                previous.then_execute(graphics_queue, command_buffer).map_err(EngineError::from).and_then(|future|
+                {
+                    finish_frame_future(Box::new(future), wait)
+                })
+            }
+        };
 
         match future {
-            Ok(future) => {
-                self.previous_frame_end_future = Some(Box::new(future) as Box<_>);
+            Ok(fence) => {
+                //the next frame is submitted after this one, and reusing this frame's resources waits for it
+                self.frames.set_fence(fence.clone());
+                self.previous_frame_end_future = Some(Box::new(fence) as Box<_>);
+
+                if let Some(buffer) = capture_buffer
+                {
+                    let raw = buffer.read()?;
+                    self.captured_frame = Some(CapturedFrame::from_raw(self.dimensions, self.target_format(), &raw));
+                }
+
+                Ok(())
             }
-            Err(FlushError::OutOfDate) => {
+            Err(EngineError::SwapchainOutOfDate) => {
                 self.previous_frame_end_future = Some(Box::new(now(self.device.clone())) as Box<_>);
+                self.should_recreate_swapchain = true;
+                //the frame was never presented, so the capture goes to the next one instead
+                if capture_buffer.is_some()
+                {
+                    self.capture_requested = true;
+                }
+                Ok(())
             }
-            Err(e) => {
-                println!("{:?}", e);
+            Err(err) => {
                 self.previous_frame_end_future = Some(Box::new(now(self.device.clone())) as Box<_>);
+                Err(err)
             }
         }
     }
 
     fn draw_triangle(&mut self, points : [[f32; 2]; 3])
     {
-        self.triangle_drawer.draw_triangle(points);
+        self.triangle_drawer().draw_triangle(points);
+    }
+
+    fn draw_triangle_colored(&mut self, points : [[f32; 2]; 3], color : [f32; 4])
+    {
+        self.triangle_drawer().draw_triangle_colored(points, color);
+    }
+
+    fn draw_triangle_gradient(&mut self, points : [[f32; 2]; 3], colors : [[f32; 4]; 3])
+    {
+        self.triangle_drawer().draw_triangle_gradient(points, colors);
+    }
+
+    fn draw_triangle_textured(&mut self, points : [[f32; 2]; 3], uvs : [[f32; 2]; 3], texture : Arc<Texture>)
+    {
+        self.triangle_drawer().draw_triangle_textured(points, uvs, texture);
+    }
+
+    fn draw_sprite(&mut self, min : [f32; 2], max : [f32; 2], texture : Arc<Texture>)
+    {
+        self.triangle_drawer().draw_sprite(min, max, texture);
+    }
+
+    fn load_texture(&mut self, path : &Path, mipmaps : bool) -> Result<Arc<Texture>, EngineError>
+    {
+        let (texture, upload) = self.texture_cache.load(path, mipmaps)?;
+
+        if let Some(upload) = upload
+        {
+            self.add_upload_future(upload)?;
+        }
+
+        Ok(texture)
+    }
+
+    fn create_texture(&mut self, dimensions : [u32; 2], pixels : &[u8], mipmaps : bool) -> Result<Arc<Texture>, EngineError>
+    {
+        let (texture, upload) = Texture::from_pixels(self.graphics_queue.clone(), dimensions, pixels, mipmaps)?;
+        self.add_upload_future(upload)?;
+        Ok(texture)
+    }
+
+    fn create_mesh(&mut self, vertices : &[MeshVertex], indices : &[u32]) -> Result<Arc<Mesh>, EngineError>
+    {
+        self.mesh_drawer().create_mesh(vertices, indices)
+    }
+
+    fn draw_mesh(&mut self, mesh : Arc<Mesh>, model : Mat4)
+    {
+        self.mesh_drawer().draw_mesh(mesh, model);
+    }
+
+    fn draw_mesh_textured(&mut self, mesh : Arc<Mesh>, model : Mat4, texture : Arc<Texture>)
+    {
+        self.mesh_drawer().draw_mesh_textured(mesh, model, texture);
+    }
+
+    fn draw_mesh_tinted(&mut self, mesh : Arc<Mesh>, model : Mat4, tint : [f32; 4])
+    {
+        self.mesh_drawer().draw_mesh_tinted(mesh, model, tint);
+    }
+
+    fn draw_scene(&mut self, scene : &mut Scene)
+    {
+        scene.update_world_matrices();
+
+        if let Some(node) = scene.camera_node().and_then(|id| scene.node(id))
+        {
+            if let Some(ref camera) = node.camera
+            {
+                camera.apply(&mut self.camera, node.world_matrix());
+            }
+        }
+
+        //the mesh drawer has a single light, the first one found wins
+        let mut light_set = false;
+
+        for (_, node) in scene.nodes()
+        {
+            if let Some(ref mesh) = node.mesh
+            {
+                match mesh.texture
+                {
+                    Some(ref texture) => self.mesh_drawer().draw_mesh_textured_tinted(mesh.mesh.clone(), node.world_matrix(), texture.clone(), mesh.tint),
+                    None => self.mesh_drawer().draw_mesh_tinted(mesh.mesh.clone(), node.world_matrix(), mesh.tint)
+                }
+            }
+
+            if let Some(ref light) = node.light
+            {
+                if !light_set
+                {
+                    let direction = node.world_matrix().transform_vector(-Vec3::unit_z());
+                    let color = [light.color[0] * light.intensity, light.color[1] * light.intensity, light.color[2] * light.intensity];
+                    self.mesh_drawer().set_directional_light(direction, color);
+                    light_set = true;
+                }
+            }
+        }
+    }
+
+    fn camera(&mut self) -> &mut Camera
+    {
+        &mut self.camera
+    }
+
+    fn device(&self) -> Arc<Device>
+    {
+        self.device.clone()
+    }
+
+    fn drawers(&mut self) -> &mut DrawerRegistry
+    {
+        &mut self.drawers
+    }
+
+    fn shaders(&mut self) -> &mut ShaderLibrary
+    {
+        &mut self.shaders
+    }
+
+    fn pipelines(&self) -> Arc<PipelineManager>
+    {
+        self.pipelines.clone()
+    }
+
+    fn frames_in_flight(&self) -> usize
+    {
+        self.frames.frames_in_flight()
+    }
+
+    fn set_frames_in_flight(&mut self, frames_in_flight : usize) -> Result<(), EngineError>
+    {
+        if self.command_buffer_builder.is_some()
+        {
+            return Err(EngineError::FrameInProgress);
+        }
+
+        let frames = FrameRing::new(self.device.clone(), frames_in_flight)?;
+        self.frames.wait_idle()?;
+        self.frames = frames;
+
+        Ok(())
+    }
+
+    fn set_present_modes(&mut self, new_present_modes : PresentModeConfig)
+    {
+        if let RenderTarget::Window { ref mut present_modes, .. } = self.render_target
+        {
+            *present_modes = new_present_modes;
+            self.should_recreate_swapchain = true;
+        }
+    }
+
+    fn present_mode(&self) -> Option<PresentMode>
+    {
+        match self.render_target
+        {
+            RenderTarget::Window { ref swapchain, .. } => Some(swapchain.present_mode()),
+            RenderTarget::Offscreen { .. } => None
+        }
+    }
+
+    fn capture_next_frame(&mut self)
+    {
+        self.capture_requested = true;
+    }
+
+    fn take_captured_frame(&mut self) -> Option<CapturedFrame>
+    {
+        self.captured_frame.take()
     }
 }